@@ -0,0 +1,107 @@
+use std::fs::File;
+use std::io::{prelude::*, BufReader};
+
+use aes::{Aes128, Mode};
+use cryptanalysis::CribDragSession;
+use encoding::Decode;
+
+static INPUT_FILE_PATH: &str = "./challenges/set03/challenge19/data/19.txt";
+static NONCE: [u8; 8] = [0; 8];
+
+static HELP: &str = "Commands:
+  show                            print the plaintexts recovered so far
+  drag <crib>                     slide a guessed word across every pair of messages
+  commit <index> <offset> <text>  accept a guess for a message
+  undo                            revert the last accepted guess
+  help                            print this message
+  quit                            exit";
+
+fn main() {
+    let f = File::open(INPUT_FILE_PATH).unwrap();
+    let reader = BufReader::new(f);
+
+    let mut key = [0; 16];
+    File::open("/dev/urandom")
+        .unwrap()
+        .read_exact(&mut key)
+        .unwrap();
+    let cipher = Aes128::new(key.to_vec(), Mode::CTR, Some(NONCE.to_vec())).unwrap();
+
+    let mut ciphertexts = vec![];
+    for line in reader.lines() {
+        let plaintext = Vec::from_base64(line.unwrap().trim()).unwrap();
+        ciphertexts.push(cipher.encrypt(&plaintext).unwrap());
+    }
+
+    let mut session = CribDragSession::new(ciphertexts, "EN");
+
+    println!("{}\n", HELP);
+    print_plaintexts(&session);
+
+    let stdin = std::io::stdin();
+    loop {
+        print!("> ");
+        std::io::stdout().flush().unwrap();
+
+        let mut line = String::new();
+        if stdin.read_line(&mut line).unwrap() == 0 {
+            break;
+        }
+        let line = line.trim_end_matches(['\r', '\n']);
+        let (command, args) = line.split_once(' ').unwrap_or((line, ""));
+
+        match command {
+            "show" => print_plaintexts(&session),
+            "drag" => {
+                for m in session.drag(args.as_bytes()).iter().take(15) {
+                    println!(
+                        "[{:>2} -> {:>2}] offset {:>2}  score {:>2}  {:?}",
+                        m.index,
+                        m.other,
+                        m.offset,
+                        m.score,
+                        String::from_utf8_lossy(&m.fragment)
+                    );
+                }
+            }
+            "commit" => {
+                let mut args = args.splitn(3, ' ');
+                let index = args.next().and_then(|a| a.parse().ok());
+                let offset = args.next().and_then(|a| a.parse().ok());
+                let text = args.next();
+
+                match (index, offset, text) {
+                    (Some(index), Some(offset), Some(text)) => {
+                        match session.commit(index, offset, text.as_bytes()) {
+                            Ok(()) => print_plaintexts(&session),
+                            Err(e) => println!("{}", e),
+                        }
+                    }
+                    _ => println!("usage: commit <index> <offset> <text>"),
+                }
+            }
+            "undo" => match session.undo() {
+                Ok(()) => print_plaintexts(&session),
+                Err(e) => println!("{}", e),
+            },
+            "help" => println!("{}", HELP),
+            "quit" | "exit" => break,
+            "" => {}
+            _ => println!("unknown command: {}", command),
+        }
+    }
+}
+
+fn print_plaintexts(session: &CribDragSession) {
+    for (i, plaintext) in session.plaintexts().iter().enumerate() {
+        let text: String = plaintext
+            .iter()
+            .map(|u| match u {
+                Some(u) if u.is_ascii_graphic() || *u == b' ' => *u as char,
+                Some(_) => '?',
+                None => '_',
+            })
+            .collect();
+        println!("{:>2}: {}", i, text);
+    }
+}
@@ -0,0 +1,188 @@
+use std::fs::File;
+use std::io::{prelude::*, BufReader};
+use std::net::{TcpListener, TcpStream};
+use std::thread;
+use std::time::Duration;
+
+use cryptanalysis::{recover_mac_by_timing, Estimator};
+use encoding::{Decode, Encode};
+use primitives::{Hmac, Mac, Sha1};
+
+static ADDRESS: &str = "127.0.0.1";
+static FILE: &str = "foo";
+static MAC_SIZE: usize = 20;
+
+static USAGE: &str = "Usage: challenge31 [--serve] [--port <port>] [--delay <ms>] [--samples <n>]
+  --serve        only run the server
+  --port <port>  the localhost port of the server (default 9000)
+  --delay <ms>   the sleep after each matching byte of the signature (default 50)
+  --samples <n>  the number of timing measurements per candidate byte (default 3)";
+
+struct Options {
+    serve: bool,
+    port: u16,
+    delay: Duration,
+    samples: usize,
+}
+
+fn main() {
+    let options = match parse_options() {
+        Some(options) => options,
+        None => {
+            println!("{}", USAGE);
+            return;
+        }
+    };
+
+    let mut key = [0; 16];
+    File::open("/dev/urandom")
+        .unwrap()
+        .read_exact(&mut key)
+        .unwrap();
+
+    let listener = TcpListener::bind((ADDRESS, options.port)).unwrap();
+    println!("Listening on http://{}:{}", ADDRESS, options.port);
+
+    if options.serve {
+        serve(listener, &key, options.delay);
+        return;
+    }
+
+    let delay = options.delay;
+    thread::spawn(move || serve(listener, &key, delay));
+
+    let oracle = |signature: &[u8]| query(options.port, FILE, signature);
+    let progress = |known: &[u8]| println!("{}", known.to_vec().to_hex());
+    let signature = recover_mac_by_timing(
+        oracle,
+        MAC_SIZE,
+        options.samples,
+        Estimator::Median,
+        progress,
+    );
+
+    match signature {
+        Some(signature) => println!("Valid signature for {:?}: {}", FILE, signature.to_hex()),
+        None => println!("The attack failed, try again with more samples"),
+    }
+}
+
+fn parse_options() -> Option<Options> {
+    let mut options = Options {
+        serve: false,
+        port: 9000,
+        delay: Duration::from_millis(50),
+        samples: 3,
+    };
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--serve" => options.serve = true,
+            "--port" => options.port = args.next()?.parse().ok()?,
+            "--delay" => options.delay = Duration::from_millis(args.next()?.parse().ok()?),
+            "--samples" => options.samples = args.next()?.parse().ok().filter(|&n| n > 0)?,
+            _ => return None,
+        }
+    }
+
+    Some(options)
+}
+
+/// Answers `GET /test?file=...&signature=...` requests one at a time with a 200 if the
+/// signature is the HMAC-SHA1 of the file name and a 500 otherwise
+fn serve(listener: TcpListener, key: &[u8], delay: Duration) {
+    for stream in listener.incoming() {
+        let mut stream = match stream {
+            Ok(stream) => stream,
+            Err(_) => continue,
+        };
+
+        let mut reader = BufReader::new(&stream);
+        let mut request_line = String::new();
+        if reader.read_line(&mut request_line).is_err() {
+            continue;
+        }
+        // skip the headers
+        let mut header = String::new();
+        while reader.read_line(&mut header).is_ok() && header.trim_end() != "" {
+            header.clear();
+        }
+
+        let status = match parse_request(&request_line) {
+            Some((file, signature)) => {
+                let mac = Hmac::<Sha1>::mac(key, file.as_bytes());
+                if insecure_compare(&mac, &signature, delay) {
+                    "200 OK"
+                } else {
+                    "500 Internal Server Error"
+                }
+            }
+            None => "404 Not Found",
+        };
+
+        let response = format!(
+            "HTTP/1.1 {}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+            status
+        );
+        let _ = stream.write_all(response.as_bytes());
+    }
+}
+
+/// Extracts the file name and the signature from a request line
+fn parse_request(request_line: &str) -> Option<(String, Vec<u8>)> {
+    let mut parts = request_line.split_whitespace();
+    if parts.next()? != "GET" {
+        return None;
+    }
+
+    let (path, query) = parts.next()?.split_once('?')?;
+    if path != "/test" {
+        return None;
+    }
+
+    let mut file = None;
+    let mut signature = None;
+    for pair in query.split('&') {
+        match pair.split_once('=')? {
+            ("file", value) => file = Some(value.to_string()),
+            ("signature", value) => signature = Vec::from_hex(value).ok(),
+            _ => {}
+        }
+    }
+
+    Some((file?, signature?))
+}
+
+/// Compares two MACs byte by byte, exiting at the first difference and sleeping after every
+/// matching byte
+fn insecure_compare(a: &[u8], b: &[u8], delay: Duration) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+
+    for (x, y) in a.iter().zip(b) {
+        if x != y {
+            return false;
+        }
+        thread::sleep(delay);
+    }
+
+    true
+}
+
+/// Sends a request to the server and returns whether it accepted the signature
+fn query(port: u16, file: &str, signature: &[u8]) -> bool {
+    let mut stream = TcpStream::connect((ADDRESS, port)).unwrap();
+    let request = format!(
+        "GET /test?file={}&signature={} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n",
+        file,
+        signature.to_vec().to_hex()
+    );
+    stream.write_all(request.as_bytes()).unwrap();
+
+    let mut status_line = String::new();
+    BufReader::new(&stream).read_line(&mut status_line).unwrap();
+
+    status_line.split_whitespace().nth(1) == Some("200")
+}
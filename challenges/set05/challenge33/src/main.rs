@@ -0,0 +1,29 @@
+use dh::{DhGroup, DiffieHellman, Kdf};
+use encoding::Encode;
+use primitives::{BigUint, OsRng};
+
+fn main() {
+    let small = DhGroup::new(BigUint::from(37u64), BigUint::from(5u64), None).unwrap();
+    exchange("p = 37, g = 5", small);
+    exchange("NIST 1536 bit MODP group, g = 2", DhGroup::modp_1536());
+}
+
+fn exchange(name: &str, group: DhGroup) {
+    let mut alice = DiffieHellman::new(group.clone(), &mut OsRng);
+    let mut bob = DiffieHellman::new(group, &mut OsRng);
+    // the toy group is too small for the public key checks
+    alice.set_public_key_validation(false);
+    bob.set_public_key_validation(false);
+
+    let alice_secret = alice.shared_secret(bob.public_key()).unwrap();
+    let bob_secret = bob.shared_secret(alice.public_key()).unwrap();
+    assert_eq!(alice_secret, bob_secret);
+
+    let key = alice.derive_aes_key(bob.public_key(), Kdf::Sha256).unwrap();
+
+    println!("{}", name);
+    println!("  A = {}", alice.public_key());
+    println!("  B = {}", bob.public_key());
+    println!("  s = {}", alice_secret);
+    println!("  key = {}\n", key.to_hex());
+}
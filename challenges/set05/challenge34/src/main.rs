@@ -0,0 +1,59 @@
+use std::thread;
+
+use dh::{derive_aes_key, DhGroup, Kdf};
+use primitives::{BigUint, OsRng};
+use protocols::{
+    channel_pair, decrypt_message, dh_echo_client, dh_echo_server, run_mitm, Action, DhEchoConfig,
+    DhEchoMessage, Direction, Handshake,
+};
+
+fn main() {
+    let (client, mitm_client) = channel_pair();
+    let (mitm_server, server) = channel_pair();
+
+    let server = thread::spawn(move || dh_echo_server(server, &mut OsRng, false));
+    let client = thread::spawn(move || {
+        let messages = vec![
+            b"Diffie-Hellman is secure".to_vec(),
+            b"as long as nobody is listening".to_vec(),
+        ];
+        let config = DhEchoConfig {
+            handshake: Handshake::Direct,
+            validate_public_keys: false,
+        };
+        dh_echo_client(client, DhGroup::modp_1536(), &messages, &mut OsRng, config)
+    });
+
+    // both public keys are replaced with p, so both parties compute s = p^x mod p = 0
+    let key = derive_aes_key(&BigUint::zero(), Kdf::Sha1);
+    let mut p = BigUint::zero();
+
+    run_mitm(mitm_client, mitm_server, |direction, message, _| {
+        match message {
+            DhEchoMessage::Parameters { p: group_p, g, .. } => {
+                p = group_p.clone();
+                return Action::Replace(DhEchoMessage::Parameters {
+                    p: p.clone(),
+                    g: g.clone(),
+                    public_key: p.clone(),
+                });
+            }
+            DhEchoMessage::PublicKey(_) => {
+                return Action::Replace(DhEchoMessage::PublicKey(p.clone()));
+            }
+            _ => {}
+        }
+
+        let plaintext = decrypt_message(&key, message).unwrap();
+        let arrow = match direction {
+            Direction::ClientToServer => "A -> B",
+            Direction::ServerToClient => "B -> A",
+        };
+        println!("{}: {}", arrow, String::from_utf8_lossy(&plaintext));
+        Action::Forward
+    })
+    .unwrap();
+
+    client.join().unwrap().unwrap();
+    server.join().unwrap().unwrap();
+}
@@ -0,0 +1,61 @@
+use std::thread;
+
+use dh::{derive_aes_key, DhGroup, Kdf};
+use primitives::{BigUint, OsRng};
+use protocols::{
+    channel_pair, decrypt_message, dh_echo_client, dh_echo_server, run_mitm, Action, DhEchoConfig,
+    DhEchoMessage, Direction, Handshake,
+};
+
+fn main() {
+    let p = DhGroup::modp_1536().p().clone();
+
+    // the server computes B = g^b, and the client s = B^a
+    attack("g = 1", BigUint::one(), vec![BigUint::one()]);
+    attack("g = p", p.clone(), vec![BigUint::zero()]);
+    attack("g = p - 1", &p - 1u64, vec![BigUint::one(), &p - 1u64]);
+}
+
+/// Replaces the generator proposed to the server and decrypts the first message of the client
+/// with each candidate shared secret
+fn attack(name: &str, g: BigUint, secrets: Vec<BigUint>) {
+    let (client, mitm_client) = channel_pair();
+    let (mitm_server, server) = channel_pair();
+
+    let server = thread::spawn(move || dh_echo_server(server, &mut OsRng, false));
+    let client = thread::spawn(move || {
+        let messages = vec![b"negotiate this".to_vec()];
+        let config = DhEchoConfig {
+            handshake: Handshake::Negotiated,
+            validate_public_keys: false,
+        };
+        dh_echo_client(client, DhGroup::modp_1536(), &messages, &mut OsRng, config)
+    });
+
+    println!("{}", name);
+    run_mitm(mitm_client, mitm_server, |direction, message, _| {
+        match (direction, message) {
+            (Direction::ClientToServer, DhEchoMessage::Negotiate { p, .. }) => {
+                return Action::Replace(DhEchoMessage::Negotiate {
+                    p: p.clone(),
+                    g: g.clone(),
+                });
+            }
+            (Direction::ClientToServer, DhEchoMessage::Encrypted { .. }) => {
+                for secret in &secrets {
+                    let key = derive_aes_key(secret, Kdf::Sha1);
+                    if let Ok(plaintext) = decrypt_message(&key, message) {
+                        println!("  A -> B: {}", String::from_utf8_lossy(&plaintext));
+                    }
+                }
+            }
+            _ => {}
+        }
+        Action::Forward
+    })
+    .unwrap();
+
+    // the server derives another key, so the echo fails
+    let _ = client.join().unwrap();
+    println!("  server: {}", server.join().unwrap().unwrap_err());
+}
@@ -0,0 +1,53 @@
+use std::thread;
+
+use dh::DhGroup;
+use encoding::Encode;
+use primitives::OsRng;
+use protocols::{channel_pair, srp_client, srp_server, SrpConfig, SrpVerifier};
+
+fn main() {
+    let group = DhGroup::modp_1536();
+    let users = vec![SrpVerifier::new(
+        &group,
+        "alice@example.com",
+        "correct horse battery staple",
+        &mut OsRng,
+    )];
+
+    for password in ["correct horse battery staple", "hunter2"] {
+        let (client, server) = channel_pair();
+        let server_group = group.clone();
+        let server_users = users.clone();
+        let server = thread::spawn(move || {
+            srp_server(
+                server,
+                &server_group,
+                &server_users,
+                &mut OsRng,
+                SrpConfig::default(),
+            )
+        });
+
+        let config = SrpConfig::default();
+        let result = srp_client(
+            client,
+            &group,
+            "alice@example.com",
+            password,
+            &mut OsRng,
+            config,
+        );
+
+        println!("password {:?}", password);
+        match (result, server.join().unwrap()) {
+            (Ok(key), Ok(session)) => {
+                assert_eq!(key, session.key);
+                println!("  OK, K = {}", key.to_hex());
+            }
+            (client, server) => {
+                println!("  client: {}", client.unwrap_err());
+                println!("  server: {}", server.unwrap_err());
+            }
+        }
+    }
+}
@@ -0,0 +1,50 @@
+use std::net::TcpListener;
+use std::thread;
+
+use dh::DhGroup;
+use encoding::Encode;
+use primitives::OsRng;
+use protocols::{
+    srp_client, srp_server, srp_zero_key_login, SrpConfig, SrpMessage, SrpVerifier, TcpTransport,
+};
+
+fn main() {
+    let group = DhGroup::modp_1536();
+    let identity = "alice@example.com";
+    let users = vec![SrpVerifier::new(&group, identity, "s3cr3t", &mut OsRng)];
+    let config = SrpConfig {
+        validate_public_keys: false,
+        ..SrpConfig::default()
+    };
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    let server_group = group.clone();
+    let server = thread::spawn(move || {
+        // one honest login and four bypasses
+        (0..5)
+            .map(|_| {
+                let transport = TcpTransport::<SrpMessage>::accept(&listener).unwrap();
+                srp_server(transport, &server_group, &users, &mut OsRng, config).unwrap()
+            })
+            .collect::<Vec<_>>()
+    });
+
+    let transport = TcpTransport::connect(address).unwrap();
+    let key = srp_client(transport, &group, identity, "s3cr3t", &mut OsRng, config).unwrap();
+    println!("password: K = {}", key.to_hex());
+
+    for multiple in 0..4 {
+        let transport = TcpTransport::connect(address).unwrap();
+        let key = srp_zero_key_login(transport, &group, identity, multiple).unwrap();
+        println!("A = {} * N: K = {}", multiple, key.to_hex());
+    }
+
+    for session in server.join().unwrap() {
+        println!(
+            "server: {} logged in with K = {}",
+            session.identity,
+            session.key.to_hex()
+        );
+    }
+}
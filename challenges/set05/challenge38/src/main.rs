@@ -0,0 +1,60 @@
+use std::net::TcpListener;
+use std::thread;
+
+use cryptanalysis::{dictionary_attack, load_wordlist};
+use dh::DhGroup;
+use primitives::{BigUint, OsRng};
+use protocols::{
+    srp_client, srp_impersonate_server, SrpConfig, SrpMessage, SrpVariant, TcpTransport,
+};
+
+static WORDLIST_PATH: &str = "./lib/cryptanalysis/data/wordlist.txt";
+
+fn main() {
+    let wordlist = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| WORDLIST_PATH.to_string());
+
+    // the victim picks a password from the wordlist
+    let words = load_wordlist(&wordlist).unwrap();
+    let index = BigUint::random_below(&mut OsRng, &BigUint::from(words.len() as u64));
+    let password = words[index.to_u64().unwrap() as usize].clone();
+
+    let group = DhGroup::modp_1536();
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+
+    let client_group = group.clone();
+    let client = thread::spawn(move || {
+        let transport = TcpTransport::<SrpMessage>::connect(address).unwrap();
+        let config = SrpConfig {
+            variant: SrpVariant::Simplified,
+            validate_public_keys: true,
+        };
+        srp_client(
+            transport,
+            &client_group,
+            "alice",
+            &password,
+            &mut OsRng,
+            config,
+        )
+    });
+
+    let transport = TcpTransport::accept(&listener).unwrap();
+    let capture = srp_impersonate_server(transport, &group, &mut OsRng).unwrap();
+    println!("client: {}", client.join().unwrap().unwrap_err());
+    println!("captured the proof of {}", capture.identity);
+
+    let report = dictionary_attack(&words, |word| capture.check_password(&group, word));
+    println!(
+        "tried {} passwords in {:.2?} ({:.0} passwords/s)",
+        report.tried,
+        report.elapsed,
+        report.throughput()
+    );
+    match report.password {
+        Some(password) => println!("password: {:?}", password),
+        None => println!("password not in {}", wordlist),
+    }
+}
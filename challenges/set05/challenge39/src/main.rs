@@ -0,0 +1,29 @@
+use encoding::Encode;
+use primitives::{BigUint, OsRng};
+use rsa::RsaPrivateKey;
+
+fn main() {
+    let inverse = BigUint::from(17u64)
+        .modinv(&BigUint::from(3120u64))
+        .unwrap();
+    println!("invmod(17, 3120) = {}", inverse);
+
+    let e = BigUint::from(3u64);
+    let key = RsaPrivateKey::generate(&mut OsRng, 1024, &e).unwrap();
+    let public_key = key.public_key();
+    println!("public key: {}", public_key.to_base64());
+
+    let m = BigUint::from(42u64);
+    let c = public_key.encrypt(&m).unwrap();
+    println!("42 -> {} -> {}", c, key.decrypt(&c).unwrap());
+
+    let message = "RSA is just modular exponentiation";
+    let m = BigUint::from_be_bytes(message.as_bytes());
+    let c = public_key.encrypt(&m).unwrap();
+    let decrypted = key.decrypt_crt(&c).unwrap().to_be_bytes();
+    println!(
+        "{:?} -> {}",
+        String::from_utf8(decrypted).unwrap(),
+        c.to_hex()
+    );
+}
@@ -0,0 +1,24 @@
+use cryptanalysis::{hastad_broadcast, BroadcastCiphertext};
+use primitives::{BigUint, OsRng};
+use rsa::RsaPrivateKey;
+
+fn main() {
+    let message = "Three keys are not better than one when e = 3 and nothing is padded";
+    let m = BigUint::from_be_bytes(message.as_bytes());
+
+    // the same message encrypted under three different public keys with e = 3
+    let e = BigUint::from(3u64);
+    let ciphertexts: Vec<_> = (0..3)
+        .map(|_| {
+            let key = RsaPrivateKey::generate(&mut OsRng, 1024, &e).unwrap();
+            let public_key = key.public_key();
+            BroadcastCiphertext {
+                n: public_key.n().clone(),
+                c: public_key.encrypt(&m).unwrap(),
+            }
+        })
+        .collect();
+
+    let recovered = hastad_broadcast(3, &ciphertexts).unwrap();
+    println!("{:?}", String::from_utf8_lossy(&recovered.to_be_bytes()));
+}
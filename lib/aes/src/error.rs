@@ -5,6 +5,7 @@ pub enum AesError {
     InvalidBlockSizeError,
     InvalidIvSizeError,
     InvalidKeySizeError,
+    InvalidNonceSizeError,
     IvRequiredError,
     NotMultipleOfBlockSizeError,
 }
@@ -17,6 +18,7 @@ impl fmt::Display for AesError {
             AesError::InvalidBlockSizeError => "An AES block must have 16 bytes of length".fmt(f),
             AesError::InvalidIvSizeError => "The size of the IV must be 16 bytes".fmt(f),
             AesError::InvalidKeySizeError => "The key size must be 128, 192 or 256 bits".fmt(f),
+            AesError::InvalidNonceSizeError => "The size of the nonce must be 8 bytes".fmt(f),
             AesError::IvRequiredError => "This mode of operation requires an IV".fmt(f),
             AesError::NotMultipleOfBlockSizeError => {
                 "The size of the input text mut be multiple of 16".fmt(f)
@@ -12,6 +12,7 @@ pub static BLOCKSIZE: usize = 16;
 pub enum Mode {
     ECB,
     CBC,
+    CTR,
 }
 
 /// A struct representing an AES128 cipher
@@ -30,7 +31,7 @@ impl Aes128 {
     ///
     /// `mode`: the mode of operation
     ///
-    /// `iv`: (optional) the initialization vector
+    /// `iv`: (optional) the initialization vector. In CTR mode it holds the 8 bytes nonce
     ///
     /// # Returns
     /// A `Result` wrapping the created `Aes128` struct or an `AesError` in case of failure
@@ -48,7 +49,7 @@ impl Aes128 {
             return Err(AesError::InvalidKeySizeError);
         }
 
-        if mode == Mode::CBC && iv.is_none() {
+        if mode != Mode::ECB && iv.is_none() {
             return Err(AesError::IvRequiredError);
         }
 
@@ -56,6 +57,10 @@ impl Aes128 {
             return Err(AesError::InvalidIvSizeError);
         }
 
+        if mode == Mode::CTR && iv.as_ref().unwrap().len() != 8 {
+            return Err(AesError::InvalidNonceSizeError);
+        }
+
         Ok(Aes128 { key, mode, iv })
     }
 
@@ -88,7 +93,7 @@ impl Aes128 {
     /// assert_eq!(result, expected);
     /// ```
     pub fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>, AesError> {
-        if plaintext.len() % BLOCKSIZE != 0 {
+        if self.mode != Mode::CTR && !plaintext.len().is_multiple_of(BLOCKSIZE) {
             return Err(AesError::NotMultipleOfBlockSizeError);
         }
 
@@ -100,6 +105,7 @@ impl Aes128 {
                 self.iv.as_ref().unwrap(),
                 false,
             )),
+            Mode::CTR => Ok(aes_ctr(plaintext, &self.key, self.iv.as_ref().unwrap())),
         }
     }
 
@@ -135,7 +141,7 @@ impl Aes128 {
     /// assert_eq!(result, expected);
     /// ```
     pub fn decrypt(&self, ciphertext: &[u8]) -> Result<Vec<u8>, AesError> {
-        if ciphertext.len() % BLOCKSIZE != 0 {
+        if self.mode != Mode::CTR && !ciphertext.len().is_multiple_of(BLOCKSIZE) {
             return Err(AesError::NotMultipleOfBlockSizeError);
         }
 
@@ -147,6 +153,7 @@ impl Aes128 {
                 self.iv.as_ref().unwrap(),
                 true,
             )),
+            Mode::CTR => Ok(aes_ctr(ciphertext, &self.key, self.iv.as_ref().unwrap())),
         }
     }
 }
@@ -193,8 +200,29 @@ fn aes_cbc(text: &[u8], key: &[u8], iv: &[u8], decrypt: bool) -> Vec<u8> {
     blocks.iter().flatten().copied().collect()
 }
 
+/// Encrypts/Decrypts the given `text` with the given `key` and `nonce` using AES in CTR mode.
+/// Each keystream block is the encryption of the nonce followed by a 64 bit little endian
+/// block counter
+fn aes_ctr(text: &[u8], key: &[u8], nonce: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(text.len());
+
+    text.chunks(BLOCKSIZE)
+        .enumerate()
+        .for_each(|(counter, chunk)| {
+            let mut counter_block = nonce.to_vec();
+            counter_block.extend((counter as u64).to_le_bytes());
+
+            let keystream = aes_ecb(&counter_block, key, false);
+            out.extend(xor(chunk, &keystream));
+        });
+
+    out
+}
+
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
 mod tests {
+    use encoding::Decode;
     use primitives::pad_pkcs7;
 
     use super::*;
@@ -431,4 +459,47 @@ mod tests {
 
         assert_eq!(result, expected);
     }
+
+    #[test]
+    fn aes_new_ctr_invalid_nonce_size() {
+        let key = "ABDCDEFGHIJKLHIJ";
+        let result = Aes128::new(key.as_bytes().to_vec(), Mode::CTR, Some(vec![0; 16]));
+        let expected = Err(AesError::InvalidNonceSizeError);
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn aes_ctr_decrypt() {
+        let ciphertext = Vec::from_base64(
+            "L77na/nrFsKvynd6HzOoG7GHTLXsTVu9qvY/2syLXzhPweyyMTJULu/6/kXX0KSvoOLSFQ==",
+        )
+        .unwrap();
+        let key = "YELLOW SUBMARINE";
+
+        let cipher = Aes128::new(key.as_bytes().to_vec(), Mode::CTR, Some(vec![0; 8])).unwrap();
+        let result = cipher.decrypt(&ciphertext).unwrap();
+
+        let expected = "Yo, VIP Let's kick it Ice, Ice, baby Ice, Ice, baby ".as_bytes();
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn aes_ctr_encrypt_decrypt() {
+        let plaintext = "THIS IS A TEST WITH NO PADDING".as_bytes();
+        let key = "abcdefghijkuhgfq";
+        let nonce = "01234567";
+
+        let cipher = Aes128::new(
+            key.as_bytes().to_vec(),
+            Mode::CTR,
+            Some(nonce.as_bytes().to_vec()),
+        )
+        .unwrap();
+        let ciphertext = cipher.encrypt(plaintext).unwrap();
+        let result = cipher.decrypt(&ciphertext).unwrap();
+
+        assert_eq!(ciphertext.len(), plaintext.len());
+        assert_eq!(result, plaintext);
+    }
 }
@@ -0,0 +1,276 @@
+use std::cmp::Reverse;
+
+use primitives::xor;
+
+use crate::utils::count_printable;
+use crate::{calculate_lang_score, CribDragError};
+
+/// A position where a crib produces a plausible fragment in another message
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CribMatch {
+    /// The message the crib is assumed to be in
+    pub index: usize,
+
+    /// The message the fragment is revealed in
+    pub other: usize,
+
+    /// The position of the crib in both messages
+    pub offset: usize,
+
+    /// The bytes of `other` revealed by assuming the crib is at `offset` of `index`
+    pub fragment: Vec<u8>,
+
+    /// The language score of the fragment
+    pub score: usize,
+}
+
+/// A crib-dragging session over a set of ciphertexts encrypted with the same keystream
+/// (e.g. CTR with a fixed nonce or a reused one-time pad).
+///
+/// XORing two of those ciphertexts cancels the keystream and leaves the XOR of the two
+/// plaintexts, so a guessed word placed in one message reveals the bytes at the same
+/// position of the other. Accepted guesses fix the keystream for every message.
+#[derive(Debug, Clone)]
+pub struct CribDragSession {
+    ciphertexts: Vec<Vec<u8>>,
+    keystream: Vec<Option<u8>>,
+    history: Vec<(usize, Vec<Option<u8>>)>,
+    lang: String,
+}
+
+impl CribDragSession {
+    /// Creates a new `CribDragSession` with an unknown keystream
+    ///
+    /// # Args
+    /// `ciphertexts`: the ciphertexts encrypted with the same keystream
+    ///
+    /// `lang`: the suspected source language (e.g. "EN")
+    pub fn new(ciphertexts: Vec<Vec<u8>>, lang: &str) -> Self {
+        let keystream_len = ciphertexts.iter().map(|c| c.len()).max().unwrap_or(0);
+
+        CribDragSession {
+            ciphertexts,
+            keystream: vec![None; keystream_len],
+            history: vec![],
+            lang: lang.to_string(),
+        }
+    }
+
+    /// Returns the number of ciphertexts in the session
+    pub fn len(&self) -> usize {
+        self.ciphertexts.len()
+    }
+
+    /// Returns `true` if the session has no ciphertexts
+    pub fn is_empty(&self) -> bool {
+        self.ciphertexts.is_empty()
+    }
+
+    /// Returns the keystream recovered so far, `None` marking the unknown bytes
+    pub fn keystream(&self) -> &[Option<u8>] {
+        &self.keystream
+    }
+
+    /// Returns the plaintexts recovered so far, `None` marking the unknown bytes
+    pub fn plaintexts(&self) -> Vec<Vec<Option<u8>>> {
+        self.ciphertexts
+            .iter()
+            .map(|c| {
+                c.iter()
+                    .zip(&self.keystream)
+                    .map(|(u, k)| k.map(|k| u ^ k))
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Slides the given crib across every pair of ciphertexts
+    ///
+    /// # Args
+    /// `crib`: the guessed plaintext fragment
+    ///
+    /// # Returns
+    /// A `Vec<CribMatch>` with every position where the crib reveals printable text in the
+    /// other message, from most to least plausible
+    pub fn drag(&self, crib: &[u8]) -> Vec<CribMatch> {
+        let mut matches = vec![];
+
+        if crib.is_empty() {
+            return matches;
+        }
+
+        for index in 0..self.ciphertexts.len() {
+            for other in 0..self.ciphertexts.len() {
+                if index == other {
+                    continue;
+                }
+
+                let a = &self.ciphertexts[index];
+                let b = &self.ciphertexts[other];
+                let len = a.len().min(b.len());
+                if len < crib.len() {
+                    continue;
+                }
+
+                let plaintexts_xor = xor(&a[..len], &b[..len]);
+                for offset in 0..=len - crib.len() {
+                    let fragment = xor(&plaintexts_xor[offset..offset + crib.len()], crib);
+                    if count_printable(&fragment) != fragment.len() {
+                        continue;
+                    }
+
+                    let score = calculate_lang_score(&fragment, &self.lang);
+                    matches.push(CribMatch {
+                        index,
+                        other,
+                        offset,
+                        fragment,
+                        score,
+                    });
+                }
+            }
+        }
+
+        // the language score is coarse on short fragments, prefer lowercase letters and
+        // spaces among matches with the same score
+        matches.sort_by_key(|m| {
+            let common = m
+                .fragment
+                .iter()
+                .filter(|u| u.is_ascii_lowercase() || **u == b' ')
+                .count();
+            Reverse((m.score, common))
+        });
+
+        matches
+    }
+
+    /// Accepts a guess, fixing the keystream bytes under it for every message
+    ///
+    /// # Args
+    /// `index`: the index of the message the guess belongs to
+    ///
+    /// `offset`: the position of the guess in that message
+    ///
+    /// `guess`: the guessed plaintext bytes
+    ///
+    /// # Returns
+    /// A `Result` wrapping nothing or a `CribDragError` if the guess does not fit
+    pub fn commit(
+        &mut self,
+        index: usize,
+        offset: usize,
+        guess: &[u8],
+    ) -> Result<(), CribDragError> {
+        let ciphertext = self
+            .ciphertexts
+            .get(index)
+            .ok_or(CribDragError::InvalidIndex)?;
+        if offset + guess.len() > ciphertext.len() {
+            return Err(CribDragError::OutOfBounds);
+        }
+
+        let range = offset..offset + guess.len();
+        self.history
+            .push((offset, self.keystream[range.clone()].to_vec()));
+
+        for (pos, &u) in range.zip(guess) {
+            self.keystream[pos] = Some(ciphertext[pos] ^ u);
+        }
+
+        Ok(())
+    }
+
+    /// Reverts the last committed guess
+    pub fn undo(&mut self) -> Result<(), CribDragError> {
+        let (offset, previous) = self.history.pop().ok_or(CribDragError::NothingToUndo)?;
+        self.keystream[offset..offset + previous.len()].copy_from_slice(&previous);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static KEYSTREAM: [u8; 32] = [
+        0x3C, 0x91, 0x0A, 0xE5, 0x72, 0x4B, 0xD8, 0x19, 0x66, 0xF0, 0x2E, 0x83, 0x5D, 0xB7, 0x04,
+        0xC9, 0x98, 0x31, 0x7F, 0xE2, 0x4D, 0xA6, 0x1B, 0x50, 0xCE, 0x8F, 0x27, 0x6A, 0xF4, 0x03,
+        0xB9, 0x45,
+    ];
+
+    static PLAINTEXTS: [&str; 4] = [
+        "Attack the east wall at dawn",
+        "Hold the west gate until noon",
+        "Send more men to the river",
+        "We retreat after the signal",
+    ];
+
+    fn session() -> CribDragSession {
+        let ciphertexts = PLAINTEXTS
+            .iter()
+            .map(|p| xor(p.as_bytes(), &KEYSTREAM))
+            .collect();
+        CribDragSession::new(ciphertexts, "EN")
+    }
+
+    #[test]
+    fn crib_drag_finds_fragment() {
+        let session = session();
+        let matches = session.drag(" the ".as_bytes());
+
+        // " the " is at offset 6 of the first message, which reveals "he we" in the second
+        let expected = CribMatch {
+            index: 0,
+            other: 1,
+            offset: 6,
+            fragment: "he we".as_bytes().to_vec(),
+            score: calculate_lang_score("he we".as_bytes(), "EN"),
+        };
+        assert!(matches.contains(&expected));
+        assert!(matches
+            .iter()
+            .all(|m| count_printable(&m.fragment) == m.fragment.len()));
+    }
+
+    #[test]
+    fn crib_drag_commit_reveals_all_messages() {
+        let mut session = session();
+        session
+            .commit(1, 0, "Hold the west gate until noon".as_bytes())
+            .unwrap();
+
+        let plaintexts = session.plaintexts();
+        for (plaintext, expected) in plaintexts.iter().zip(PLAINTEXTS) {
+            let known: Vec<u8> = plaintext.iter().map_while(|u| *u).collect();
+            assert_eq!(known, expected.as_bytes()[..known.len()]);
+        }
+        assert_eq!(plaintexts[0].iter().filter(|u| u.is_some()).count(), 28);
+        assert_eq!(plaintexts[1].iter().filter(|u| u.is_none()).count(), 0);
+    }
+
+    #[test]
+    fn crib_drag_undo() {
+        let mut session = session();
+        session.commit(0, 0, "Attack".as_bytes()).unwrap();
+        session.commit(0, 0, "Attach".as_bytes()).unwrap();
+        session.undo().unwrap();
+
+        let result: Vec<Option<u8>> = session.plaintexts()[0][..7].to_vec();
+        let mut expected: Vec<Option<u8>> = "Attack".bytes().map(Some).collect();
+        expected.push(None);
+        assert_eq!(result, expected);
+
+        session.undo().unwrap();
+        assert!(session.keystream().iter().all(|k| k.is_none()));
+        assert_eq!(session.undo(), Err(CribDragError::NothingToUndo));
+    }
+
+    #[test]
+    fn crib_drag_commit_out_of_bounds() {
+        let mut session = session();
+        let result = session.commit(2, 20, "the river!".as_bytes());
+        assert_eq!(result, Err(CribDragError::OutOfBounds));
+    }
+}
@@ -0,0 +1,158 @@
+use std::fs;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// The outcome of a dictionary attack
+#[derive(Debug, Clone, PartialEq)]
+pub struct DictionaryAttackReport {
+    /// The password accepted by the check, if any
+    pub password: Option<String>,
+
+    /// The number of candidates checked before the attack stopped
+    pub tried: usize,
+
+    /// The duration of the attack
+    pub elapsed: Duration,
+}
+
+impl DictionaryAttackReport {
+    /// Returns the number of candidates checked per second
+    pub fn throughput(&self) -> f64 {
+        self.tried as f64 / self.elapsed.as_secs_f64().max(f64::EPSILON)
+    }
+}
+
+/// Reads a wordlist file with one candidate per line. Trailing whitespace is trimmed and
+/// blank lines are skipped
+///
+/// # Returns
+/// A `Result` wrapping the candidates or an `std::io::Error` if the file cannot be read
+pub fn load_wordlist<P: AsRef<Path>>(path: P) -> Result<Vec<String>, std::io::Error> {
+    let content = fs::read_to_string(path)?;
+    let words = content
+        .lines()
+        .map(str::trim_end)
+        .filter(|word| !word.is_empty())
+        .map(str::to_string)
+        .collect();
+
+    Ok(words)
+}
+
+/// Runs `dictionary_attack` over a wordlist file loaded with `load_wordlist`
+///
+/// # Returns
+/// A `Result` wrapping the `DictionaryAttackReport` or an `std::io::Error` if the wordlist
+/// cannot be read
+pub fn dictionary_attack_file<P, F>(
+    wordlist: P,
+    check: F,
+) -> Result<DictionaryAttackReport, std::io::Error>
+where
+    P: AsRef<Path>,
+    F: Fn(&str) -> bool + Sync,
+{
+    let words = load_wordlist(wordlist)?;
+    Ok(dictionary_attack(&words, check))
+}
+
+/// Tests candidate passwords against an offline check, split across all available cores. The
+/// candidates are dealt to the threads in turn so that the beginning of the list, where the
+/// most likely passwords are, is tried first, and all threads stop as soon as one of them finds
+/// the password.
+///
+/// # Args
+/// `words`: the candidate passwords
+///
+/// `check`: returns whether a candidate is the password (e.g. whether it reproduces a captured
+/// MAC)
+///
+/// # Returns
+/// A `DictionaryAttackReport` with the password, if found, and the throughput of the attack
+pub fn dictionary_attack<S, F>(words: &[S], check: F) -> DictionaryAttackReport
+where
+    S: AsRef<str> + Sync,
+    F: Fn(&str) -> bool + Sync,
+{
+    let n_threads = thread::available_parallelism().map_or(1, |n| n.get());
+    let start = Instant::now();
+    let found = AtomicBool::new(false);
+    let tried = AtomicUsize::new(0);
+    let password = Mutex::new(None);
+
+    thread::scope(|scope| {
+        for offset in 0..n_threads.min(words.len()) {
+            let (found, tried, password, check) = (&found, &tried, &password, &check);
+            scope.spawn(move || {
+                for word in words.iter().skip(offset).step_by(n_threads) {
+                    if found.load(Ordering::Relaxed) {
+                        return;
+                    }
+                    tried.fetch_add(1, Ordering::Relaxed);
+                    if check(word.as_ref()) {
+                        found.store(true, Ordering::Relaxed);
+                        *password.lock().unwrap() = Some(word.as_ref().to_string());
+                        return;
+                    }
+                }
+            });
+        }
+    });
+
+    DictionaryAttackReport {
+        password: password.into_inner().unwrap(),
+        tried: tried.into_inner(),
+        elapsed: start.elapsed(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use primitives::Sha256;
+
+    use super::*;
+
+    static WORDLIST: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/data/wordlist.txt");
+
+    #[test]
+    fn dictionary_attack_finds_password() {
+        let target = Sha256::digest("salt:dolphin".as_bytes());
+        let check = |word: &str| Sha256::digest(format!("salt:{}", word).as_bytes()) == target;
+
+        let result = dictionary_attack_file(WORDLIST, check).unwrap();
+        assert_eq!(result.password.as_deref(), Some("dolphin"));
+        assert!(result.tried > 0);
+        assert!(result.throughput() > 0.0);
+    }
+
+    #[test]
+    fn dictionary_attack_stops_early() {
+        let words: Vec<String> = (0..10_000).map(|i| i.to_string()).collect();
+
+        let result = dictionary_attack(&words, |word| word == "3");
+        assert_eq!(result.password.as_deref(), Some("3"));
+        assert!(result.tried < words.len());
+    }
+
+    #[test]
+    fn dictionary_attack_exhausts_wordlist() {
+        let result = dictionary_attack_file(WORDLIST, |word| word == "correct horse").unwrap();
+        assert_eq!(result.password, None);
+        assert_eq!(result.tried, 244);
+
+        assert!(dictionary_attack_file("/nonexistent/wordlist.txt", |_| true).is_err());
+    }
+
+    #[test]
+    fn load_wordlist_skips_blank_lines() {
+        let path = std::env::temp_dir().join("cryptanalysis_load_wordlist.txt");
+        fs::write(&path, "alpha  \n\n   \nbeta\r\ngamma").unwrap();
+
+        let result = load_wordlist(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(result, ["alpha", "beta", "gamma"]);
+    }
+}
@@ -0,0 +1,172 @@
+use std::fmt;
+
+/// Errors that can occur while running a CBC padding oracle attack
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PaddingOracleError {
+    /// The block size must be greater than 1
+    InvalidBlockSize,
+
+    /// The IV must be exactly one block long
+    InvalidIvSize,
+
+    /// The ciphertext must be a non-empty multiple of the block size
+    InvalidCiphertextSize,
+
+    /// No byte value produced a valid padding, the oracle is inconsistent
+    NoValidPadding,
+}
+
+impl std::error::Error for PaddingOracleError {}
+
+impl fmt::Display for PaddingOracleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PaddingOracleError::InvalidBlockSize => "the block size must be greater than 1".fmt(f),
+            PaddingOracleError::InvalidIvSize => "the IV must be one block long".fmt(f),
+            PaddingOracleError::InvalidCiphertextSize => {
+                "the ciphertext must be a non-empty multiple of the block size".fmt(f)
+            }
+            PaddingOracleError::NoValidPadding => {
+                "the oracle did not accept any padding for the current byte".fmt(f)
+            }
+        }
+    }
+}
+
+/// Errors that can occur while committing guesses in a crib-dragging session
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CribDragError {
+    /// There is no ciphertext with the given index
+    InvalidIndex,
+
+    /// The guess does not fit in the ciphertext at the given offset
+    OutOfBounds,
+
+    /// There is no committed guess to undo
+    NothingToUndo,
+}
+
+impl std::error::Error for CribDragError {}
+
+impl fmt::Display for CribDragError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CribDragError::InvalidIndex => "there is no ciphertext with the given index".fmt(f),
+            CribDragError::OutOfBounds => {
+                "the guess does not fit in the ciphertext at the given offset".fmt(f)
+            }
+            CribDragError::NothingToUndo => "there is no committed guess to undo".fmt(f),
+        }
+    }
+}
+
+/// Errors that can occur while refining a fixed-nonce CTR break with a crib
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FixedNonceCtrError {
+    /// There is no ciphertext with the given index
+    InvalidIndex,
+}
+
+impl std::error::Error for FixedNonceCtrError {}
+
+impl fmt::Display for FixedNonceCtrError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FixedNonceCtrError::InvalidIndex => {
+                "there is no ciphertext with the given index".fmt(f)
+            }
+        }
+    }
+}
+
+/// Errors that can occur while recovering the internal state of a MT19937 generator
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Mt19937RecoveryError {
+    /// The observations do not pin down a single internal state
+    Underdetermined,
+
+    /// The observations contradict each other and can't come from a MT19937 generator
+    Inconsistent,
+}
+
+impl std::error::Error for Mt19937RecoveryError {}
+
+impl fmt::Display for Mt19937RecoveryError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Mt19937RecoveryError::Underdetermined => {
+                "not enough observed bits to recover the internal state".fmt(f)
+            }
+            Mt19937RecoveryError::Inconsistent => {
+                "the observations are not consistent with a MT19937 generator".fmt(f)
+            }
+        }
+    }
+}
+
+/// Errors that can occur while forging a MAC with a length extension attack
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LengthExtensionError {
+    /// The MAC is not a full digest of the hash function
+    InvalidMacSize,
+}
+
+impl std::error::Error for LengthExtensionError {}
+
+impl fmt::Display for LengthExtensionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LengthExtensionError::InvalidMacSize => {
+                "the MAC must be a full digest of the hash function".fmt(f)
+            }
+        }
+    }
+}
+
+/// Errors that can occur while running a Håstad broadcast attack
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HastadError {
+    /// At least one ciphertext is required
+    NoCiphertexts,
+
+    /// A modulus is zero
+    ZeroModulus,
+
+    /// The public exponent must be positive
+    InvalidExponent,
+
+    /// Two moduli share a factor, which factors both of them
+    ModuliNotCoprime,
+
+    /// The combined ciphertext is not an exact e-th power: the message is too large for the
+    /// number of ciphertexts
+    NoIntegerRoot,
+
+    /// The multiplier of a padding is not invertible modulo its modulus
+    NonInvertiblePadding,
+
+    /// The paddings are not related in a way that can be undone without Coppersmith's method
+    UnsupportedPadding,
+}
+
+impl std::error::Error for HastadError {}
+
+impl fmt::Display for HastadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            HastadError::NoCiphertexts => "at least one ciphertext is required".fmt(f),
+            HastadError::ZeroModulus => "the moduli must be positive".fmt(f),
+            HastadError::InvalidExponent => "the public exponent must be positive".fmt(f),
+            HastadError::ModuliNotCoprime => "the moduli are not pairwise coprime".fmt(f),
+            HastadError::NoIntegerRoot => {
+                "the combined ciphertext is not an e-th power, more ciphertexts are needed".fmt(f)
+            }
+            HastadError::NonInvertiblePadding => {
+                "the padding multiplier is not invertible modulo n".fmt(f)
+            }
+            HastadError::UnsupportedPadding => {
+                "the paddings cannot be undone without Coppersmith's method".fmt(f)
+            }
+        }
+    }
+}
@@ -0,0 +1,276 @@
+use std::cmp::Reverse;
+
+use primitives::xor;
+
+use crate::utils::count_printable;
+use crate::{break_single_byte_xor, calculate_lang_score, FixedNonceCtrError};
+
+/// How ciphertexts of different lengths are lined up before attacking the keystream
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Alignment {
+    /// Truncate every ciphertext to the length of the shortest one, turning the problem
+    /// into a repeating-key XOR with a key as long as the shortest ciphertext
+    Truncate,
+
+    /// Keep every byte and attack each keystream column with all the ciphertexts that
+    /// are long enough to reach it
+    Columns,
+}
+
+/// The outcome of a fixed-nonce CTR attack
+#[derive(Debug, Clone, PartialEq)]
+pub struct FixedNonceCtrBreak {
+    /// The recovered keystream
+    pub keystream: Vec<u8>,
+
+    /// The plaintexts obtained by decrypting every ciphertext with `keystream`
+    pub plaintexts: Vec<Vec<u8>>,
+
+    /// A value between 0 and 1 for each plaintext byte telling how confident we are that
+    /// it was recovered correctly
+    pub confidence: Vec<Vec<f64>>,
+
+    ciphertexts: Vec<Vec<u8>>,
+    keystream_confidence: Vec<f64>,
+}
+
+impl FixedNonceCtrBreak {
+    /// Fixes the keystream bytes under a known piece of plaintext (a crib) and updates
+    /// every plaintext accordingly. The fixed bytes are given full confidence.
+    ///
+    /// # Args
+    /// `index`: the index of the ciphertext the crib belongs to
+    ///
+    /// `offset`: the position of the crib in that plaintext
+    ///
+    /// `crib`: the known plaintext bytes
+    ///
+    /// # Returns
+    /// The number of keystream bytes that changed, or `FixedNonceCtrError::InvalidIndex` if
+    /// there is no ciphertext at `index`
+    pub fn apply_crib(
+        &mut self,
+        index: usize,
+        offset: usize,
+        crib: &[u8],
+    ) -> Result<usize, FixedNonceCtrError> {
+        let ciphertext = self
+            .ciphertexts
+            .get(index)
+            .ok_or(FixedNonceCtrError::InvalidIndex)?;
+        let end = ciphertext
+            .len()
+            .min(offset + crib.len())
+            .min(self.keystream.len());
+        let mut changed = 0;
+
+        for pos in offset..end {
+            let key_byte = ciphertext[pos] ^ crib[pos - offset];
+            if self.keystream[pos] != key_byte {
+                self.keystream[pos] = key_byte;
+                changed += 1;
+            }
+            self.keystream_confidence[pos] = 1.0;
+        }
+
+        self.update_plaintexts();
+
+        Ok(changed)
+    }
+
+    fn update_plaintexts(&mut self) {
+        self.plaintexts = self
+            .ciphertexts
+            .iter()
+            .map(|c| xor(&c[..c.len().min(self.keystream.len())], &self.keystream))
+            .collect();
+
+        self.confidence = self
+            .plaintexts
+            .iter()
+            .map(|p| self.keystream_confidence[..p.len()].to_vec())
+            .collect();
+    }
+}
+
+/// Attack a set of ciphertexts encrypted in CTR mode with the same key and nonce.
+///
+/// Since every ciphertext was XORed with the same keystream, the bytes at the same position
+/// of each ciphertext form a single-byte XOR problem that is solved with
+/// `break_single_byte_xor`. Among the best scoring key bytes of each column, the one that
+/// decrypts to the most printable characters is kept.
+///
+/// # Args
+/// `ciphertexts`: The target ciphertexts
+///
+/// `alignment`: How ciphertexts of different lengths are lined up
+///
+/// `lang`: The suspected source language (e.g. "EN")
+///
+/// # Returns
+/// A `FixedNonceCtrBreak` with the recovered keystream, the plaintexts and a per-byte
+/// confidence that can be refined later with `FixedNonceCtrBreak::apply_crib`
+pub fn break_fixed_nonce_ctr(
+    ciphertexts: &[Vec<u8>],
+    alignment: Alignment,
+    lang: &str,
+) -> FixedNonceCtrBreak {
+    let keystream_len = match alignment {
+        Alignment::Truncate => ciphertexts.iter().map(|c| c.len()).min().unwrap_or(0),
+        Alignment::Columns => ciphertexts.iter().map(|c| c.len()).max().unwrap_or(0),
+    };
+    let ciphertexts: Vec<Vec<u8>> = ciphertexts
+        .iter()
+        .map(|c| c[..c.len().min(keystream_len)].to_vec())
+        .collect();
+
+    let mut keystream = Vec::with_capacity(keystream_len);
+    let mut keystream_confidence = Vec::with_capacity(keystream_len);
+
+    for pos in 0..keystream_len {
+        let column: Vec<u8> = ciphertexts
+            .iter()
+            .filter_map(|c| c.get(pos))
+            .copied()
+            .collect();
+        let (key_byte, confidence) = break_column(&column, lang);
+        keystream.push(key_byte);
+        keystream_confidence.push(confidence);
+    }
+
+    let mut result = FixedNonceCtrBreak {
+        keystream,
+        plaintexts: vec![],
+        confidence: vec![],
+        ciphertexts,
+        keystream_confidence,
+    };
+    result.update_plaintexts();
+
+    result
+}
+
+/// Returns the most likely key byte of a column with the confidence on it
+fn break_column(column: &[u8], lang: &str) -> (u8, f64) {
+    let key_scores = break_single_byte_xor(column, lang);
+
+    // the language score alone is coarse, so rank the candidates by the number of
+    // printable characters they produce and break ties with the number of lowercase
+    // letters and spaces and then with the language score
+    let mut candidates: Vec<(u8, usize, usize, usize)> = key_scores
+        .iter()
+        .map(|&(key, score)| {
+            let plaintext = xor(column, &[key]);
+            let common = plaintext
+                .iter()
+                .filter(|u| u.is_ascii_lowercase() || **u == b' ')
+                .count();
+            (key, count_printable(&plaintext), score, common)
+        })
+        .collect();
+    candidates.sort_by_key(|&(_, printable, score, common)| Reverse((printable, common, score)));
+
+    let (key, printable, _, _) = candidates[0];
+    let plaintext = xor(column, &[key]);
+
+    // short columns (e.g. the tail of the longest ciphertexts) carry little evidence
+    let printable_ratio = printable as f64 / column.len() as f64;
+    let lang_ratio = calculate_lang_score(&plaintext, lang) as f64 / 12.0;
+    let sample_ratio = (column.len() as f64 / 16.0).min(1.0);
+
+    (
+        key,
+        printable_ratio * (0.5 + 0.5 * lang_ratio) * sample_ratio,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use aes::{Aes128, Mode};
+
+    use super::*;
+
+    static PLAINTEXTS: [&str; 20] = [
+        "I have met them at close of day",
+        "Coming with vivid faces",
+        "From counter or desk among grey",
+        "Eighteenth-century houses.",
+        "I have passed with a nod of the head",
+        "Or polite meaningless words,",
+        "Or have lingered awhile and said",
+        "Polite meaningless words,",
+        "And thought before I had done",
+        "Of a mocking tale or a gibe",
+        "To please a companion",
+        "Around the fire at the club,",
+        "Being certain that they and I",
+        "But lived where motley is worn:",
+        "All changed, changed utterly:",
+        "A terrible beauty is born.",
+        "That woman's days were spent",
+        "In ignorant good will,",
+        "Her nights in argument",
+        "Until her voice grew shrill.",
+    ];
+
+    fn encrypt_all() -> Vec<Vec<u8>> {
+        let cipher = Aes128::new(
+            "YELLOW SUBMARINE".as_bytes().to_vec(),
+            Mode::CTR,
+            Some(vec![0; 8]),
+        )
+        .unwrap();
+        PLAINTEXTS
+            .iter()
+            .map(|p| cipher.encrypt(p.as_bytes()).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn fixed_nonce_ctr_truncate() {
+        let ciphertexts = encrypt_all();
+        let result = break_fixed_nonce_ctr(&ciphertexts, Alignment::Truncate, "EN");
+
+        assert_eq!(result.keystream.len(), 21);
+
+        // the first column only has uppercase letters, whose case can't be told apart
+        // statistically
+        for (plaintext, expected) in result.plaintexts.iter().zip(PLAINTEXTS) {
+            assert_eq!(plaintext[1..], expected.as_bytes()[1..21]);
+        }
+    }
+
+    #[test]
+    fn fixed_nonce_ctr_columns_with_crib() {
+        let ciphertexts = encrypt_all();
+        let mut result = break_fixed_nonce_ctr(&ciphertexts, Alignment::Columns, "EN");
+
+        assert_eq!(result.keystream.len(), 36);
+
+        // the last columns are only covered by one ciphertext and can't be trusted
+        assert!(result.confidence[4][35] < result.confidence[4][5]);
+
+        result.apply_crib(0, 0, "I have".as_bytes()).unwrap();
+        result
+            .apply_crib(4, 21, "nod of the head".as_bytes())
+            .unwrap();
+        for (plaintext, expected) in result.plaintexts.iter().zip(PLAINTEXTS) {
+            assert_eq!(plaintext, expected.as_bytes());
+        }
+        assert_eq!(result.confidence[4][0], 1.0);
+        assert_eq!(result.confidence[4][35], 1.0);
+    }
+
+    #[test]
+    fn fixed_nonce_ctr_crib_invalid_index() {
+        let ciphertexts = encrypt_all();
+        let mut result = break_fixed_nonce_ctr(&ciphertexts, Alignment::Truncate, "EN");
+
+        let keystream = result.keystream.clone();
+        assert_eq!(
+            result.apply_crib(20, 0, "I have".as_bytes()),
+            Err(FixedNonceCtrError::InvalidIndex)
+        );
+        assert_eq!(result.keystream, keystream);
+    }
+}
@@ -0,0 +1,341 @@
+use primitives::BigUint;
+
+use crate::HastadError;
+
+/// A ciphertext `c = m^e mod n` of the broadcast message
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BroadcastCiphertext {
+    pub n: BigUint,
+    pub c: BigUint,
+}
+
+/// A ciphertext `c = (a * m + b)^e mod n` of the broadcast message after a known linear padding
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PaddedBroadcastCiphertext {
+    pub n: BigUint,
+    pub c: BigUint,
+    pub a: BigUint,
+    pub b: BigUint,
+}
+
+/// Solves a system of congruences `x = r_i mod n_i` with the Chinese Remainder Theorem
+///
+/// # Args
+/// `congruences`: the pairs `(r_i, n_i)`, with pairwise coprime moduli
+///
+/// # Returns
+/// A `Result` wrapping the solution `x` in `[0, N)` and the product `N` of the moduli, or a
+/// `HastadError` if there are no congruences, a modulus is zero or the moduli are not pairwise
+/// coprime
+///
+/// # Examples
+/// ```
+/// use cryptanalysis::chinese_remainder;
+/// use primitives::BigUint;
+///
+/// let congruences = [(2u64, 3u64), (3, 5), (2, 7)]
+///     .map(|(r, n)| (BigUint::from(r), BigUint::from(n)));
+/// let (x, n) = chinese_remainder(&congruences).unwrap();
+///
+/// assert_eq!(x, BigUint::from(23u64));
+/// assert_eq!(n, BigUint::from(105u64));
+/// ```
+pub fn chinese_remainder(
+    congruences: &[(BigUint, BigUint)],
+) -> Result<(BigUint, BigUint), HastadError> {
+    if congruences.is_empty() {
+        return Err(HastadError::NoCiphertexts);
+    }
+    if congruences.iter().any(|(_, n)| n.is_zero()) {
+        return Err(HastadError::ZeroModulus);
+    }
+
+    let product = congruences
+        .iter()
+        .fold(BigUint::one(), |product, (_, n)| product * n);
+
+    let mut x = BigUint::zero();
+    for (r, n) in congruences {
+        let cofactor = &product / n;
+        let inverse = (&cofactor % n)
+            .modinv(n)
+            .ok_or(HastadError::ModuliNotCoprime)?;
+        x += (r % n) * inverse % n * cofactor;
+    }
+
+    Ok((x % &product, product))
+}
+
+/// Recovers a message encrypted under `e` different RSA keys with the same small public
+/// exponent `e` and no padding (Håstad's broadcast attack).
+///
+/// By the CRT, the ciphertexts give `m^e mod n_1 * ... * n_k`, which is `m^e` itself as soon as
+/// `m^e` is smaller than the product of the moduli, and an integer e-th root reveals `m`. `e`
+/// ciphertexts always suffice, fewer do for short messages.
+///
+/// # Args
+/// `e`: the common public exponent
+///
+/// `ciphertexts`: the ciphertexts of the same message under keys with coprime moduli
+///
+/// # Returns
+/// A `Result` wrapping the message or a `HastadError`
+pub fn hastad_broadcast(
+    e: u32,
+    ciphertexts: &[BroadcastCiphertext],
+) -> Result<BigUint, HastadError> {
+    if e == 0 {
+        return Err(HastadError::InvalidExponent);
+    }
+
+    let congruences: Vec<_> = ciphertexts
+        .iter()
+        .map(|ciphertext| (ciphertext.c.clone(), ciphertext.n.clone()))
+        .collect();
+    let (power, _) = chinese_remainder(&congruences)?;
+
+    let root = power.nth_root(e);
+    if root.pow(e) != power {
+        return Err(HastadError::NoIntegerRoot);
+    }
+    Ok(root)
+}
+
+/// Recovers a message broadcast with a known linear padding `a_i * m + b_i` per recipient,
+/// in the cases that reduce to `hastad_broadcast` without Coppersmith's method:
+///
+/// - every recipient uses the same padding `(a, b)` and `a * m + b` is smaller than the moduli:
+///   the padded message is recovered, then `m = (M - b) / a`
+/// - the paddings share the same shift `t = b_i / a_i mod n_i` (e.g. `b_i = 0`): multiplying
+///   each ciphertext by `a_i^-e` gives `(m + t)^e mod n_i`
+///
+/// Any other padding, such as a per-recipient prefix, requires finding a small root of a
+/// polynomial modulo `N` (Coppersmith) and returns `HastadError::UnsupportedPadding`.
+///
+/// # Args
+/// `e`: the common public exponent
+///
+/// `ciphertexts`: the ciphertexts of the padded message under keys with coprime moduli
+///
+/// # Returns
+/// A `Result` wrapping the message or a `HastadError`
+pub fn hastad_broadcast_linear(
+    e: u32,
+    ciphertexts: &[PaddedBroadcastCiphertext],
+) -> Result<BigUint, HastadError> {
+    let Some(first) = ciphertexts.first() else {
+        return Err(HastadError::NoCiphertexts);
+    };
+    if e == 0 {
+        return Err(HastadError::InvalidExponent);
+    }
+    if ciphertexts.iter().any(|ciphertext| ciphertext.n.is_zero()) {
+        return Err(HastadError::ZeroModulus);
+    }
+    // a zero or non-invertible a_i loses information on m
+    let inverses = ciphertexts
+        .iter()
+        .map(|ciphertext| ciphertext.a.modinv(&ciphertext.n))
+        .collect::<Option<Vec<_>>>()
+        .ok_or(HastadError::NonInvertiblePadding)?;
+
+    if ciphertexts
+        .iter()
+        .all(|ciphertext| ciphertext.a == first.a && ciphertext.b == first.b)
+    {
+        let plain: Vec<_> = ciphertexts
+            .iter()
+            .map(|ciphertext| BroadcastCiphertext {
+                n: ciphertext.n.clone(),
+                c: ciphertext.c.clone(),
+            })
+            .collect();
+        let padded = hastad_broadcast(e, &plain)?;
+
+        let shifted = padded
+            .checked_sub(&first.b)
+            .ok_or(HastadError::NoIntegerRoot)?;
+        let (m, remainder) = shifted.div_rem(&first.a);
+        if !remainder.is_zero() {
+            return Err(HastadError::NoIntegerRoot);
+        }
+        return Ok(m);
+    }
+
+    // (a * m + b)^e = a^e * (m + b / a)^e mod n
+    let mut shift = None;
+    let mut stripped = vec![];
+    for (PaddedBroadcastCiphertext { n, c, b, .. }, inverse) in ciphertexts.iter().zip(inverses) {
+        let t = b * &inverse % n;
+        match &shift {
+            None => shift = Some(t),
+            Some(shift) if *shift == t => {}
+            Some(_) => return Err(HastadError::UnsupportedPadding),
+        }
+        stripped.push(BroadcastCiphertext {
+            n: n.clone(),
+            c: c * inverse.modpow(&BigUint::from(e as u64), n) % n,
+        });
+    }
+
+    let shifted = hastad_broadcast(e, &stripped)?;
+    shifted
+        .checked_sub(&shift.unwrap())
+        .ok_or(HastadError::NoIntegerRoot)
+}
+
+#[cfg(test)]
+mod tests {
+    use primitives::Mt19937;
+    use rsa::{RsaPrivateKey, RsaPublicKey};
+
+    use super::*;
+
+    fn keys(rng: &mut Mt19937, count: usize, bits: usize, e: u64) -> Vec<RsaPublicKey> {
+        (0..count)
+            .map(|_| {
+                let key = RsaPrivateKey::generate(rng, bits, &BigUint::from(e)).unwrap();
+                key.public_key().clone()
+            })
+            .collect()
+    }
+
+    fn broadcast(keys: &[RsaPublicKey], m: &BigUint) -> Vec<BroadcastCiphertext> {
+        keys.iter()
+            .map(|key| BroadcastCiphertext {
+                n: key.n().clone(),
+                c: key.encrypt(m).unwrap(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn chinese_remainder_errors() {
+        assert_eq!(chinese_remainder(&[]), Err(HastadError::NoCiphertexts));
+
+        let congruences = [(1u64, 5u64), (0, 0)].map(|(r, n)| (BigUint::from(r), BigUint::from(n)));
+        assert_eq!(
+            chinese_remainder(&congruences),
+            Err(HastadError::ZeroModulus)
+        );
+
+        let congruences = [(1u64, 6u64), (2, 9)].map(|(r, n)| (BigUint::from(r), BigUint::from(n)));
+        assert_eq!(
+            chinese_remainder(&congruences),
+            Err(HastadError::ModuliNotCoprime)
+        );
+    }
+
+    #[test]
+    fn hastad_invalid_exponent() {
+        let ciphertexts = [BroadcastCiphertext {
+            n: BigUint::from(35u64),
+            c: BigUint::from(2u64),
+        }];
+        let result = hastad_broadcast(0, &ciphertexts);
+        assert_eq!(result, Err(HastadError::InvalidExponent));
+
+        let ciphertexts = [PaddedBroadcastCiphertext {
+            n: BigUint::from(35u64),
+            c: BigUint::from(2u64),
+            a: BigUint::from(3u64),
+            b: BigUint::one(),
+        }];
+        let result = hastad_broadcast_linear(0, &ciphertexts);
+        assert_eq!(result, Err(HastadError::InvalidExponent));
+    }
+
+    #[test]
+    fn hastad_small_exponents() {
+        let mut rng = Mt19937::new(1);
+        for e in [3, 5, 7] {
+            let keys = keys(&mut rng, e as usize, 256, e);
+            let m = BigUint::random_bits(&mut rng, 250);
+
+            let result = hastad_broadcast(e as u32, &broadcast(&keys, &m));
+            assert_eq!(result, Ok(m.clone()));
+
+            // one ciphertext short
+            let result = hastad_broadcast(e as u32, &broadcast(&keys[1..], &m));
+            assert_eq!(result, Err(HastadError::NoIntegerRoot));
+        }
+    }
+
+    #[test]
+    fn hastad_short_message() {
+        let mut rng = Mt19937::new(2);
+        let keys = keys(&mut rng, 1, 512, 3);
+
+        // m^3 < n, no reduction happens
+        let m = BigUint::from_be_bytes(b"short message");
+        assert_eq!(hastad_broadcast(3, &broadcast(&keys, &m)), Ok(m));
+    }
+
+    fn pad(
+        keys: &[RsaPublicKey],
+        m: &BigUint,
+        paddings: &[(BigUint, BigUint)],
+    ) -> Vec<PaddedBroadcastCiphertext> {
+        keys.iter()
+            .zip(paddings)
+            .map(|(key, (a, b))| PaddedBroadcastCiphertext {
+                n: key.n().clone(),
+                c: key.encrypt(&((a * m + b) % key.n())).unwrap(),
+                a: a.clone(),
+                b: b.clone(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn hastad_same_padding() {
+        let mut rng = Mt19937::new(3);
+        let keys = keys(&mut rng, 3, 512, 3);
+        let m = BigUint::random_bits(&mut rng, 400);
+
+        // a fixed header before the message and a fixed trailer of zeros
+        let padding = (BigUint::one() << 16, BigUint::from(0x4d53_4731u64) << 416);
+        let paddings = vec![padding; 3];
+        let result = hastad_broadcast_linear(3, &pad(&keys, &m, &paddings));
+        assert_eq!(result, Ok(m));
+    }
+
+    #[test]
+    fn hastad_multiplicative_padding() {
+        let mut rng = Mt19937::new(4);
+        let keys = keys(&mut rng, 3, 512, 3);
+        let m = BigUint::random_bits(&mut rng, 500);
+
+        // a different large multiplier per recipient
+        let paddings: Vec<_> = keys
+            .iter()
+            .map(|key| (BigUint::random_below(&mut rng, key.n()), BigUint::zero()))
+            .collect();
+        let result = hastad_broadcast_linear(3, &pad(&keys, &m, &paddings));
+        assert_eq!(result, Ok(m));
+    }
+
+    #[test]
+    fn hastad_unsupported_padding() {
+        let mut rng = Mt19937::new(5);
+        let keys = keys(&mut rng, 3, 512, 3);
+        let m = BigUint::random_bits(&mut rng, 400);
+
+        // a per-recipient prefix
+        let paddings: Vec<_> = (1..=3u64)
+            .map(|i| (BigUint::one(), BigUint::from(i) << 450))
+            .collect();
+        let result = hastad_broadcast_linear(3, &pad(&keys, &m, &paddings));
+        assert_eq!(result, Err(HastadError::UnsupportedPadding));
+
+        let mut paddings = vec![(BigUint::from(2u64), BigUint::zero()); 3];
+        paddings[0].0 = keys[0].n().clone();
+        let result = hastad_broadcast_linear(3, &pad(&keys, &m, &paddings));
+        assert_eq!(result, Err(HastadError::NonInvertiblePadding));
+
+        // the same padding everywhere, with a = 0
+        let paddings = vec![(BigUint::zero(), BigUint::from(2u64)); 3];
+        let result = hastad_broadcast_linear(3, &pad(&keys, &m, &paddings));
+        assert_eq!(result, Err(HastadError::NonInvertiblePadding));
+    }
+}
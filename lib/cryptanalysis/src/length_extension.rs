@@ -0,0 +1,209 @@
+use std::ops::RangeInclusive;
+
+use primitives::MerkleDamgard;
+
+use crate::LengthExtensionError;
+
+/// A message forged by a length extension attack, assuming a given key length
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LengthExtension {
+    /// The key length this forgery assumes
+    pub key_length: usize,
+
+    /// The original message, its glue padding and the appended suffix
+    pub message: Vec<u8>,
+
+    /// The MAC of `message` under the unknown key
+    pub mac: Vec<u8>,
+}
+
+/// Forges secret-prefix MACs `H(key || message)` for an extended message with a length
+/// extension attack.
+///
+/// The MAC is the chaining value of `H` after processing `key || message || padding`, so the
+/// hash can be resumed from it to append a suffix. The glue padding depends on the key length,
+/// hence one forgery is returned per candidate key length.
+///
+/// # Args
+/// `mac`: the MAC of `message`
+///
+/// `message`: the message authenticated by `mac`
+///
+/// `suffix`: the data to append
+///
+/// `key_lengths`: the candidate key lengths
+///
+/// # Returns
+/// A `Result` wrapping a `Vec<LengthExtension>` with one forgery per key length or a
+/// `LengthExtensionError` if the MAC is not a digest of `H`
+///
+/// # Examples
+/// ```
+/// use cryptanalysis::length_extend;
+/// use primitives::Sha1;
+///
+/// let key = "YELLOW SUBMARINE".as_bytes();
+/// let message = "comment1=cooking%20MCs;userdata=foo".as_bytes();
+/// let mac = Sha1::digest(&[key, message].concat());
+///
+/// let forgeries = length_extend::<Sha1>(&mac, message, ";admin=true".as_bytes(), 16..=16).unwrap();
+///
+/// let forgery = &forgeries[0];
+/// assert!(forgery.message.ends_with(";admin=true".as_bytes()));
+/// assert_eq!(forgery.mac, Sha1::digest(&[key, &forgery.message].concat()));
+/// ```
+pub fn length_extend<H: MerkleDamgard>(
+    mac: &[u8],
+    message: &[u8],
+    suffix: &[u8],
+    key_lengths: RangeInclusive<usize>,
+) -> Result<Vec<LengthExtension>, LengthExtensionError> {
+    if mac.len() != H::OUTPUT_SIZE {
+        return Err(LengthExtensionError::InvalidMacSize);
+    }
+
+    let forgeries = key_lengths
+        .map(|key_length| {
+            let length = (key_length + message.len()) as u64;
+            let padding = H::padding(length);
+
+            let mut hasher = H::from_digest(mac, length + padding.len() as u64);
+            hasher.update(suffix);
+
+            LengthExtension {
+                key_length,
+                message: [message, &padding, suffix].concat(),
+                mac: hasher.finalize(),
+            }
+        })
+        .collect();
+
+    Ok(forgeries)
+}
+
+/// Forges a secret-prefix MAC for an extended message like `length_extend` and returns the
+/// first forgery accepted by a verifier.
+///
+/// # Args
+/// `mac`: the MAC of `message`
+///
+/// `message`: the message authenticated by `mac`
+///
+/// `suffix`: the data to append
+///
+/// `key_lengths`: the candidate key lengths
+///
+/// `verifier`: a closure that takes a message and a MAC and returns whether the MAC is valid
+///
+/// # Returns
+/// A `Result` wrapping the accepted `LengthExtension`, or `None` if the verifier rejected all
+/// of them, or a `LengthExtensionError` if the MAC is not a digest of `H`
+pub fn length_extend_verified<H, F>(
+    mac: &[u8],
+    message: &[u8],
+    suffix: &[u8],
+    key_lengths: RangeInclusive<usize>,
+    verifier: F,
+) -> Result<Option<LengthExtension>, LengthExtensionError>
+where
+    H: MerkleDamgard,
+    F: Fn(&[u8], &[u8]) -> bool,
+{
+    let forgeries = length_extend::<H>(mac, message, suffix, key_lengths)?;
+
+    Ok(forgeries
+        .into_iter()
+        .find(|forgery| verifier(&forgery.message, &forgery.mac)))
+}
+
+#[cfg(test)]
+mod tests {
+    use primitives::{Hmac, Mac, Md4, SecretPrefixMac, Sha1, Sha256, Sha512};
+
+    use super::*;
+
+    static KEY: &[u8] = b"a secret key of unknown length";
+    static MESSAGE: &[u8] =
+        b"comment1=cooking%20MCs;userdata=foo;comment2=%20like%20a%20pound%20of%20bacon";
+    static SUFFIX: &[u8] = b";admin=true";
+
+    fn forge_and_verify<H: MerkleDamgard>() {
+        let mac = H::digest(&[KEY, MESSAGE].concat());
+        let verifier = |message: &[u8], mac: &[u8]| H::digest(&[KEY, message].concat()) == mac;
+
+        let result = length_extend_verified::<H, _>(&mac, MESSAGE, SUFFIX, 0..=64, verifier)
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(result.key_length, KEY.len());
+        assert!(result.message.starts_with(MESSAGE));
+        assert!(result.message.ends_with(SUFFIX));
+    }
+
+    #[test]
+    fn length_extend_sha1() {
+        forge_and_verify::<Sha1>();
+    }
+
+    #[test]
+    fn length_extend_md4() {
+        forge_and_verify::<Md4>();
+    }
+
+    #[test]
+    fn length_extend_sha256() {
+        forge_and_verify::<Sha256>();
+    }
+
+    #[test]
+    fn length_extend_sha512() {
+        forge_and_verify::<Sha512>();
+    }
+
+    #[test]
+    fn length_extend_one_candidate_per_key_length() {
+        let mac = Sha1::digest(&[KEY, MESSAGE].concat());
+
+        let result = length_extend::<Sha1>(&mac, MESSAGE, SUFFIX, 10..=40).unwrap();
+
+        assert_eq!(result.len(), 31);
+        let forgery = result.iter().find(|f| f.key_length == KEY.len()).unwrap();
+        assert_eq!(forgery.mac, Sha1::digest(&[KEY, &forgery.message].concat()));
+    }
+
+    #[test]
+    fn length_extend_rejects_truncated_mac() {
+        let mac = Sha256::digest(&[KEY, MESSAGE].concat());
+
+        let result = length_extend::<Sha256>(&mac[..28], MESSAGE, SUFFIX, 0..=64);
+        let expected = Err(LengthExtensionError::InvalidMacSize);
+        assert_eq!(result, expected);
+    }
+
+    /// Runs a length extension attack against the MAC `M` built on `H` and returns whether
+    /// any forgery was accepted
+    fn length_extension_breaks<M: Mac, H: MerkleDamgard>() -> bool {
+        let tag = M::mac(KEY, MESSAGE);
+        let verifier = |message: &[u8], tag: &[u8]| M::verify(KEY, message, tag);
+
+        length_extend_verified::<H, _>(&tag, MESSAGE, SUFFIX, 0..=64, verifier)
+            .unwrap()
+            .is_some()
+    }
+
+    #[test]
+    fn length_extension_breaks_secret_prefix_mac() {
+        assert!(length_extension_breaks::<SecretPrefixMac<Sha1>, Sha1>());
+        assert!(length_extension_breaks::<SecretPrefixMac<Md4>, Md4>());
+        assert!(length_extension_breaks::<SecretPrefixMac<Sha256>, Sha256>());
+        assert!(length_extension_breaks::<SecretPrefixMac<Sha512>, Sha512>());
+    }
+
+    #[test]
+    fn length_extension_does_not_break_hmac() {
+        assert!(!length_extension_breaks::<Hmac<Sha1>, Sha1>());
+        assert!(!length_extension_breaks::<Hmac<Md4>, Md4>());
+        assert!(!length_extension_breaks::<Hmac<Sha256>, Sha256>());
+        assert!(!length_extension_breaks::<Hmac<Sha512>, Sha512>());
+    }
+}
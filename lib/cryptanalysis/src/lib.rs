@@ -1,5 +1,32 @@
 use std::collections::HashSet;
 
+mod crib_drag;
+pub use crib_drag::*;
+
+mod dictionary;
+pub use dictionary::*;
+
+mod error;
+pub use error::*;
+
+mod fixed_nonce_ctr;
+pub use fixed_nonce_ctr::*;
+
+mod hastad;
+pub use hastad::*;
+
+mod length_extension;
+pub use length_extension::*;
+
+mod mt19937;
+pub use mt19937::*;
+
+mod padding_oracle;
+pub use padding_oracle::*;
+
+mod timing;
+pub use timing::*;
+
 mod utils;
 
 use primitives::xor;
@@ -69,7 +96,7 @@ pub fn break_single_byte_xor(text: &[u8], lang: &str) -> Vec<(u8, usize)> {
         key_scores.push((key, key_score));
     }
 
-    key_scores.sort_by(|a, b| b.1.cmp(&a.1));
+    key_scores.sort_by_key(|k| std::cmp::Reverse(k.1));
 
     key_scores
 }
@@ -0,0 +1,556 @@
+use std::ops::RangeInclusive;
+use std::thread;
+
+use primitives::{generate_reset_token, temper, xor, Mt19937, Mt19937Cipher};
+
+use crate::Mt19937RecoveryError;
+
+const N: usize = 624;
+const M: usize = 397;
+const W: usize = 32;
+const MATRIX_A: u32 = 0x9908B0DF;
+
+/// Known bits of one MT19937 output
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Mt19937Observation {
+    /// The position of the output in the stream, counting from the first output to recover
+    pub index: usize,
+
+    /// The bits of the output that were observed
+    pub mask: u32,
+
+    /// The value of the output (only the bits set in `mask` are used)
+    pub value: u32,
+}
+
+impl Mt19937Observation {
+    /// Creates an observation of a whole output
+    pub fn full(index: usize, value: u32) -> Self {
+        Mt19937Observation {
+            index,
+            mask: u32::MAX,
+            value,
+        }
+    }
+}
+
+/// Recovers the internal state of a MT19937 generator from partial observations of its
+/// outputs.
+///
+/// Both the twist and the tempering are linear over GF(2), so every observed bit is a linear
+/// equation in the 19968 bits of the state at the first output. The outputs are computed
+/// symbolically and the resulting system is solved with Gaussian elimination, which works for
+/// non-consecutive outputs and for outputs where only some bits are known (e.g. the high bits
+/// or the low byte of each output), as long as there are enough of them.
+///
+/// # Args
+/// `observations`: the known bits of the outputs
+///
+/// # Returns
+/// A `Result` wrapping a `Mt19937` whose next output is the output at index 0 or a
+/// `Mt19937RecoveryError` if the state could not be recovered
+pub fn recover_mt19937_state(
+    observations: &[Mt19937Observation],
+) -> Result<Mt19937, Mt19937RecoveryError> {
+    let mut observations = observations.to_vec();
+    observations.sort_by_key(|o| o.index);
+
+    // the outputs of the generator are tempered words of the state
+    let temper_columns: Vec<u32> = (0..W).map(|i| temper(1 << i)).collect();
+
+    // state[k][i] holds bit i of the state word at slot k as an equation over the initial
+    // state bits
+    let mut state: Vec<Vec<Equation>> = (0..N)
+        .map(|k| (0..W).map(|i| Equation::variable(k * W + i)).collect())
+        .collect();
+
+    let mut system = System::new(N * W);
+    let mut observations = observations.iter().peekable();
+    let mut k = 0;
+
+    while let Some(observation) = observations.peek() {
+        if observation.index > k {
+            k += 1;
+            if k >= N {
+                twist_word(&mut state, k % N);
+            }
+            continue;
+        }
+
+        let slot = k % N;
+        for j in (0..W).filter(|j| observation.mask >> j & 1 == 1) {
+            let mut equation = Equation::default();
+            for (i, column) in temper_columns.iter().enumerate() {
+                if column >> j & 1 == 1 {
+                    equation.xor_assign(&state[slot][i]);
+                }
+            }
+            equation.constant ^= observation.value >> j & 1 == 1;
+            system.insert(equation)?;
+        }
+
+        observations.next();
+    }
+
+    let bits = system.solve()?;
+
+    let mut words = [0u32; N];
+    for (k, word) in words.iter_mut().enumerate() {
+        for i in 0..W {
+            *word |= (bits[k * W + i] as u32) << i;
+        }
+    }
+
+    Ok(Mt19937::from_state(words, 0))
+}
+
+/// A seed that reproduces the observed outputs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SeedCandidate {
+    /// The seed given to `Mt19937::new`
+    pub seed: u32,
+
+    /// The position of the first observed output in the stream generated by `seed`
+    pub position: usize,
+}
+
+/// Brute-forces the seed of a MT19937 generator seeded with a value from a known range
+/// (typically a window of UNIX timestamps). The search is split across all available cores.
+///
+/// # Args
+/// `outputs`: one or more consecutive outputs of the generator
+///
+/// `seeds`: the range of seeds to try
+///
+/// `max_offset`: the maximum number of outputs that may have been drawn before the first
+/// observed one
+///
+/// # Returns
+/// A `Vec<SeedCandidate>` with every seed and position that reproduce the outputs, sorted by
+/// seed
+pub fn crack_mt19937_seed(
+    outputs: &[u32],
+    seeds: RangeInclusive<u32>,
+    max_offset: usize,
+) -> Vec<SeedCandidate> {
+    if outputs.is_empty() || seeds.is_empty() {
+        return vec![];
+    }
+
+    let n_threads = thread::available_parallelism().map_or(1, |n| n.get()) as u64;
+    let start = *seeds.start() as u64;
+    let end = *seeds.end() as u64;
+    let chunk_size = (end - start + 1).div_ceil(n_threads);
+
+    thread::scope(|scope| {
+        let handles: Vec<_> = (start..=end)
+            .step_by(chunk_size as usize)
+            .map(|chunk_start| {
+                let chunk_end = (chunk_start + chunk_size - 1).min(end);
+                scope.spawn(move || {
+                    (chunk_start..=chunk_end)
+                        .flat_map(|seed| find_outputs(seed as u32, outputs, max_offset))
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().unwrap())
+            .collect()
+    })
+}
+
+/// Returns the positions where the given outputs appear in the stream generated by `seed`
+fn find_outputs(seed: u32, outputs: &[u32], max_offset: usize) -> Vec<SeedCandidate> {
+    let mut rng = Mt19937::new(seed);
+    let stream: Vec<u32> = (0..max_offset + outputs.len())
+        .map(|_| rng.next_u32())
+        .collect();
+
+    stream
+        .windows(outputs.len())
+        .enumerate()
+        .filter(|(_, window)| *window == outputs)
+        .map(|(position, _)| SeedCandidate { seed, position })
+        .collect()
+}
+
+/// Recovers the 16 bit key of a `Mt19937Cipher` from a ciphertext whose plaintext ends with a
+/// known suffix, by trying all the 65536 keys
+///
+/// # Args
+/// `ciphertext`: the target ciphertext
+///
+/// `known_suffix`: the known last bytes of the plaintext
+///
+/// # Returns
+/// A `Vec<u16>` with every key whose keystream turns the end of the ciphertext into the suffix
+pub fn recover_mt19937_cipher_key(ciphertext: &[u8], known_suffix: &[u8]) -> Vec<u16> {
+    if known_suffix.is_empty() || known_suffix.len() > ciphertext.len() {
+        return vec![];
+    }
+
+    let offset = ciphertext.len() - known_suffix.len();
+    let expected_keystream = xor(&ciphertext[offset..], known_suffix);
+
+    (0..=u16::MAX)
+        .filter(|&key| {
+            Mt19937Cipher::new(key).keystream(ciphertext.len())[offset..] == expected_keystream
+        })
+        .collect()
+}
+
+/// Tells whether a token was generated by `generate_reset_token` with a timestamp close to
+/// the current time
+///
+/// # Args
+/// `token`: the token to test
+///
+/// `now`: the current UNIX timestamp
+///
+/// `window`: how many seconds back in time to look
+///
+/// # Returns
+/// The timestamp used as seed or `None` if the token was not generated in the time window
+pub fn detect_time_seeded_token(token: &[u8], now: u32, window: u32) -> Option<u32> {
+    (now.saturating_sub(window)..=now)
+        .rev()
+        .find(|&timestamp| generate_reset_token(timestamp) == token)
+}
+
+/// Symbolically replaces the word at the given slot with its next generation value
+fn twist_word(state: &mut [Vec<Equation>], slot: usize) {
+    let next = (slot + 1) % N;
+    let far = (slot + M) % N;
+
+    // y is made of the upper bit of the current word and the lower bits of the next one
+    let y = |i: usize| -> &Equation {
+        if i == W - 1 {
+            &state[slot][i]
+        } else {
+            &state[next][i]
+        }
+    };
+
+    let mut word = Vec::with_capacity(W);
+    for (j, far_bit) in state[far].iter().enumerate() {
+        let mut bit = far_bit.clone();
+        if j < W - 1 {
+            bit.xor_assign(y(j + 1));
+        }
+        if MATRIX_A >> j & 1 == 1 {
+            bit.xor_assign(y(0));
+        }
+        word.push(bit);
+    }
+
+    state[slot] = word;
+}
+
+/// A linear equation over GF(2). The coefficients are stored as a bitset that only spans the
+/// words between the first and the last non-zero one
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct Equation {
+    lo: usize,
+    words: Vec<u64>,
+    constant: bool,
+}
+
+impl Equation {
+    fn variable(var: usize) -> Self {
+        Equation {
+            lo: var / 64,
+            words: vec![1 << (var % 64)],
+            constant: false,
+        }
+    }
+
+    fn xor_assign(&mut self, other: &Equation) {
+        self.constant ^= other.constant;
+
+        if other.words.is_empty() {
+            return;
+        }
+        if self.words.is_empty() {
+            self.lo = other.lo;
+            self.words = other.words.clone();
+            return;
+        }
+
+        let lo = self.lo.min(other.lo);
+        let hi = (self.lo + self.words.len()).max(other.lo + other.words.len());
+
+        if lo < self.lo {
+            let mut words = vec![0; self.lo - lo];
+            words.append(&mut self.words);
+            self.words = words;
+            self.lo = lo;
+        }
+        self.words.resize(hi - lo, 0);
+
+        let offset = other.lo - lo;
+        for (w, o) in self.words[offset..].iter_mut().zip(&other.words) {
+            *w ^= o;
+        }
+
+        self.trim();
+    }
+
+    fn trim(&mut self) {
+        let leading = self.words.iter().take_while(|&&w| w == 0).count();
+        if leading == self.words.len() {
+            self.words.clear();
+            self.lo = 0;
+            return;
+        }
+
+        self.words.drain(..leading);
+        self.lo += leading;
+
+        while self.words.last() == Some(&0) {
+            self.words.pop();
+        }
+    }
+
+    /// Returns the lowest variable with a non-zero coefficient
+    fn lowest_variable(&self) -> Option<usize> {
+        self.words
+            .first()
+            .map(|w| self.lo * 64 + w.trailing_zeros() as usize)
+    }
+
+    fn variables(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words.iter().enumerate().flat_map(move |(i, &w)| {
+            (0..64)
+                .filter(move |b| w >> b & 1 == 1)
+                .map(move |b| (self.lo + i) * 64 + b)
+        })
+    }
+}
+
+/// A system of linear equations over GF(2) kept in echelon form
+struct System {
+    pivots: Vec<Option<Equation>>,
+}
+
+impl System {
+    fn new(n_variables: usize) -> Self {
+        System {
+            pivots: vec![None; n_variables],
+        }
+    }
+
+    fn insert(&mut self, mut equation: Equation) -> Result<(), Mt19937RecoveryError> {
+        while let Some(var) = equation.lowest_variable() {
+            match &self.pivots[var] {
+                Some(pivot) => equation.xor_assign(pivot),
+                None => {
+                    self.pivots[var] = Some(equation);
+                    return Ok(());
+                }
+            }
+        }
+
+        // the equation was a combination of the previous ones
+        match equation.constant {
+            true => Err(Mt19937RecoveryError::Inconsistent),
+            false => Ok(()),
+        }
+    }
+
+    fn solve(&self) -> Result<Vec<bool>, Mt19937RecoveryError> {
+        let mut values = vec![false; self.pivots.len()];
+
+        for var in (0..self.pivots.len()).rev() {
+            match &self.pivots[var] {
+                Some(pivot) => {
+                    values[var] = pivot
+                        .variables()
+                        .filter(|&v| v != var)
+                        .fold(pivot.constant, |acc, v| acc ^ values[v]);
+                }
+                // only the upper bit of the first word takes part in the twist, so the lower
+                // bits are free unless the first output was observed
+                None if var < W - 1 => {}
+                None => return Err(Mt19937RecoveryError::Underdetermined),
+            }
+        }
+
+        Ok(values)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use primitives::StreamCipher;
+
+    use super::*;
+
+    fn outputs(n: usize) -> (Mt19937, Vec<u32>) {
+        let mut rng = Mt19937::new(0xC0FFEE);
+        for _ in 0..1000 {
+            rng.next_u32();
+        }
+        let expected = rng.clone();
+        (expected, (0..n).map(|_| rng.next_u32()).collect())
+    }
+
+    #[test]
+    fn recover_state_from_full_outputs() {
+        let (mut expected, outputs) = outputs(N);
+        let observations: Vec<_> = outputs
+            .iter()
+            .enumerate()
+            .map(|(i, &o)| Mt19937Observation::full(i, o))
+            .collect();
+
+        let mut result = recover_mt19937_state(&observations).unwrap();
+        for _ in 0..2000 {
+            assert_eq!(result.next_u32(), expected.next_u32());
+        }
+    }
+
+    #[test]
+    fn recover_state_from_non_consecutive_outputs() {
+        let (mut expected, outputs) = outputs(1700);
+        let observations: Vec<_> = outputs
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| i % 3 != 1)
+            .map(|(i, &o)| Mt19937Observation::full(i, o))
+            .collect();
+
+        let mut result = recover_mt19937_state(&observations).unwrap();
+        for _ in 0..2000 {
+            assert_eq!(result.next_u32(), expected.next_u32());
+        }
+    }
+
+    #[test]
+    fn recover_state_from_high_bits() {
+        let (mut expected, outputs) = outputs(1400);
+        let observations: Vec<_> = outputs
+            .iter()
+            .enumerate()
+            .map(|(index, &o)| Mt19937Observation {
+                index,
+                mask: 0xFFFF0000,
+                value: o,
+            })
+            .collect();
+
+        // the lower bits of the first word are only constrained by the first output
+        let mut result = recover_mt19937_state(&observations).unwrap();
+        result.next_u32();
+        expected.next_u32();
+        for _ in 0..2000 {
+            assert_eq!(result.next_u32(), expected.next_u32());
+        }
+    }
+
+    #[test]
+    fn recover_state_from_low_bytes() {
+        let (mut expected, outputs) = outputs(4000);
+        let observations: Vec<_> = outputs
+            .iter()
+            .enumerate()
+            .map(|(index, &o)| Mt19937Observation {
+                index,
+                mask: 0xFF,
+                value: o,
+            })
+            .collect();
+
+        // the lower bits of the first word are only constrained by the first output
+        let mut result = recover_mt19937_state(&observations).unwrap();
+        result.next_u32();
+        expected.next_u32();
+        for _ in 0..2000 {
+            assert_eq!(result.next_u32(), expected.next_u32());
+        }
+    }
+
+    #[test]
+    fn crack_seed_first_output() {
+        let seed = 1_700_000_123;
+        let mut rng = Mt19937::new(seed);
+        let output = rng.next_u32();
+
+        let result = crack_mt19937_seed(&[output], 1_700_000_000..=1_700_001_000, 0);
+        let expected = vec![SeedCandidate { seed, position: 0 }];
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn crack_seed_with_offset() {
+        let seed = 1_700_000_777;
+        let mut rng = Mt19937::new(seed);
+        for _ in 0..37 {
+            rng.next_u32();
+        }
+        let outputs = [rng.next_u32(), rng.next_u32()];
+
+        let result = crack_mt19937_seed(&outputs, 1_700_000_000..=1_700_001_000, 50);
+        let expected = vec![SeedCandidate { seed, position: 37 }];
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn crack_seed_outside_window() {
+        let mut rng = Mt19937::new(42);
+        let output = rng.next_u32();
+
+        let result = crack_mt19937_seed(&[output], 1_700_000_000..=1_700_001_000, 10);
+        assert_eq!(result, vec![]);
+    }
+
+    #[test]
+    fn recover_cipher_key_known_suffix() {
+        let key = 0xA51C;
+        let mut text = "q#9x!L0".as_bytes().to_vec();
+        text.extend("AAAAAAAAAAAAAA".as_bytes());
+        Mt19937Cipher::new(key).apply_keystream(&mut text);
+
+        let result = recover_mt19937_cipher_key(&text, "AAAAAAAAAAAAAA".as_bytes());
+        assert_eq!(result, vec![key]);
+    }
+
+    #[test]
+    fn detect_time_seeded_token_recent() {
+        let now = 1_700_000_000;
+        let token = generate_reset_token(now - 120);
+
+        let result = detect_time_seeded_token(&token, now, 3600);
+        assert_eq!(result, Some(now - 120));
+    }
+
+    #[test]
+    fn detect_time_seeded_token_random() {
+        let token = [
+            0x3B, 0x7E, 0x01, 0xD2, 0x99, 0x4A, 0xC0, 0x5F, 0x18, 0xE7, 0x6D, 0x23, 0xA4, 0x80,
+            0x0C, 0xF1,
+        ];
+
+        let result = detect_time_seeded_token(&token, 1_700_000_000, 3600);
+        assert_eq!(result, None);
+    }
+
+    #[test]
+    fn recover_state_not_enough_outputs() {
+        let (_, outputs) = outputs(N);
+        let observations: Vec<_> = outputs
+            .iter()
+            .enumerate()
+            .map(|(index, &o)| Mt19937Observation {
+                index,
+                mask: 0xFFFF0000,
+                value: o,
+            })
+            .collect();
+
+        let result = recover_mt19937_state(&observations);
+        assert_eq!(result.err(), Some(Mt19937RecoveryError::Underdetermined));
+    }
+}
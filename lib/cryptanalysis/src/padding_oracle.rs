@@ -0,0 +1,244 @@
+use primitives::{pad_pkcs7, xor};
+
+use crate::PaddingOracleError;
+
+/// A CBC padding oracle attack engine.
+///
+/// Wraps an oracle `Fn(iv, ciphertext) -> bool` that tells whether the given ciphertext
+/// decrypts to a plaintext with valid PKCS#7 padding, and uses it to decrypt arbitrary
+/// ciphertexts or to forge ciphertexts for chosen plaintexts without knowing the key.
+pub struct PaddingOracleAttack<F>
+where
+    F: Fn(&[u8], &[u8]) -> bool,
+{
+    oracle: F,
+    block_size: usize,
+    queries: usize,
+}
+
+impl<F> PaddingOracleAttack<F>
+where
+    F: Fn(&[u8], &[u8]) -> bool,
+{
+    /// Creates a new `PaddingOracleAttack`
+    ///
+    /// # Args
+    /// `oracle`: a function that receives an IV and a ciphertext and returns `true` if the
+    /// decrypted plaintext is correctly padded
+    ///
+    /// `block_size`: the block size of the underlying cipher
+    ///
+    /// # Returns
+    /// A `Result` wrapping the created `PaddingOracleAttack` or a `PaddingOracleError` if the
+    /// block size is not supported
+    pub fn new(oracle: F, block_size: usize) -> Result<Self, PaddingOracleError> {
+        if !(2..=255).contains(&block_size) {
+            return Err(PaddingOracleError::InvalidBlockSize);
+        }
+
+        Ok(PaddingOracleAttack {
+            oracle,
+            block_size,
+            queries: 0,
+        })
+    }
+
+    /// Returns the number of oracle queries issued so far
+    pub fn queries(&self) -> usize {
+        self.queries
+    }
+
+    /// Decrypts the given ciphertext block by block
+    ///
+    /// # Args
+    /// `iv`: the initialization vector used to encrypt the ciphertext
+    ///
+    /// `ciphertext`: the ciphertext to decrypt
+    ///
+    /// # Returns
+    /// A `Result` wrapping the recovered plaintext (the padding is left untouched) or a
+    /// `PaddingOracleError` in case of failure
+    pub fn decrypt(&mut self, iv: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>, PaddingOracleError> {
+        if iv.len() != self.block_size {
+            return Err(PaddingOracleError::InvalidIvSize);
+        }
+        if ciphertext.is_empty() || !ciphertext.len().is_multiple_of(self.block_size) {
+            return Err(PaddingOracleError::InvalidCiphertextSize);
+        }
+
+        let mut plaintext = Vec::with_capacity(ciphertext.len());
+        let mut prev = iv;
+
+        for block in ciphertext.chunks(self.block_size) {
+            let intermediate = self.recover_intermediate(prev, block)?;
+            plaintext.extend(xor(&intermediate, prev));
+            prev = block;
+        }
+
+        Ok(plaintext)
+    }
+
+    /// Forges a ciphertext that decrypts to the given plaintext. The plaintext is padded
+    /// with PKCS#7 before being encrypted.
+    ///
+    /// # Args
+    /// `plaintext`: the chosen plaintext
+    ///
+    /// # Returns
+    /// A `Result` wrapping a tuple with the forged IV and ciphertext or a
+    /// `PaddingOracleError` in case of failure
+    pub fn encrypt(&mut self, plaintext: &[u8]) -> Result<(Vec<u8>, Vec<u8>), PaddingOracleError> {
+        let plaintext = pad_pkcs7(plaintext, self.block_size)
+            .map_err(|_| PaddingOracleError::InvalidBlockSize)?;
+
+        // work backwards from an arbitrary last block: each previous block is chosen so that
+        // the intermediate state of the next one xors to the wanted plaintext block
+        let mut blocks = vec![vec![0; self.block_size]];
+        for plaintext_block in plaintext.chunks(self.block_size).rev() {
+            let zeros = vec![0; self.block_size];
+            let intermediate = self.recover_intermediate(&zeros, &blocks[0])?;
+            blocks.insert(0, xor(&intermediate, plaintext_block));
+        }
+
+        let iv = blocks.remove(0);
+        let ciphertext = blocks.into_iter().flatten().collect();
+
+        Ok((iv, ciphertext))
+    }
+
+    /// Recovers the intermediate state (the raw block cipher decryption) of `block`.
+    ///
+    /// `prev` is the block that precedes `block` in the ciphertext. It is used as the
+    /// starting point for the forged IV so that, for the last block, the original padding
+    /// is found among the candidates and must be told apart from the forged one.
+    fn recover_intermediate(
+        &mut self,
+        prev: &[u8],
+        block: &[u8],
+    ) -> Result<Vec<u8>, PaddingOracleError> {
+        let block_size = self.block_size;
+        let mut intermediate = vec![0u8; block_size];
+        let mut forged_iv = prev.to_vec();
+
+        for padding in 1..=block_size {
+            let pos = block_size - padding;
+
+            // the bytes after `pos` must decrypt to the current padding value
+            for i in pos + 1..block_size {
+                forged_iv[i] = intermediate[i] ^ padding as u8;
+            }
+
+            let mut found = None;
+            for guess in 0x00..=0xFF {
+                forged_iv[pos] = guess;
+                if !self.query(&forged_iv, block) {
+                    continue;
+                }
+
+                // a valid padding on the last byte may be a longer one (e.g. \x02\x02)
+                // in which case changing the byte before it invalidates the padding
+                if padding == 1 {
+                    forged_iv[pos - 1] ^= 0xFF;
+                    let is_single_byte_padding = self.query(&forged_iv, block);
+                    forged_iv[pos - 1] ^= 0xFF;
+                    if !is_single_byte_padding {
+                        continue;
+                    }
+                }
+
+                found = Some(guess);
+                break;
+            }
+
+            match found {
+                Some(guess) => intermediate[pos] = guess ^ padding as u8,
+                None => return Err(PaddingOracleError::NoValidPadding),
+            }
+        }
+
+        Ok(intermediate)
+    }
+
+    fn query(&mut self, iv: &[u8], ciphertext: &[u8]) -> bool {
+        self.queries += 1;
+        (self.oracle)(iv, ciphertext)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use aes::{Aes128, Mode, BLOCKSIZE};
+    use primitives::unpad_pkcs7;
+
+    use super::*;
+
+    static KEY: &str = "YELLOW SUBMARINE";
+    static IV: &str = "0123456789ABCDEF";
+
+    fn encrypt(plaintext: &[u8]) -> Vec<u8> {
+        let cipher = Aes128::new(
+            KEY.as_bytes().to_vec(),
+            Mode::CBC,
+            Some(IV.as_bytes().to_vec()),
+        )
+        .unwrap();
+        cipher
+            .encrypt(&pad_pkcs7(plaintext, BLOCKSIZE).unwrap())
+            .unwrap()
+    }
+
+    fn oracle(iv: &[u8], ciphertext: &[u8]) -> bool {
+        let cipher = Aes128::new(KEY.as_bytes().to_vec(), Mode::CBC, Some(iv.to_vec())).unwrap();
+        let plaintext = cipher.decrypt(ciphertext).unwrap();
+        unpad_pkcs7(&plaintext, BLOCKSIZE).is_ok()
+    }
+
+    #[test]
+    fn padding_oracle_decrypt() {
+        let plaintext = "000001With the bass kicked in and the Vega's are pumpin'".as_bytes();
+        let ciphertext = encrypt(plaintext);
+
+        let mut attack = PaddingOracleAttack::new(oracle, BLOCKSIZE).unwrap();
+        let result = attack.decrypt(IV.as_bytes(), &ciphertext).unwrap();
+        let expected = pad_pkcs7(plaintext, BLOCKSIZE).unwrap();
+
+        assert_eq!(result, expected);
+        assert!(attack.queries() > 0);
+        assert!(attack.queries() <= 4 * 256 * BLOCKSIZE);
+    }
+
+    #[test]
+    fn padding_oracle_decrypt_longer_valid_padding() {
+        // the original plaintext ends with \x02\x02, which is also a valid padding when
+        // guessing the last byte
+        let plaintext = "ICE ICE BABY, IC".as_bytes();
+        let ciphertext = encrypt(&plaintext[..14]);
+
+        let mut attack = PaddingOracleAttack::new(oracle, BLOCKSIZE).unwrap();
+        let result = attack.decrypt(IV.as_bytes(), &ciphertext).unwrap();
+        let expected = "ICE ICE BABY, \x02\x02".as_bytes();
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn padding_oracle_encrypt() {
+        let plaintext = "Cooking MC's like a pound of bacon".as_bytes();
+
+        let mut attack = PaddingOracleAttack::new(oracle, BLOCKSIZE).unwrap();
+        let (iv, ciphertext) = attack.encrypt(plaintext).unwrap();
+
+        let cipher = Aes128::new(KEY.as_bytes().to_vec(), Mode::CBC, Some(iv)).unwrap();
+        let result = unpad_pkcs7(&cipher.decrypt(&ciphertext).unwrap(), BLOCKSIZE).unwrap();
+
+        assert_eq!(result, plaintext);
+    }
+
+    #[test]
+    fn padding_oracle_invalid_ciphertext_size() {
+        let mut attack = PaddingOracleAttack::new(oracle, BLOCKSIZE).unwrap();
+        let result = attack.decrypt(IV.as_bytes(), &[0; 15]);
+        let expected = Err(PaddingOracleError::InvalidCiphertextSize);
+        assert_eq!(result, expected);
+    }
+}
@@ -0,0 +1,422 @@
+use std::time::Instant;
+
+/// How the repeated timing measurements of one candidate are combined into a single estimate
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Estimator {
+    /// The median of the measurements
+    Median,
+
+    /// The mean of the measurements after discarding the given fraction (between 0 and 0.5)
+    /// of the lowest and of the highest ones
+    TrimmedMean(f64),
+}
+
+impl Estimator {
+    /// Combines measurements into a single estimate
+    ///
+    /// # Panics
+    /// If `measurements` is empty
+    pub fn estimate(&self, measurements: &[f64]) -> f64 {
+        assert!(
+            !measurements.is_empty(),
+            "at least one measurement is required"
+        );
+
+        let mut sorted = measurements.to_vec();
+        sorted.sort_by(f64::total_cmp);
+
+        match *self {
+            Estimator::Median => {
+                let middle = sorted.len() / 2;
+                if sorted.len() % 2 == 1 {
+                    sorted[middle]
+                } else {
+                    (sorted[middle - 1] + sorted[middle]) / 2.0
+                }
+            }
+            Estimator::TrimmedMean(fraction) => {
+                let trimmed = (sorted.len() as f64 * fraction.clamp(0.0, 0.5)) as usize;
+                let kept = &sorted[trimmed..sorted.len() - trimmed];
+                if kept.is_empty() {
+                    return Estimator::Median.estimate(measurements);
+                }
+                kept.iter().sum::<f64>() / kept.len() as f64
+            }
+        }
+    }
+}
+
+/// A byte of a MAC recovered from timing measurements
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TimingGuess {
+    /// The value that took the longest to be rejected
+    pub byte: u8,
+
+    /// How much longer than a typical candidate it took, in seconds. It approximates the
+    /// per-byte leak when the known bytes are correct and is close to zero otherwise
+    pub leak: f64,
+}
+
+/// Recovers the next byte of a MAC from a verifier that leaks through its response time how
+/// many leading bytes of a candidate MAC are correct (e.g. an early-exit comparison).
+///
+/// Every candidate value for the byte is timed `samples` times, interleaving the candidates so
+/// that slow drifts of the system affect all of them alike, and the measurements are combined
+/// with `estimator`. The three slowest candidates are then timed again with four times as many
+/// samples to confirm the choice.
+///
+/// # Args
+/// `oracle`: a closure that takes a candidate MAC and returns whether it is valid
+///
+/// `known`: the bytes of the MAC recovered so far
+///
+/// `mac_size`: the size of the MAC in bytes
+///
+/// `samples`: the number of measurements per candidate
+///
+/// `estimator`: how to combine the measurements of each candidate
+///
+/// # Returns
+/// A `TimingGuess` for the byte following `known`
+///
+/// # Panics
+/// If `samples` is 0 or `known` is not shorter than `mac_size`
+pub fn recover_mac_byte_by_timing<F: FnMut(&[u8]) -> bool>(
+    oracle: &mut F,
+    known: &[u8],
+    mac_size: usize,
+    samples: usize,
+    estimator: Estimator,
+) -> TimingGuess {
+    let mut clock = system_clock();
+    recover_mac_byte_with_clock(oracle, &mut clock, known, mac_size, samples, estimator)
+}
+
+/// `recover_mac_byte_by_timing` with the time, in seconds, read from `clock`
+fn recover_mac_byte_with_clock<F, C>(
+    oracle: &mut F,
+    clock: &mut C,
+    known: &[u8],
+    mac_size: usize,
+    samples: usize,
+    estimator: Estimator,
+) -> TimingGuess
+where
+    F: FnMut(&[u8]) -> bool,
+    C: FnMut() -> f64,
+{
+    assert!(samples > 0, "at least one sample per candidate is required");
+    assert!(known.len() < mac_size, "the MAC is already known");
+
+    let mut mac = known.to_vec();
+    mac.resize(mac_size, 0);
+    let position = known.len();
+
+    let candidates: Vec<u8> = (0..=255).collect();
+    let estimates = time_candidates(
+        oracle,
+        clock,
+        &mut mac,
+        position,
+        &candidates,
+        samples,
+        estimator,
+    );
+    let baseline = Estimator::Median.estimate(&estimates);
+
+    let mut ranking: Vec<(u8, f64)> = candidates.into_iter().zip(estimates).collect();
+    ranking.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+    let finalists: Vec<u8> = ranking.iter().take(3).map(|&(byte, _)| byte).collect();
+    let estimates = time_candidates(
+        oracle,
+        clock,
+        &mut mac,
+        position,
+        &finalists,
+        4 * samples,
+        estimator,
+    );
+
+    let (byte, estimate) = finalists
+        .into_iter()
+        .zip(estimates)
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .unwrap();
+
+    TimingGuess {
+        byte,
+        leak: estimate - baseline,
+    }
+}
+
+/// Recovers a whole MAC from a verifier that leaks through its response time how many leading
+/// bytes of a candidate MAC are correct. Each byte but the last is found with
+/// `recover_mac_byte_by_timing` and the last one by checking which value the oracle accepts.
+///
+/// A wrong byte makes every following candidate fail at the same place, so the leak measured
+/// for the next byte vanishes. When it drops below half of the median leak of the known bytes,
+/// or when no value of the last byte is accepted, the previous byte is measured again.
+///
+/// # Args
+/// `oracle`: a closure that takes a candidate MAC and returns whether it is valid
+///
+/// `mac_size`: the size of the MAC in bytes
+///
+/// `samples`: the number of measurements per candidate
+///
+/// `estimator`: how to combine the measurements of each candidate
+///
+/// `progress`: a closure called with the known bytes every time they change
+///
+/// # Returns
+/// An `Option` wrapping the valid MAC, or `None` if `mac_size` or `samples` is 0 or if the
+/// MAC was not found after backtracking `2 * mac_size` times
+pub fn recover_mac_by_timing<F, P>(
+    oracle: F,
+    mac_size: usize,
+    samples: usize,
+    estimator: Estimator,
+    progress: P,
+) -> Option<Vec<u8>>
+where
+    F: FnMut(&[u8]) -> bool,
+    P: FnMut(&[u8]),
+{
+    recover_mac_with_clock(
+        oracle,
+        system_clock(),
+        mac_size,
+        samples,
+        estimator,
+        progress,
+    )
+}
+
+/// `recover_mac_by_timing` with the time, in seconds, read from `clock`
+fn recover_mac_with_clock<F, C, P>(
+    mut oracle: F,
+    mut clock: C,
+    mac_size: usize,
+    samples: usize,
+    estimator: Estimator,
+    mut progress: P,
+) -> Option<Vec<u8>>
+where
+    F: FnMut(&[u8]) -> bool,
+    C: FnMut() -> f64,
+    P: FnMut(&[u8]),
+{
+    if mac_size == 0 || samples == 0 {
+        return None;
+    }
+
+    let mut mac = Vec::with_capacity(mac_size);
+    let mut leaks = Vec::with_capacity(mac_size);
+    let mut backtracks = 0;
+
+    loop {
+        if mac.len() == mac_size - 1 {
+            mac.push(0);
+            for byte in 0..=255 {
+                mac[mac_size - 1] = byte;
+                if oracle(&mac) {
+                    return Some(mac);
+                }
+            }
+            mac.pop();
+        } else {
+            let guess = recover_mac_byte_with_clock(
+                &mut oracle,
+                &mut clock,
+                &mac,
+                mac_size,
+                samples,
+                estimator,
+            );
+            if mac.is_empty() || guess.leak >= Estimator::Median.estimate(&leaks) / 2.0 {
+                leaks.push(guess.leak);
+                mac.push(guess.byte);
+                progress(&mac);
+                continue;
+            }
+        }
+
+        backtracks += 1;
+        if backtracks > 2 * mac_size || mac.is_empty() {
+            return None;
+        }
+        mac.pop();
+        leaks.pop();
+        progress(&mac);
+    }
+}
+
+/// Returns a clock that reads the seconds elapsed since its creation
+fn system_clock() -> impl FnMut() -> f64 {
+    let origin = Instant::now();
+    move || origin.elapsed().as_secs_f64()
+}
+
+/// Times the oracle on `mac` with each candidate placed at `position` and returns one estimate
+/// per candidate
+fn time_candidates<F, C>(
+    oracle: &mut F,
+    clock: &mut C,
+    mac: &mut [u8],
+    position: usize,
+    candidates: &[u8],
+    samples: usize,
+    estimator: Estimator,
+) -> Vec<f64>
+where
+    F: FnMut(&[u8]) -> bool,
+    C: FnMut() -> f64,
+{
+    let mut measurements = vec![Vec::with_capacity(samples); candidates.len()];
+
+    for _ in 0..samples {
+        for (&candidate, times) in candidates.iter().zip(measurements.iter_mut()) {
+            mac[position] = candidate;
+            let start = clock();
+            oracle(mac);
+            times.push(clock() - start);
+        }
+    }
+
+    measurements
+        .iter()
+        .map(|times| estimator.estimate(times))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::{Cell, RefCell};
+
+    use primitives::Mt19937;
+
+    use super::*;
+
+    static MAC: [u8; 4] = [0xDE, 0xAD, 0xBE, 0xEF];
+
+    /// A simulated clock: the oracles advance it instead of sleeping, with some jitter so that
+    /// the measurements are noisy
+    struct FakeClock {
+        now: Cell<f64>,
+        jitter: RefCell<Mt19937>,
+    }
+
+    impl FakeClock {
+        fn new() -> FakeClock {
+            FakeClock {
+                now: Cell::new(0.0),
+                jitter: RefCell::new(Mt19937::new(1234)),
+            }
+        }
+
+        fn now(&self) -> f64 {
+            self.now.get()
+        }
+
+        /// Advances the clock by `seconds` plus up to 100µs of jitter
+        fn sleep(&self, seconds: f64) {
+            let jitter = self.jitter.borrow_mut().next_u32() as f64 / u32::MAX as f64 * 100e-6;
+            self.now.set(self.now.get() + seconds + jitter);
+        }
+    }
+
+    /// Compares byte by byte, sleeping after each matching byte
+    fn insecure_compare(candidate: &[u8], clock: &FakeClock) -> bool {
+        let mut matching = 0;
+        for (a, b) in candidate.iter().zip(MAC.iter()) {
+            if a != b {
+                break;
+            }
+            matching += 1;
+        }
+        clock.sleep(matching as f64 * 500e-6);
+        matching == MAC.len() && candidate.len() == MAC.len()
+    }
+
+    #[test]
+    fn estimators() {
+        let measurements = [5.0, 1.0, 100.0, 2.0, 3.0, 4.0];
+
+        assert_eq!(Estimator::Median.estimate(&measurements), 3.5);
+        assert_eq!(Estimator::TrimmedMean(0.2).estimate(&measurements), 3.5);
+        assert_eq!(Estimator::TrimmedMean(0.0).estimate(&[1.0, 2.0, 6.0]), 3.0);
+    }
+
+    #[test]
+    fn recover_mac_with_median() {
+        let clock = FakeClock::new();
+        let oracle = |mac: &[u8]| insecure_compare(mac, &clock);
+
+        let result = recover_mac_with_clock(
+            oracle,
+            || clock.now(),
+            MAC.len(),
+            3,
+            Estimator::Median,
+            |_| {},
+        );
+        let expected = Some(MAC.to_vec());
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn recover_mac_with_trimmed_mean() {
+        let clock = FakeClock::new();
+        let oracle = |mac: &[u8]| insecure_compare(mac, &clock);
+
+        let result = recover_mac_with_clock(
+            oracle,
+            || clock.now(),
+            MAC.len(),
+            5,
+            Estimator::TrimmedMean(0.2),
+            |_| {},
+        );
+        let expected = Some(MAC.to_vec());
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn recover_mac_backtracks_wrong_byte() {
+        let clock = FakeClock::new();
+        let mut skewed = true;
+        let mut progress = vec![];
+        // the second byte is skewed towards a wrong value until the third byte is measured
+        let oracle = |mac: &[u8]| {
+            if mac[..2] == [0xDE, 0x00] && mac[2] != 0 {
+                skewed = false;
+            }
+            if skewed && mac[..2] == [0xDE, 0x00] {
+                clock.sleep(2e-3);
+            }
+            insecure_compare(mac, &clock)
+        };
+
+        let result = recover_mac_with_clock(
+            oracle,
+            || clock.now(),
+            MAC.len(),
+            3,
+            Estimator::Median,
+            |known| progress.push(known.to_vec()),
+        );
+        let expected = Some(MAC.to_vec());
+        assert_eq!(result, expected);
+        assert!(progress.contains(&vec![0xDE, 0x00]));
+    }
+
+    #[test]
+    fn recover_mac_without_samples() {
+        let clock = FakeClock::new();
+        let oracle = |mac: &[u8]| insecure_compare(mac, &clock);
+
+        let result = recover_mac_by_timing(oracle, MAC.len(), 0, Estimator::Median, |_| {});
+        assert_eq!(result, None);
+    }
+}
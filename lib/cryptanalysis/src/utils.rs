@@ -75,6 +75,14 @@ pub fn rate_keysizes(text: &[u8], min_keysize: usize, max_keysize: usize) -> Vec
     keysize_hamming_dists
 }
 
+/// Counts the characters that are likely to appear in a text (letters, digits, space and
+/// common punctuation)
+pub fn count_printable(text: &[u8]) -> usize {
+    text.iter()
+        .filter(|u| u.is_ascii_alphanumeric() || b" ,.'!?;:-\"".contains(u))
+        .count()
+}
+
 fn is_alphabetic(c: u8) -> bool {
     (0x41..=0x5A).contains(&c) || (0x61..=0x7A).contains(&c)
 }
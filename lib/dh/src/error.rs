@@ -0,0 +1,36 @@
+use std::fmt;
+
+/// Errors that can occur while validating Diffie–Hellman parameters and public keys
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DhError {
+    /// The modulus is not an odd integer greater than 3
+    InvalidModulus,
+
+    /// The generator is not in `[2, p - 1]`
+    InvalidGenerator,
+
+    /// The order of the group is not in `[3, p - 1]`
+    InvalidOrder,
+
+    /// The public key is not in `[2, p - 2]`
+    PublicKeyOutOfRange,
+
+    /// The public key is not in the subgroup of order `q`
+    PublicKeyNotInSubgroup,
+}
+
+impl std::error::Error for DhError {}
+
+impl fmt::Display for DhError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DhError::InvalidModulus => "the modulus must be an odd integer greater than 3".fmt(f),
+            DhError::InvalidGenerator => "the generator must be in [2, p - 1]".fmt(f),
+            DhError::InvalidOrder => "the order of the group must be in [3, p - 1]".fmt(f),
+            DhError::PublicKeyOutOfRange => "the public key must be in [2, p - 2]".fmt(f),
+            DhError::PublicKeyNotInSubgroup => {
+                "the public key is not in the prime order subgroup".fmt(f)
+            }
+        }
+    }
+}
@@ -0,0 +1,377 @@
+mod error;
+pub use error::*;
+
+mod groups;
+pub use groups::*;
+
+use encoding::Decode;
+use primitives::{BigUint, Rng, Sha1, Sha256};
+
+/// The size of the AES keys derived from shared secrets
+pub static AES_KEY_SIZE: usize = 16;
+
+/// A multiplicative group modulo a prime `p` with generator `g`, optionally of known prime
+/// order `q`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DhGroup {
+    p: BigUint,
+    g: BigUint,
+    q: Option<BigUint>,
+}
+
+impl DhGroup {
+    /// Creates a new `DhGroup`
+    ///
+    /// # Args
+    /// `p`: the prime modulus
+    ///
+    /// `g`: the generator
+    ///
+    /// `q`: (optional) the order of `g`, which enables the subgroup check of public keys
+    ///
+    /// # Returns
+    /// A `Result` wrapping the group, or a `DhError` if `p` is not an odd integer greater than
+    /// 3, `g` is not in `[2, p - 1]` or `q` is not in `[3, p - 1]`. The primality of `p` and the
+    /// order of `g` are not checked
+    ///
+    /// # Examples
+    /// ```
+    /// use dh::{DhError, DhGroup};
+    /// use primitives::BigUint;
+    ///
+    /// let group = DhGroup::new(BigUint::from(37u64), BigUint::from(5u64), None);
+    /// assert!(group.is_ok());
+    ///
+    /// let group = DhGroup::new(BigUint::from(37u64), BigUint::one(), None);
+    /// assert_eq!(group, Err(DhError::InvalidGenerator));
+    /// ```
+    pub fn new(p: BigUint, g: BigUint, q: Option<BigUint>) -> Result<DhGroup, DhError> {
+        if p.is_even() || p <= BigUint::from(3u64) {
+            return Err(DhError::InvalidModulus);
+        }
+        if g <= BigUint::one() || g >= p {
+            return Err(DhError::InvalidGenerator);
+        }
+        if let Some(q) = &q {
+            if *q < BigUint::from(3u64) || *q >= p {
+                return Err(DhError::InvalidOrder);
+            }
+        }
+
+        Ok(DhGroup { p, g, q })
+    }
+
+    /// Creates a new `DhGroup` without any of the checks of `DhGroup::new`, e.g. to model a
+    /// party that accepts whatever group its peer proposes
+    ///
+    /// # Args
+    /// `p`: the modulus. It must be odd and greater than 3, or `DiffieHellman` panics
+    ///
+    /// `g`: the generator, which can be degenerate (e.g. 1 or `p`)
+    ///
+    /// `q`: (optional) the order of `g`, which must be at least 3 if given
+    ///
+    /// # Examples
+    /// ```
+    /// use dh::{DhGroup, DiffieHellman};
+    /// use primitives::BigUint;
+    ///
+    /// let group = DhGroup::new_unchecked(BigUint::from(37u64), BigUint::one(), None);
+    /// let party = DiffieHellman::from_private_key(group, BigUint::from(7u64));
+    /// assert_eq!(*party.public_key(), BigUint::one());
+    /// ```
+    pub fn new_unchecked(p: BigUint, g: BigUint, q: Option<BigUint>) -> DhGroup {
+        DhGroup { p, g, q }
+    }
+
+    /// The 1536 bit MODP group (RFC 3526 group 5), also used in NIST examples
+    pub fn modp_1536() -> DhGroup {
+        DhGroup::safe_prime_group(MODP_1536_P)
+    }
+
+    /// The 2048 bit MODP group (RFC 3526 group 14)
+    pub fn modp_2048() -> DhGroup {
+        DhGroup::safe_prime_group(MODP_2048_P)
+    }
+
+    /// The 3072 bit MODP group (RFC 3526 group 15)
+    pub fn modp_3072() -> DhGroup {
+        DhGroup::safe_prime_group(MODP_3072_P)
+    }
+
+    /// The 4096 bit MODP group (RFC 3526 group 16)
+    pub fn modp_4096() -> DhGroup {
+        DhGroup::safe_prime_group(MODP_4096_P)
+    }
+
+    pub fn p(&self) -> &BigUint {
+        &self.p
+    }
+
+    pub fn g(&self) -> &BigUint {
+        &self.g
+    }
+
+    pub fn q(&self) -> Option<&BigUint> {
+        self.q.as_ref()
+    }
+
+    /// Checks that a public key is in `[2, p - 2]` and, when the order `q` of the group is known,
+    /// that it belongs to the subgroup of order `q`. This rules out the keys 0, 1 and `p - 1`
+    /// that force the shared secret to a predictable value, and small subgroup confinement
+    ///
+    /// # Returns
+    /// A `Result` which is empty if the key is valid or holds a `DhError` otherwise
+    pub fn validate_public_key(&self, public_key: &BigUint) -> Result<(), DhError> {
+        let two = BigUint::from(2u64);
+        if *public_key < two || *public_key > &self.p - 2u64 {
+            return Err(DhError::PublicKeyOutOfRange);
+        }
+
+        if let Some(q) = &self.q {
+            if !public_key.modpow(q, &self.p).is_one() {
+                return Err(DhError::PublicKeyNotInSubgroup);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Creates a group from an RFC 3526 safe prime with generator 2
+    fn safe_prime_group(p: &str) -> DhGroup {
+        let p = BigUint::from_hex(p).unwrap();
+        let q = &p >> 1;
+        DhGroup::new(p, BigUint::from(2u64), Some(q)).unwrap()
+    }
+}
+
+/// The hash function used to turn a shared secret into an AES key
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kdf {
+    Sha1,
+    Sha256,
+}
+
+/// One party of a Diffie–Hellman key exchange
+///
+/// # Examples
+/// ```
+/// use dh::{DhGroup, DiffieHellman, Kdf};
+/// use primitives::OsRng;
+///
+/// let group = DhGroup::modp_1536();
+/// let alice = DiffieHellman::new(group.clone(), &mut OsRng);
+/// let bob = DiffieHellman::new(group, &mut OsRng);
+///
+/// let alice_key = alice.derive_aes_key(bob.public_key(), Kdf::Sha256).unwrap();
+/// let bob_key = bob.derive_aes_key(alice.public_key(), Kdf::Sha256).unwrap();
+/// assert_eq!(alice_key, bob_key);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffieHellman {
+    group: DhGroup,
+    private_key: BigUint,
+    public_key: BigUint,
+    validate_peer: bool,
+}
+
+impl DiffieHellman {
+    /// Creates a new party with a random private key in `[2, q - 1]`, or `[2, p - 2]` if the
+    /// order of the group is unknown. The public keys of peers are validated
+    pub fn new<R: Rng>(group: DhGroup, rng: &mut R) -> DiffieHellman {
+        let high = match &group.q {
+            Some(q) => q.clone(),
+            None => &group.p - 1u64,
+        };
+        let private_key = BigUint::random_range(rng, &BigUint::from(2u64), &high);
+        DiffieHellman::from_private_key(group, private_key)
+    }
+
+    /// Creates a new party with the given private key. The public keys of peers are validated
+    pub fn from_private_key(group: DhGroup, private_key: BigUint) -> DiffieHellman {
+        let public_key = group.g.modpow_ct(&private_key, &group.p);
+        DiffieHellman {
+            group,
+            private_key,
+            public_key,
+            validate_peer: true,
+        }
+    }
+
+    /// Enables or disables the validation of the public keys received from peers
+    pub fn set_public_key_validation(&mut self, enabled: bool) {
+        self.validate_peer = enabled;
+    }
+
+    pub fn group(&self) -> &DhGroup {
+        &self.group
+    }
+
+    pub fn private_key(&self) -> &BigUint {
+        &self.private_key
+    }
+
+    pub fn public_key(&self) -> &BigUint {
+        &self.public_key
+    }
+
+    /// Computes the shared secret `peer_public_key^private_key mod p`
+    ///
+    /// # Returns
+    /// A `Result` wrapping the shared secret or a `DhError` if validation is enabled and the
+    /// public key of the peer is invalid
+    pub fn shared_secret(&self, peer_public_key: &BigUint) -> Result<BigUint, DhError> {
+        if self.validate_peer {
+            self.group.validate_public_key(peer_public_key)?;
+        }
+
+        Ok(peer_public_key.modpow_ct(&self.private_key, &self.group.p))
+    }
+
+    /// Computes the shared secret and derives an AES key from it with `derive_aes_key`
+    ///
+    /// # Returns
+    /// A `Result` wrapping the 16 bytes key or a `DhError` if validation is enabled and the
+    /// public key of the peer is invalid
+    pub fn derive_aes_key(&self, peer_public_key: &BigUint, kdf: Kdf) -> Result<Vec<u8>, DhError> {
+        let secret = self.shared_secret(peer_public_key)?;
+        Ok(derive_aes_key(&secret, kdf))
+    }
+}
+
+/// Derives an AES-128 key from a shared secret: the first 16 bytes of the hash of its minimal
+/// big endian representation
+pub fn derive_aes_key(secret: &BigUint, kdf: Kdf) -> Vec<u8> {
+    let bytes = secret.to_be_bytes();
+    let mut digest = match kdf {
+        Kdf::Sha1 => Sha1::digest(&bytes),
+        Kdf::Sha256 => Sha256::digest(&bytes),
+    };
+    digest.truncate(AES_KEY_SIZE);
+    digest
+}
+
+#[cfg(test)]
+mod tests {
+    use primitives::{is_prime, jacobi, Mt19937};
+
+    use super::*;
+
+    #[test]
+    fn dh_small_group() {
+        let group = DhGroup::new(BigUint::from(37u64), BigUint::from(5u64), None).unwrap();
+        let alice = DiffieHellman::from_private_key(group.clone(), BigUint::from(7u64));
+        let bob = DiffieHellman::from_private_key(group, BigUint::from(11u64));
+
+        assert_eq!(*alice.public_key(), BigUint::from(18u64));
+        let result = alice.shared_secret(bob.public_key()).unwrap();
+        let expected = bob.shared_secret(alice.public_key()).unwrap();
+        assert_eq!(result, expected);
+        assert_eq!(
+            result,
+            BigUint::from(5u64).modpow(&BigUint::from(77u64), &BigUint::from(37u64))
+        );
+    }
+
+    #[test]
+    fn dh_rejects_invalid_groups() {
+        let group = |p: u64, g: u64, q: Option<u64>| {
+            DhGroup::new(BigUint::from(p), BigUint::from(g), q.map(BigUint::from))
+        };
+
+        for p in [0, 1, 2, 3, 1000] {
+            assert_eq!(group(p, 2, None), Err(DhError::InvalidModulus));
+        }
+        for g in [0, 1, 23, 24] {
+            assert_eq!(group(23, g, None), Err(DhError::InvalidGenerator));
+        }
+        for q in [0, 2, 23] {
+            assert_eq!(group(23, 2, Some(q)), Err(DhError::InvalidOrder));
+        }
+        assert!(group(5, 4, Some(3)).is_ok());
+        assert!(group(23, 22, Some(11)).is_ok());
+    }
+
+    #[test]
+    fn dh_unchecked_group_degenerate_generators() {
+        let p = BigUint::from(23u64);
+        for (g, expected) in [
+            (BigUint::one(), BigUint::one()),
+            (p.clone(), BigUint::zero()),
+        ] {
+            let group = DhGroup::new_unchecked(p.clone(), g, None);
+            let alice = DiffieHellman::new(group, &mut Mt19937::new(1));
+            assert_eq!(*alice.public_key(), expected);
+        }
+    }
+
+    #[test]
+    fn dh_named_groups_are_safe_primes() {
+        for group in [DhGroup::modp_1536(), DhGroup::modp_2048()] {
+            assert!(is_prime(group.p()));
+            assert!(is_prime(group.q().unwrap()));
+            assert!(group.validate_public_key(group.g()).is_ok());
+        }
+        assert_eq!(DhGroup::modp_3072().p().bits(), 3072);
+        assert_eq!(DhGroup::modp_4096().p().bits(), 4096);
+    }
+
+    #[test]
+    fn dh_key_agreement() {
+        let mut rng = Mt19937::new(5489);
+        let group = DhGroup::modp_1536();
+        let alice = DiffieHellman::new(group.clone(), &mut rng);
+        let bob = DiffieHellman::new(group, &mut rng);
+
+        let result = alice.derive_aes_key(bob.public_key(), Kdf::Sha1).unwrap();
+        let expected = bob.derive_aes_key(alice.public_key(), Kdf::Sha1).unwrap();
+        assert_eq!(result, expected);
+        assert_eq!(result.len(), AES_KEY_SIZE);
+        assert_ne!(
+            result,
+            alice.derive_aes_key(bob.public_key(), Kdf::Sha256).unwrap()
+        );
+    }
+
+    #[test]
+    fn dh_rejects_degenerate_public_keys() {
+        let group = DhGroup::modp_1536();
+        let alice = DiffieHellman::new(group.clone(), &mut Mt19937::new(1));
+        let p = group.p();
+
+        for key in [
+            BigUint::zero(),
+            BigUint::one(),
+            p - 1u64,
+            p.clone(),
+            p + 1u64,
+        ] {
+            assert_eq!(alice.shared_secret(&key), Err(DhError::PublicKeyOutOfRange));
+        }
+    }
+
+    #[test]
+    fn dh_rejects_public_key_outside_subgroup() {
+        let group = DhGroup::modp_1536();
+        let alice = DiffieHellman::new(group.clone(), &mut Mt19937::new(1));
+
+        // quadratic non-residues generate the whole group, not the subgroup of order q
+        let non_residue = (3u64..)
+            .map(BigUint::from)
+            .find(|y| jacobi(y, group.p()) == -1)
+            .unwrap();
+        let result = alice.shared_secret(&non_residue);
+        let expected = Err(DhError::PublicKeyNotInSubgroup);
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn dh_without_validation_accepts_degenerate_keys() {
+        let group = DhGroup::modp_1536();
+        let mut alice = DiffieHellman::new(group.clone(), &mut Mt19937::new(1));
+        alice.set_public_key_validation(false);
+
+        assert_eq!(alice.shared_secret(&BigUint::one()), Ok(BigUint::one()));
+        assert_eq!(alice.shared_secret(group.p()), Ok(BigUint::zero()));
+    }
+}
@@ -35,7 +35,7 @@ pub trait Decode: Sized {
 
 impl Decode for Vec<u8> {
     fn from_hex(s: &str) -> Result<Self, DecodeHexError> {
-        if s.len() % 2 != 0 {
+        if !s.len().is_multiple_of(2) {
             return Err(DecodeHexError::OddLength);
         }
 
@@ -58,7 +58,7 @@ impl Decode for Vec<u8> {
             return Ok(vec![]);
         }
 
-        if s.len() % 4 != 0 {
+        if !s.len().is_multiple_of(4) {
             return Err(DecodeBase64Error::InvalidLength);
         }
 
@@ -278,4 +278,13 @@ mod tests {
         let expected = String::from("");
         assert_eq!(result, expected);
     }
+
+    #[test]
+    fn bytes_to_base64_all_sextets() {
+        let input = vec![0xFB, 0xEF, 0xBE, 0xFF];
+        let result = input.to_base64();
+        let expected = String::from("++++/w==");
+        assert_eq!(result, expected);
+        assert_eq!(Vec::from_base64(&result).unwrap(), input);
+    }
 }
@@ -52,7 +52,7 @@ fn u8_to_base64(u: u8) -> char {
         0..=25 => (b'A' + u) as char,
         26..=51 => (b'a' + (u - 26)) as char,
         52..=61 => (b'0' + (u - 52)) as char,
-        62 => '=',
+        62 => '+',
         63 => '/',
         _ => panic!("byte exceeded range for base64 conversion: {}", u),
     }
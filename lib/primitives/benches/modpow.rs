@@ -0,0 +1,55 @@
+//! Compares the modular exponentiation algorithms on random odd moduli.
+//!
+//! Run with `cargo bench -p primitives`.
+
+use std::time::{Duration, Instant};
+
+use primitives::{BigUint, MontgomeryContext, Mt19937};
+
+const SIZES: [usize; 3] = [1024, 2048, 4096];
+const MIN_DURATION: Duration = Duration::from_secs(2);
+
+fn random_biguint(rng: &mut Mt19937, bits: usize) -> BigUint {
+    let bytes: Vec<u8> = (0..bits / 8).map(|_| rng.next_u32() as u8).collect();
+    BigUint::from_be_bytes(&bytes)
+}
+
+/// Runs `f` until `MIN_DURATION` has elapsed and returns the mean time per call
+fn measure<F: FnMut() -> BigUint>(mut f: F) -> Duration {
+    let start = Instant::now();
+    let mut iterations = 0;
+
+    while iterations == 0 || start.elapsed() < MIN_DURATION {
+        std::hint::black_box(f());
+        iterations += 1;
+    }
+
+    start.elapsed() / iterations
+}
+
+fn main() {
+    let mut rng = Mt19937::new(5489);
+
+    println!(
+        "{:>6}  {:>14}  {:>14}  {:>14}",
+        "bits", "schoolbook", "sliding window", "ladder"
+    );
+
+    for bits in SIZES {
+        let mut modulus = random_biguint(&mut rng, bits);
+        modulus.set_bit(bits - 1, true);
+        modulus.set_bit(0, true);
+        let base = random_biguint(&mut rng, bits);
+        let exponent = random_biguint(&mut rng, bits);
+        let context = MontgomeryContext::new(&modulus).unwrap();
+
+        let schoolbook = measure(|| base.modpow_schoolbook(&exponent, &modulus));
+        let sliding_window = measure(|| context.pow(&base, &exponent));
+        let ladder = measure(|| context.pow_ladder(&base, &exponent));
+
+        println!(
+            "{:>6}  {:>14.2?}  {:>14.2?}  {:>14.2?}",
+            bits, schoolbook, sliding_window, ladder
+        );
+    }
+}
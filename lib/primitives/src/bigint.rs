@@ -0,0 +1,307 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, AddAssign, Div, Mul, MulAssign, Neg, Rem, Sub, SubAssign};
+use std::str::FromStr;
+
+use crate::{BigUint, ParseBigIntError};
+
+/// An arbitrary-precision signed integer, stored as a sign and a `BigUint` magnitude. Division
+/// truncates towards zero like the primitive integer types
+///
+/// # Examples
+/// ```
+/// use primitives::BigInt;
+///
+/// let a = BigInt::from(-7i64);
+/// let b = BigInt::from(2i64);
+///
+/// assert_eq!(&a / &b, BigInt::from(-3i64));
+/// assert_eq!(&a % &b, BigInt::from(-1i64));
+/// assert_eq!(a.rem_euclid(&b.magnitude()), 1u64.into());
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash, Default)]
+pub struct BigInt {
+    /// Never set for zero
+    negative: bool,
+    magnitude: BigUint,
+}
+
+impl BigInt {
+    /// Creates an integer from a sign and a magnitude
+    pub fn new(negative: bool, magnitude: BigUint) -> BigInt {
+        BigInt {
+            negative: negative && !magnitude.is_zero(),
+            magnitude,
+        }
+    }
+
+    /// Returns the integer 0
+    pub fn zero() -> BigInt {
+        BigInt::default()
+    }
+
+    /// Returns the integer 1
+    pub fn one() -> BigInt {
+        BigInt::from(BigUint::one())
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.magnitude.is_zero()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    /// Returns the absolute value
+    pub fn magnitude(&self) -> BigUint {
+        self.magnitude.clone()
+    }
+
+    /// Returns the value as a `BigUint`, or `None` if it is negative
+    pub fn to_biguint(&self) -> Option<BigUint> {
+        if self.negative {
+            None
+        } else {
+            Some(self.magnitude.clone())
+        }
+    }
+
+    /// Returns the remainder of the division by `modulus` in `[0, modulus)`
+    ///
+    /// # Panics
+    /// If `modulus` is 0
+    pub fn rem_euclid(&self, modulus: &BigUint) -> BigUint {
+        let remainder = &self.magnitude % modulus;
+        if self.negative && !remainder.is_zero() {
+            modulus - &remainder
+        } else {
+            remainder
+        }
+    }
+}
+
+impl From<BigUint> for BigInt {
+    fn from(magnitude: BigUint) -> Self {
+        BigInt::new(false, magnitude)
+    }
+}
+
+impl From<i64> for BigInt {
+    fn from(value: i64) -> Self {
+        BigInt::new(value < 0, BigUint::from(value.unsigned_abs()))
+    }
+}
+
+impl FromStr for BigInt {
+    type Err = ParseBigIntError;
+
+    /// Parses a decimal integer with an optional sign
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.strip_prefix('-') {
+            Some(digits) => Ok(BigInt::new(true, digits.parse()?)),
+            None => Ok(BigInt::from(
+                s.strip_prefix('+').unwrap_or(s).parse::<BigUint>()?,
+            )),
+        }
+    }
+}
+
+impl fmt::Display for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad_integral(!self.negative, "", &self.magnitude.to_string())
+    }
+}
+
+impl fmt::Debug for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, false) => self.magnitude.cmp(&other.magnitude),
+            (true, true) => other.magnitude.cmp(&self.magnitude),
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+        }
+    }
+}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Neg for &BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> BigInt {
+        BigInt::new(!self.negative, self.magnitude.clone())
+    }
+}
+
+impl Neg for BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> BigInt {
+        BigInt::new(!self.negative, self.magnitude)
+    }
+}
+
+impl Add<&BigInt> for &BigInt {
+    type Output = BigInt;
+
+    fn add(self, rhs: &BigInt) -> BigInt {
+        if self.negative == rhs.negative {
+            return BigInt::new(self.negative, &self.magnitude + &rhs.magnitude);
+        }
+
+        match self.magnitude.cmp(&rhs.magnitude) {
+            Ordering::Less => BigInt::new(rhs.negative, &rhs.magnitude - &self.magnitude),
+            _ => BigInt::new(self.negative, &self.magnitude - &rhs.magnitude),
+        }
+    }
+}
+
+impl Sub<&BigInt> for &BigInt {
+    type Output = BigInt;
+
+    fn sub(self, rhs: &BigInt) -> BigInt {
+        self + &-rhs
+    }
+}
+
+impl Mul<&BigInt> for &BigInt {
+    type Output = BigInt;
+
+    fn mul(self, rhs: &BigInt) -> BigInt {
+        BigInt::new(
+            self.negative != rhs.negative,
+            &self.magnitude * &rhs.magnitude,
+        )
+    }
+}
+
+impl Div<&BigInt> for &BigInt {
+    type Output = BigInt;
+
+    fn div(self, rhs: &BigInt) -> BigInt {
+        BigInt::new(
+            self.negative != rhs.negative,
+            &self.magnitude / &rhs.magnitude,
+        )
+    }
+}
+
+impl Rem<&BigInt> for &BigInt {
+    type Output = BigInt;
+
+    fn rem(self, rhs: &BigInt) -> BigInt {
+        BigInt::new(self.negative, &self.magnitude % &rhs.magnitude)
+    }
+}
+
+/// Implements an arithmetic operator for owned operands from the implementation on references
+macro_rules! forward_binop {
+    ($trait:ident, $method:ident) => {
+        impl $trait<BigInt> for BigInt {
+            type Output = BigInt;
+
+            fn $method(self, rhs: BigInt) -> BigInt {
+                (&self).$method(&rhs)
+            }
+        }
+
+        impl $trait<&BigInt> for BigInt {
+            type Output = BigInt;
+
+            fn $method(self, rhs: &BigInt) -> BigInt {
+                (&self).$method(rhs)
+            }
+        }
+
+        impl $trait<BigInt> for &BigInt {
+            type Output = BigInt;
+
+            fn $method(self, rhs: BigInt) -> BigInt {
+                self.$method(&rhs)
+            }
+        }
+    };
+}
+
+forward_binop!(Add, add);
+forward_binop!(Sub, sub);
+forward_binop!(Mul, mul);
+forward_binop!(Div, div);
+forward_binop!(Rem, rem);
+
+impl AddAssign<&BigInt> for BigInt {
+    fn add_assign(&mut self, rhs: &BigInt) {
+        *self = &*self + rhs;
+    }
+}
+
+impl SubAssign<&BigInt> for BigInt {
+    fn sub_assign(&mut self, rhs: &BigInt) {
+        *self = &*self - rhs;
+    }
+}
+
+impl MulAssign<&BigInt> for BigInt {
+    fn mul_assign(&mut self, rhs: &BigInt) {
+        *self = &*self * rhs;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn big(s: &str) -> BigInt {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn bigint_parse_and_display() {
+        assert_eq!(
+            big("-123456789012345678901234567890").to_string(),
+            "-123456789012345678901234567890"
+        );
+        assert_eq!(big("+42").to_string(), "42");
+        assert_eq!(big("-0"), BigInt::zero());
+        assert!(!big("-0").is_negative());
+    }
+
+    #[test]
+    fn bigint_arithmetic_signs() {
+        let a = big("100000000000000000000");
+        let b = big("-30000000000000000000");
+
+        assert_eq!(&a + &b, big("70000000000000000000"));
+        assert_eq!(&b + &a, big("70000000000000000000"));
+        assert_eq!(&b - &a, big("-130000000000000000000"));
+        assert_eq!(&a - &a, BigInt::zero());
+        assert_eq!(&a * &b, big("-3000000000000000000000000000000000000000"));
+        assert_eq!(&b * &b, big("900000000000000000000000000000000000000"));
+        assert_eq!(&a / &b, big("-3"));
+        assert_eq!(&a % &b, big("10000000000000000000"));
+        assert_eq!(&b % &a, big("-30000000000000000000"));
+    }
+
+    #[test]
+    fn bigint_ordering_and_rem_euclid() {
+        assert!(big("-5") < big("-4"));
+        assert!(big("-5") < big("3"));
+        assert!(big("5") > big("3"));
+
+        let modulus = BigUint::from(7u64);
+        assert_eq!(big("-1").rem_euclid(&modulus), BigUint::from(6u64));
+        assert_eq!(big("-14").rem_euclid(&modulus), BigUint::zero());
+        assert_eq!(big("15").rem_euclid(&modulus), BigUint::one());
+    }
+}
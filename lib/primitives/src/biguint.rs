@@ -0,0 +1,1120 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{
+    Add, AddAssign, BitAnd, BitOr, BitXor, Div, DivAssign, Mul, MulAssign, Rem, RemAssign, Shl,
+    ShlAssign, Shr, ShrAssign, Sub, SubAssign,
+};
+use std::str::FromStr;
+
+use encoding::{Decode, DecodeBase64Error, DecodeHexError, Encode};
+
+use crate::{BigInt, MontgomeryContext, ParseBigIntError, Rng};
+
+/// The largest power of 10 that fits in a limb, used for decimal conversions
+const DECIMAL_BASE: u64 = 10_000_000_000_000_000_000;
+const DECIMAL_DIGITS: usize = 19;
+
+/// An arbitrary-precision unsigned integer
+///
+/// # Examples
+/// ```
+/// use primitives::BigUint;
+///
+/// let a: BigUint = "123456789012345678901234567890".parse().unwrap();
+/// let b = BigUint::from(987654321u64);
+///
+/// assert_eq!((&a * &b).to_string(), "121932631124828532112482853211126352690");
+/// assert_eq!((&a % &b).to_string(), "574845669");
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash, Default)]
+pub struct BigUint {
+    /// The 64 bit limbs of the integer, least significant first, without trailing zeros
+    limbs: Vec<u64>,
+}
+
+impl BigUint {
+    /// Returns the integer 0
+    pub fn zero() -> BigUint {
+        BigUint { limbs: vec![] }
+    }
+
+    /// Returns the integer 1
+    pub fn one() -> BigUint {
+        BigUint { limbs: vec![1] }
+    }
+
+    /// Creates an integer from its 64 bit limbs, least significant first
+    pub fn from_limbs(limbs: &[u64]) -> BigUint {
+        let mut n = BigUint {
+            limbs: limbs.to_vec(),
+        };
+        n.normalize();
+        n
+    }
+
+    /// Returns the 64 bit limbs of the integer, least significant first
+    pub fn limbs(&self) -> &[u64] {
+        &self.limbs
+    }
+
+    /// Creates an integer from its big endian representation
+    pub fn from_be_bytes(bytes: &[u8]) -> BigUint {
+        let limbs: Vec<u64> = bytes
+            .rchunks(8)
+            .map(|chunk| {
+                chunk
+                    .iter()
+                    .fold(0u64, |limb, &byte| (limb << 8) | byte as u64)
+            })
+            .collect();
+        BigUint::from_limbs(&limbs)
+    }
+
+    /// Returns the minimal big endian representation of the integer (empty for 0)
+    pub fn to_be_bytes(&self) -> Vec<u8> {
+        let bytes: Vec<u8> = self
+            .limbs
+            .iter()
+            .rev()
+            .flat_map(|limb| limb.to_be_bytes())
+            .collect();
+        let leading_zeros = bytes.iter().take_while(|&&b| b == 0).count();
+        bytes[leading_zeros..].to_vec()
+    }
+
+    /// Returns the big endian representation of the integer left-padded with zeros to `length`
+    /// bytes, or `None` if the integer does not fit
+    pub fn to_be_bytes_padded(&self, length: usize) -> Option<Vec<u8>> {
+        let bytes = self.to_be_bytes();
+        if bytes.len() > length {
+            return None;
+        }
+
+        let mut padded = vec![0; length - bytes.len()];
+        padded.extend(bytes);
+        Some(padded)
+    }
+
+    /// Returns the value of the integer if it fits in a `u64`
+    pub fn to_u64(&self) -> Option<u64> {
+        match self.limbs.len() {
+            0 => Some(0),
+            1 => Some(self.limbs[0]),
+            _ => None,
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    pub fn is_one(&self) -> bool {
+        self.limbs == [1]
+    }
+
+    pub fn is_even(&self) -> bool {
+        self.limbs.first().is_none_or(|limb| limb & 1 == 0)
+    }
+
+    pub fn is_odd(&self) -> bool {
+        !self.is_even()
+    }
+
+    /// Returns the number of bits needed to represent the integer (0 for 0)
+    pub fn bits(&self) -> usize {
+        match self.limbs.last() {
+            Some(last) => self.limbs.len() * 64 - last.leading_zeros() as usize,
+            None => 0,
+        }
+    }
+
+    /// Returns the value of the bit at the given position, starting from the least significant
+    pub fn bit(&self, position: usize) -> bool {
+        self.limbs
+            .get(position / 64)
+            .is_some_and(|limb| limb >> (position % 64) & 1 == 1)
+    }
+
+    /// Sets the bit at the given position, starting from the least significant
+    pub fn set_bit(&mut self, position: usize, value: bool) {
+        let index = position / 64;
+        if index >= self.limbs.len() {
+            if !value {
+                return;
+            }
+            self.limbs.resize(index + 1, 0);
+        }
+
+        if value {
+            self.limbs[index] |= 1 << (position % 64);
+        } else {
+            self.limbs[index] &= !(1 << (position % 64));
+            self.normalize();
+        }
+    }
+
+    /// Returns the number of trailing zero bits, or `None` for 0
+    pub fn trailing_zeros(&self) -> Option<usize> {
+        let index = self.limbs.iter().position(|&limb| limb != 0)?;
+        Some(index * 64 + self.limbs[index].trailing_zeros() as usize)
+    }
+
+    /// Returns a uniformly random integer of at most `bits` bits
+    pub fn random_bits<R: Rng>(rng: &mut R, bits: usize) -> BigUint {
+        let mut bytes = vec![0; bits.div_ceil(8)];
+        rng.fill_bytes(&mut bytes);
+        if !bits.is_multiple_of(8) {
+            bytes[0] &= 0xFF >> (8 - bits % 8);
+        }
+        BigUint::from_be_bytes(&bytes)
+    }
+
+    /// Returns a uniformly random integer in `[0, bound)` using rejection sampling
+    ///
+    /// # Panics
+    /// If `bound` is 0
+    pub fn random_below<R: Rng>(rng: &mut R, bound: &BigUint) -> BigUint {
+        assert!(!bound.is_zero(), "the bound must be positive");
+        loop {
+            let candidate = BigUint::random_bits(rng, bound.bits());
+            if &candidate < bound {
+                return candidate;
+            }
+        }
+    }
+
+    /// Returns a uniformly random integer in `[low, high)`
+    ///
+    /// # Panics
+    /// If `high <= low`
+    pub fn random_range<R: Rng>(rng: &mut R, low: &BigUint, high: &BigUint) -> BigUint {
+        low + &BigUint::random_below(rng, &(high - low))
+    }
+
+    /// Returns the remainder of the division by a single limb, faster than `%`
+    ///
+    /// # Panics
+    /// If `divisor` is 0
+    pub fn rem_u64(&self, divisor: u64) -> u64 {
+        self.limbs.iter().rev().fold(0u128, |remainder, &limb| {
+            ((remainder << 64) | limb as u128) % divisor as u128
+        }) as u64
+    }
+
+    /// Subtracts `other`, returning `None` if the result would be negative
+    pub fn checked_sub(&self, other: &BigUint) -> Option<BigUint> {
+        if self < other {
+            return None;
+        }
+
+        let mut limbs = self.limbs.clone();
+        sub_assign_limbs(&mut limbs, &other.limbs);
+        Some(BigUint::from_limbs(&limbs))
+    }
+
+    /// Divides by `divisor` and returns the quotient and the remainder
+    ///
+    /// # Panics
+    /// If `divisor` is 0
+    pub fn div_rem(&self, divisor: &BigUint) -> (BigUint, BigUint) {
+        if divisor.is_zero() {
+            panic!("attempt to divide by zero");
+        }
+        if self < divisor {
+            return (BigUint::zero(), self.clone());
+        }
+        if divisor.limbs.len() == 1 {
+            let (quotient, remainder) = div_rem_limb(&self.limbs, divisor.limbs[0]);
+            return (BigUint::from_limbs(&quotient), BigUint::from(remainder));
+        }
+
+        let (quotient, remainder) = div_rem_knuth(&self.limbs, &divisor.limbs);
+        (
+            BigUint::from_limbs(&quotient),
+            BigUint::from_limbs(&remainder),
+        )
+    }
+
+    /// Raises the integer to the power `exponent`
+    pub fn pow(&self, exponent: u32) -> BigUint {
+        let mut result = BigUint::one();
+        let mut base = self.clone();
+        let mut exponent = exponent;
+
+        while exponent > 0 {
+            if exponent & 1 == 1 {
+                result = &result * &base;
+            }
+            exponent >>= 1;
+            if exponent > 0 {
+                base = &base * &base;
+            }
+        }
+
+        result
+    }
+
+    /// Computes `self^exponent mod modulus`. Odd moduli use Montgomery multiplication with
+    /// sliding window exponentiation, even ones fall back to `modpow_schoolbook`. The running
+    /// time depends on the exponent, use `modpow_ct` for secret exponents
+    ///
+    /// # Panics
+    /// If `modulus` is 0
+    ///
+    /// # Examples
+    /// ```
+    /// use primitives::BigUint;
+    ///
+    /// let base = BigUint::from(4u64);
+    /// let result = base.modpow(&BigUint::from(13u64), &BigUint::from(497u64));
+    /// assert_eq!(result, BigUint::from(445u64));
+    /// ```
+    pub fn modpow(&self, exponent: &BigUint, modulus: &BigUint) -> BigUint {
+        match MontgomeryContext::new(modulus) {
+            Some(context) => context.pow(self, exponent),
+            None => self.modpow_schoolbook(exponent, modulus),
+        }
+    }
+
+    /// Computes `self^exponent mod modulus` with a Montgomery ladder whose timing does not
+    /// depend on the value of the exponent
+    ///
+    /// # Panics
+    /// If `modulus` is even or smaller than 3
+    pub fn modpow_ct(&self, exponent: &BigUint, modulus: &BigUint) -> BigUint {
+        MontgomeryContext::new(modulus)
+            .expect("the modulus must be odd and greater than 1")
+            .pow_ladder(self, exponent)
+    }
+
+    /// Computes `self^exponent mod modulus` with left-to-right square-and-multiply and a long
+    /// division after every step
+    ///
+    /// # Panics
+    /// If `modulus` is 0
+    pub fn modpow_schoolbook(&self, exponent: &BigUint, modulus: &BigUint) -> BigUint {
+        if modulus.is_one() {
+            return BigUint::zero();
+        }
+
+        let base = self % modulus;
+        let mut result = BigUint::one();
+
+        for i in (0..exponent.bits()).rev() {
+            result = &(&result * &result) % modulus;
+            if exponent.bit(i) {
+                result = &(&result * &base) % modulus;
+            }
+        }
+
+        result
+    }
+
+    /// Computes the greatest common divisor with the Euclidean algorithm
+    pub fn gcd(&self, other: &BigUint) -> BigUint {
+        let mut a = self.clone();
+        let mut b = other.clone();
+
+        while !b.is_zero() {
+            let r = &a % &b;
+            a = b;
+            b = r;
+        }
+
+        a
+    }
+
+    /// Computes the least common multiple
+    pub fn lcm(&self, other: &BigUint) -> BigUint {
+        if self.is_zero() || other.is_zero() {
+            return BigUint::zero();
+        }
+        &(self / &self.gcd(other)) * other
+    }
+
+    /// Computes the inverse of the integer modulo `modulus` with the extended Euclidean
+    /// algorithm
+    ///
+    /// # Returns
+    /// An `Option` wrapping the inverse in `[0, modulus)`, or `None` if the integer is not
+    /// invertible
+    ///
+    /// # Examples
+    /// ```
+    /// use primitives::BigUint;
+    ///
+    /// let result = BigUint::from(17u64).modinv(&BigUint::from(3120u64));
+    /// assert_eq!(result, Some(BigUint::from(2753u64)));
+    ///
+    /// assert_eq!(BigUint::from(6u64).modinv(&BigUint::from(9u64)), None);
+    /// ```
+    pub fn modinv(&self, modulus: &BigUint) -> Option<BigUint> {
+        if modulus.is_zero() {
+            return None;
+        }
+
+        let (mut r0, mut r1) = (modulus.clone(), self % modulus);
+        let (mut t0, mut t1) = (BigInt::zero(), BigInt::one());
+
+        while !r1.is_zero() {
+            let (q, r) = r0.div_rem(&r1);
+            r0 = std::mem::replace(&mut r1, r);
+            let t = &t0 - &(&BigInt::from(q) * &t1);
+            t0 = std::mem::replace(&mut t1, t);
+        }
+
+        if !r0.is_one() {
+            return None;
+        }
+
+        Some(t0.rem_euclid(modulus))
+    }
+
+    /// Computes the integer `k`-th root, i.e. the largest `r` such that `r^k <= self`, with
+    /// Newton's method
+    ///
+    /// # Panics
+    /// If `k` is 0
+    ///
+    /// # Examples
+    /// ```
+    /// use primitives::BigUint;
+    ///
+    /// let n = BigUint::from(1_000_000_000_000u64);
+    /// assert_eq!(n.nth_root(3), BigUint::from(10_000u64));
+    /// assert_eq!((&n - 1u64).nth_root(3), BigUint::from(9_999u64));
+    /// ```
+    pub fn nth_root(&self, k: u32) -> BigUint {
+        assert!(k > 0, "the root must be at least 1");
+        if k == 1 || self.bits() <= 1 {
+            return self.clone();
+        }
+
+        // start from a power of two above the root so the iteration decreases monotonically
+        let k_big = BigUint::from(k as u64);
+        let mut x = BigUint::one() << self.bits().div_ceil(k as usize);
+
+        loop {
+            let y = &(&(&x * (k as u64 - 1)) + &(self / &x.pow(k - 1))) / &k_big;
+            if y >= x {
+                return x;
+            }
+            x = y;
+        }
+    }
+
+    /// Computes the integer square root
+    pub fn sqrt(&self) -> BigUint {
+        self.nth_root(2)
+    }
+
+    /// Removes the most significant zero limbs
+    fn normalize(&mut self) {
+        while self.limbs.last() == Some(&0) {
+            self.limbs.pop();
+        }
+    }
+}
+
+impl From<u64> for BigUint {
+    fn from(value: u64) -> Self {
+        BigUint::from_limbs(&[value])
+    }
+}
+
+impl From<u32> for BigUint {
+    fn from(value: u32) -> Self {
+        BigUint::from(value as u64)
+    }
+}
+
+impl FromStr for BigUint {
+    type Err = ParseBigIntError;
+
+    /// Parses a decimal integer
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Err(ParseBigIntError::Empty);
+        }
+        if !s.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(ParseBigIntError::InvalidDigit);
+        }
+
+        let mut n = BigUint::zero();
+        let first = s.len() % DECIMAL_DIGITS;
+        let chunks = std::iter::once(&s[..first])
+            .filter(|chunk| !chunk.is_empty())
+            .chain(s.as_bytes()[first..].chunks(DECIMAL_DIGITS).map(|c| {
+                // the input was checked to be ASCII digits
+                std::str::from_utf8(c).unwrap()
+            }));
+
+        for chunk in chunks {
+            let multiplier = 10u64.pow(chunk.len() as u32);
+            n = &(&n * multiplier) + chunk.parse::<u64>().unwrap();
+        }
+
+        Ok(n)
+    }
+}
+
+impl fmt::Display for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_zero() {
+            return f.pad_integral(true, "", "0");
+        }
+
+        let mut chunks = vec![];
+        let mut limbs = self.limbs.clone();
+        while !limbs.is_empty() {
+            let (quotient, remainder) = div_rem_limb(&limbs, DECIMAL_BASE);
+            chunks.push(remainder);
+            limbs = quotient;
+            while limbs.last() == Some(&0) {
+                limbs.pop();
+            }
+        }
+
+        let mut s = chunks.pop().unwrap().to_string();
+        for chunk in chunks.iter().rev() {
+            s.push_str(&format!("{:019}", chunk));
+        }
+        f.pad_integral(true, "", &s)
+    }
+}
+
+impl fmt::Debug for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+impl fmt::LowerHex for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let hex = self.to_hex();
+        let hex = hex.trim_start_matches('0');
+        f.pad_integral(true, "0x", if hex.is_empty() { "0" } else { hex })
+    }
+}
+
+impl Decode for BigUint {
+    /// Parses a big endian hexadecimal integer. Unlike byte strings, odd lengths are accepted
+    fn from_hex(s: &str) -> Result<Self, DecodeHexError> {
+        let bytes = if s.len() % 2 == 1 {
+            Vec::from_hex(&format!("0{}", s))?
+        } else {
+            Vec::from_hex(s)?
+        };
+        Ok(BigUint::from_be_bytes(&bytes))
+    }
+
+    /// Parses a base64 encoded big endian integer
+    fn from_base64(s: &str) -> Result<Self, DecodeBase64Error> {
+        Ok(BigUint::from_be_bytes(&Vec::from_base64(s)?))
+    }
+}
+
+impl Encode for BigUint {
+    /// Returns the minimal big endian hexadecimal representation with an even number of digits
+    /// ("00" for 0)
+    fn to_hex(&self) -> String {
+        if self.is_zero() {
+            return "00".to_string();
+        }
+        self.to_be_bytes().to_hex()
+    }
+
+    /// Returns the base64 encoding of the minimal big endian representation
+    fn to_base64(&self) -> String {
+        self.to_be_bytes().to_base64()
+    }
+}
+
+impl Ord for BigUint {
+    fn cmp(&self, other: &Self) -> Ordering {
+        cmp_limbs(&self.limbs, &other.limbs)
+    }
+}
+
+impl PartialOrd for BigUint {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Add<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn add(self, rhs: &BigUint) -> BigUint {
+        let (long, short) = if self.limbs.len() >= rhs.limbs.len() {
+            (self, rhs)
+        } else {
+            (rhs, self)
+        };
+
+        let mut limbs = long.limbs.clone();
+        let mut carry = false;
+        for (i, limb) in limbs.iter_mut().enumerate() {
+            let other = short.limbs.get(i).copied().unwrap_or(0);
+            if i >= short.limbs.len() && !carry {
+                break;
+            }
+            let (sum, c1) = limb.overflowing_add(other);
+            let (sum, c2) = sum.overflowing_add(carry as u64);
+            *limb = sum;
+            carry = c1 || c2;
+        }
+        if carry {
+            limbs.push(1);
+        }
+
+        BigUint { limbs }
+    }
+}
+
+impl Sub<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn sub(self, rhs: &BigUint) -> BigUint {
+        self.checked_sub(rhs)
+            .expect("attempt to subtract with overflow")
+    }
+}
+
+impl Mul<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn mul(self, rhs: &BigUint) -> BigUint {
+        if self.is_zero() || rhs.is_zero() {
+            return BigUint::zero();
+        }
+
+        let mut limbs = vec![0u64; self.limbs.len() + rhs.limbs.len()];
+        for (i, &a) in self.limbs.iter().enumerate() {
+            let mut carry = 0u64;
+            for (j, &b) in rhs.limbs.iter().enumerate() {
+                let t = a as u128 * b as u128 + limbs[i + j] as u128 + carry as u128;
+                limbs[i + j] = t as u64;
+                carry = (t >> 64) as u64;
+            }
+            limbs[i + rhs.limbs.len()] = carry;
+        }
+
+        BigUint::from_limbs(&limbs)
+    }
+}
+
+impl Div<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn div(self, rhs: &BigUint) -> BigUint {
+        self.div_rem(rhs).0
+    }
+}
+
+impl Rem<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn rem(self, rhs: &BigUint) -> BigUint {
+        self.div_rem(rhs).1
+    }
+}
+
+impl BitAnd<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn bitand(self, rhs: &BigUint) -> BigUint {
+        let limbs: Vec<u64> = self
+            .limbs
+            .iter()
+            .zip(&rhs.limbs)
+            .map(|(a, b)| a & b)
+            .collect();
+        BigUint::from_limbs(&limbs)
+    }
+}
+
+impl BitOr<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn bitor(self, rhs: &BigUint) -> BigUint {
+        let length = self.limbs.len().max(rhs.limbs.len());
+        let limbs: Vec<u64> = (0..length)
+            .map(|i| self.limbs.get(i).unwrap_or(&0) | rhs.limbs.get(i).unwrap_or(&0))
+            .collect();
+        BigUint::from_limbs(&limbs)
+    }
+}
+
+impl BitXor<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn bitxor(self, rhs: &BigUint) -> BigUint {
+        let length = self.limbs.len().max(rhs.limbs.len());
+        let limbs: Vec<u64> = (0..length)
+            .map(|i| self.limbs.get(i).unwrap_or(&0) ^ rhs.limbs.get(i).unwrap_or(&0))
+            .collect();
+        BigUint::from_limbs(&limbs)
+    }
+}
+
+impl Shl<usize> for &BigUint {
+    type Output = BigUint;
+
+    fn shl(self, shift: usize) -> BigUint {
+        if self.is_zero() {
+            return BigUint::zero();
+        }
+
+        let (limb_shift, bit_shift) = (shift / 64, shift % 64);
+        let mut limbs = vec![0; limb_shift];
+        if bit_shift == 0 {
+            limbs.extend(&self.limbs);
+        } else {
+            let mut carry = 0;
+            for &limb in &self.limbs {
+                limbs.push((limb << bit_shift) | carry);
+                carry = limb >> (64 - bit_shift);
+            }
+            limbs.push(carry);
+        }
+
+        BigUint::from_limbs(&limbs)
+    }
+}
+
+impl Shr<usize> for &BigUint {
+    type Output = BigUint;
+
+    fn shr(self, shift: usize) -> BigUint {
+        let (limb_shift, bit_shift) = (shift / 64, shift % 64);
+        if limb_shift >= self.limbs.len() {
+            return BigUint::zero();
+        }
+
+        let high = &self.limbs[limb_shift..];
+        let limbs: Vec<u64> = if bit_shift == 0 {
+            high.to_vec()
+        } else {
+            (0..high.len())
+                .map(|i| {
+                    let next = high.get(i + 1).copied().unwrap_or(0);
+                    (high[i] >> bit_shift) | (next << (64 - bit_shift))
+                })
+                .collect()
+        };
+
+        BigUint::from_limbs(&limbs)
+    }
+}
+
+/// Implements an arithmetic operator for every combination of owned and borrowed operands and
+/// for `u64` right hand sides, plus the matching assignment operator, from the implementation on
+/// two references
+macro_rules! forward_binop {
+    ($trait:ident, $method:ident, $assign_trait:ident, $assign_method:ident) => {
+        impl $trait<BigUint> for BigUint {
+            type Output = BigUint;
+
+            fn $method(self, rhs: BigUint) -> BigUint {
+                (&self).$method(&rhs)
+            }
+        }
+
+        impl $trait<&BigUint> for BigUint {
+            type Output = BigUint;
+
+            fn $method(self, rhs: &BigUint) -> BigUint {
+                (&self).$method(rhs)
+            }
+        }
+
+        impl $trait<BigUint> for &BigUint {
+            type Output = BigUint;
+
+            fn $method(self, rhs: BigUint) -> BigUint {
+                self.$method(&rhs)
+            }
+        }
+
+        impl $trait<u64> for &BigUint {
+            type Output = BigUint;
+
+            fn $method(self, rhs: u64) -> BigUint {
+                self.$method(&BigUint::from(rhs))
+            }
+        }
+
+        impl $trait<u64> for BigUint {
+            type Output = BigUint;
+
+            fn $method(self, rhs: u64) -> BigUint {
+                (&self).$method(&BigUint::from(rhs))
+            }
+        }
+
+        impl $assign_trait<&BigUint> for BigUint {
+            fn $assign_method(&mut self, rhs: &BigUint) {
+                *self = (&*self).$method(rhs);
+            }
+        }
+
+        impl $assign_trait<BigUint> for BigUint {
+            fn $assign_method(&mut self, rhs: BigUint) {
+                *self = (&*self).$method(&rhs);
+            }
+        }
+
+        impl $assign_trait<u64> for BigUint {
+            fn $assign_method(&mut self, rhs: u64) {
+                *self = (&*self).$method(&BigUint::from(rhs));
+            }
+        }
+    };
+}
+
+forward_binop!(Add, add, AddAssign, add_assign);
+forward_binop!(Sub, sub, SubAssign, sub_assign);
+forward_binop!(Mul, mul, MulAssign, mul_assign);
+forward_binop!(Div, div, DivAssign, div_assign);
+forward_binop!(Rem, rem, RemAssign, rem_assign);
+
+impl BitAnd<BigUint> for BigUint {
+    type Output = BigUint;
+
+    fn bitand(self, rhs: BigUint) -> BigUint {
+        &self & &rhs
+    }
+}
+
+impl BitOr<BigUint> for BigUint {
+    type Output = BigUint;
+
+    fn bitor(self, rhs: BigUint) -> BigUint {
+        &self | &rhs
+    }
+}
+
+impl BitXor<BigUint> for BigUint {
+    type Output = BigUint;
+
+    fn bitxor(self, rhs: BigUint) -> BigUint {
+        &self ^ &rhs
+    }
+}
+
+impl Shl<usize> for BigUint {
+    type Output = BigUint;
+
+    fn shl(self, shift: usize) -> BigUint {
+        &self << shift
+    }
+}
+
+impl Shr<usize> for BigUint {
+    type Output = BigUint;
+
+    fn shr(self, shift: usize) -> BigUint {
+        &self >> shift
+    }
+}
+
+impl ShlAssign<usize> for BigUint {
+    fn shl_assign(&mut self, shift: usize) {
+        *self = &*self << shift;
+    }
+}
+
+impl ShrAssign<usize> for BigUint {
+    fn shr_assign(&mut self, shift: usize) {
+        *self = &*self >> shift;
+    }
+}
+
+/// Compares two normalized little endian limb slices
+fn cmp_limbs(a: &[u64], b: &[u64]) -> Ordering {
+    a.len()
+        .cmp(&b.len())
+        .then_with(|| a.iter().rev().cmp(b.iter().rev()))
+}
+
+/// Subtracts `b` from `a` in place. `a` must not be smaller than `b`
+fn sub_assign_limbs(a: &mut [u64], b: &[u64]) {
+    let mut borrow = false;
+    for (i, limb) in a.iter_mut().enumerate() {
+        let other = b.get(i).copied().unwrap_or(0);
+        if i >= b.len() && !borrow {
+            break;
+        }
+        let (difference, b1) = limb.overflowing_sub(other);
+        let (difference, b2) = difference.overflowing_sub(borrow as u64);
+        *limb = difference;
+        borrow = b1 || b2;
+    }
+}
+
+/// Divides little endian limbs by a single limb, returning the quotient limbs (possibly with
+/// trailing zeros) and the remainder
+fn div_rem_limb(limbs: &[u64], divisor: u64) -> (Vec<u64>, u64) {
+    let mut quotient = vec![0; limbs.len()];
+    let mut remainder = 0u128;
+
+    for (i, &limb) in limbs.iter().enumerate().rev() {
+        let current = (remainder << 64) | limb as u128;
+        quotient[i] = (current / divisor as u128) as u64;
+        remainder = current % divisor as u128;
+    }
+
+    (quotient, remainder as u64)
+}
+
+/// Divides `u` by `v` (at least two limbs, `u >= v`) with Knuth's algorithm D and returns the
+/// quotient and remainder limbs
+fn div_rem_knuth(u: &[u64], v: &[u64]) -> (Vec<u64>, Vec<u64>) {
+    // normalize so that the most significant limb of the divisor has its top bit set, which
+    // makes the quotient digit estimates off by at most 2
+    let shift = v[v.len() - 1].leading_zeros() as usize;
+    let vn = (&BigUint::from_limbs(v) << shift).limbs;
+    let mut un = (&BigUint::from_limbs(u) << shift).limbs;
+    un.resize(u.len() + 1, 0);
+
+    let n = vn.len();
+    let m = un.len() - n;
+    let mut quotient = vec![0u64; m];
+    let base = 1u128 << 64;
+
+    for j in (0..m).rev() {
+        let numerator = ((un[j + n] as u128) << 64) | un[j + n - 1] as u128;
+        let mut qhat = numerator / vn[n - 1] as u128;
+        let mut rhat = numerator % vn[n - 1] as u128;
+
+        while qhat >= base || qhat * vn[n - 2] as u128 > ((rhat << 64) | un[j + n - 2] as u128) {
+            qhat -= 1;
+            rhat += vn[n - 1] as u128;
+            if rhat >= base {
+                break;
+            }
+        }
+
+        // un[j..=j + n] -= qhat * vn
+        let mut borrow = 0u64;
+        let mut carry = 0u64;
+        for i in 0..n {
+            let product = qhat * vn[i] as u128 + carry as u128;
+            carry = (product >> 64) as u64;
+            let (difference, b1) = un[i + j].overflowing_sub(product as u64);
+            let (difference, b2) = difference.overflowing_sub(borrow);
+            un[i + j] = difference;
+            borrow = b1 as u64 + b2 as u64;
+        }
+        let (difference, b1) = un[j + n].overflowing_sub(carry);
+        let (difference, b2) = difference.overflowing_sub(borrow);
+        un[j + n] = difference;
+
+        // the estimate was one too large, add the divisor back
+        if b1 || b2 {
+            qhat -= 1;
+            let mut carry = false;
+            for i in 0..n {
+                let (sum, c1) = un[i + j].overflowing_add(vn[i]);
+                let (sum, c2) = sum.overflowing_add(carry as u64);
+                un[i + j] = sum;
+                carry = c1 || c2;
+            }
+            un[j + n] = un[j + n].wrapping_add(carry as u64);
+        }
+
+        quotient[j] = qhat as u64;
+    }
+
+    let remainder = &BigUint::from_limbs(&un[..n]) >> shift;
+    (quotient, remainder.limbs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn big(s: &str) -> BigUint {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn biguint_decimal_round_trip() {
+        let s = "340282366920938463463374607431768211457123456789";
+        assert_eq!(big(s).to_string(), s);
+        assert_eq!(big("0").to_string(), "0");
+        assert_eq!(big("000123").to_string(), "123");
+        assert_eq!(
+            "12a".parse::<BigUint>(),
+            Err(ParseBigIntError::InvalidDigit)
+        );
+        assert_eq!("".parse::<BigUint>(), Err(ParseBigIntError::Empty));
+    }
+
+    #[test]
+    fn biguint_hex_and_bytes() {
+        let n = BigUint::from_hex("1234567890abcdef1234567890abcdef1").unwrap();
+        assert_eq!(n.to_hex(), "01234567890abcdef1234567890abcdef1");
+        assert_eq!(format!("{:x}", n), "1234567890abcdef1234567890abcdef1");
+        assert_eq!(n.to_string(), "387165715202420043663511446871040909041");
+
+        let bytes = n.to_be_bytes();
+        assert_eq!(bytes.len(), 17);
+        assert_eq!(BigUint::from_be_bytes(&bytes), n);
+        assert_eq!(BigUint::from_be_bytes(&[0, 0, 1]), BigUint::one());
+        assert_eq!(BigUint::zero().to_be_bytes(), vec![]);
+        assert_eq!(BigUint::one().to_be_bytes_padded(3), Some(vec![0, 0, 1]));
+        assert_eq!(n.to_be_bytes_padded(16), None);
+
+        let base64 = n.to_base64();
+        assert_eq!(BigUint::from_base64(&base64).unwrap(), n);
+    }
+
+    #[test]
+    fn biguint_add_sub() {
+        let a = big("18446744073709551615");
+        let b = big("1");
+        assert_eq!(&a + &b, big("18446744073709551616"));
+        assert_eq!(&(&a + &b) - &b, a);
+
+        let a = big("340282366920938463463374607431768211455");
+        assert_eq!(&a + 1u64, big("340282366920938463463374607431768211456"));
+        assert_eq!(&(&a + 1u64) - &a, BigUint::one());
+        assert_eq!(BigUint::one().checked_sub(&a), None);
+    }
+
+    #[test]
+    #[should_panic]
+    fn biguint_sub_overflow() {
+        let _ = BigUint::one() - BigUint::from(2u64);
+    }
+
+    #[test]
+    fn biguint_mul() {
+        let a = big("123456789012345678901234567890123456789");
+        let b = big("987654321098765432109876543210987654321");
+        let expected =
+            big("121932631137021795226185032733866788594487120865336229233322374638011112635269");
+        assert_eq!(&a * &b, expected);
+        assert_eq!(&a * &BigUint::zero(), BigUint::zero());
+    }
+
+    #[test]
+    fn biguint_div_rem() {
+        let a =
+            big("121932631137021795226185032733866788594487120865336229233322374638011112635270");
+        let b = big("987654321098765432109876543210987654321");
+        let (q, r) = a.div_rem(&b);
+        assert_eq!(q, big("123456789012345678901234567890123456789"));
+        assert_eq!(r, BigUint::one());
+
+        let (q, r) = a.div_rem(&BigUint::from(1_000_000_007u64));
+        assert_eq!(&(&q * 1_000_000_007u64) + &r, a);
+        assert!(r < BigUint::from(1_000_000_007u64));
+
+        let (q, r) = b.div_rem(&a);
+        assert_eq!(q, BigUint::zero());
+        assert_eq!(r, b);
+    }
+
+    #[test]
+    fn biguint_div_rem_add_back_case() {
+        // the quotient estimate needs the rarely taken add back step for these operands
+        let u = BigUint::from_limbs(&[0, 0, 0x8000_0000_0000_0000, 0x7FFF_FFFF_FFFF_FFFF]);
+        let v = BigUint::from_limbs(&[1, 0, 0x8000_0000_0000_0000]);
+        let (q, r) = u.div_rem(&v);
+        assert_eq!(&(&q * &v) + &r, u);
+        assert!(r < v);
+    }
+
+    #[test]
+    fn biguint_div_rem_random() {
+        let mut state = 0x1234_5678_9ABC_DEF0u64;
+        let mut next = || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+
+        for size in 2..8 {
+            for _ in 0..50 {
+                let u: Vec<u64> = (0..2 * size).map(|_| next()).collect();
+                let mut v: Vec<u64> = (0..size).map(|_| next()).collect();
+                v[size - 1] >>= next() % 64;
+                let (u, v) = (BigUint::from_limbs(&u), BigUint::from_limbs(&v));
+
+                let (q, r) = u.div_rem(&v);
+                assert_eq!(&(&q * &v) + &r, u);
+                assert!(r < v);
+            }
+        }
+    }
+
+    #[test]
+    fn biguint_shifts_and_bits() {
+        let n = big("12345678901234567890123");
+        assert_eq!(&(&n << 100) >> 100, n);
+        assert_eq!(&n >> 200, BigUint::zero());
+        assert_eq!(&BigUint::one() << 64, big("18446744073709551616"));
+        assert_eq!((BigUint::one() << 64).bits(), 65);
+        assert_eq!((BigUint::one() << 64).trailing_zeros(), Some(64));
+        assert_eq!(BigUint::zero().trailing_zeros(), None);
+
+        let mut m = BigUint::zero();
+        m.set_bit(130, true);
+        assert!(m.bit(130));
+        assert!(!m.bit(129));
+        m.set_bit(130, false);
+        assert!(m.is_zero());
+
+        let a = BigUint::from(0b1100u64);
+        let b = BigUint::from(0b1010u64);
+        assert_eq!(&a & &b, BigUint::from(0b1000u64));
+        assert_eq!(&a | &b, BigUint::from(0b1110u64));
+        assert_eq!(&a ^ &b, BigUint::from(0b0110u64));
+    }
+
+    #[test]
+    fn biguint_modpow() {
+        let base = big("4");
+        let exponent = big("123456789012345678901234567890");
+        let modulus = big("170141183460469231731687303715884105727");
+        let expected = big("9444732965739290427392");
+        assert_eq!(base.modpow(&exponent, &modulus), expected);
+        assert_eq!(base.modpow(&BigUint::zero(), &modulus), BigUint::one());
+        assert_eq!(base.modpow(&exponent, &BigUint::one()), BigUint::zero());
+        assert_eq!(base.modpow_ct(&exponent, &modulus), expected);
+        assert_eq!(base.modpow_schoolbook(&exponent, &modulus), expected);
+
+        let even_modulus = &modulus + 1u64;
+        let expected = base.modpow_schoolbook(&exponent, &even_modulus);
+        assert_eq!(base.modpow(&exponent, &even_modulus), expected);
+    }
+
+    #[test]
+    fn biguint_gcd_lcm_modinv() {
+        let a = big("123456789012345678901234567890");
+        let b = big("987654321098765432109876543210");
+        assert_eq!(a.gcd(&b), big("9000000000900000000090"));
+        assert_eq!(a.lcm(&b), big("13548070124980948012498094801236261410"));
+
+        let modulus = big("170141183460469231731687303715884105727");
+        let inverse = a.modinv(&modulus).unwrap();
+        assert_eq!(&(&a * &inverse) % &modulus, BigUint::one());
+        assert_eq!(a.modinv(&b), None);
+    }
+
+    #[test]
+    fn biguint_nth_root() {
+        let root = big("123456789012345678901234567890");
+        let cube = root.pow(3);
+        assert_eq!(cube.nth_root(3), root);
+        assert_eq!((&cube - 1u64).nth_root(3), &root - 1u64);
+        assert_eq!((&cube + 1u64).nth_root(3), root);
+        assert_eq!(big("99").sqrt(), big("9"));
+        assert_eq!(big("1").nth_root(5), big("1"));
+        assert_eq!(big("0").sqrt(), big("0"));
+    }
+}
@@ -0,0 +1,96 @@
+use std::hint::black_box;
+
+/// Returns `0xFF` if `x` is zero and `0x00` otherwise, without branching on `x`
+pub fn ct_is_zero_u8(x: u8) -> u8 {
+    let x = black_box(x) as u16;
+    (x.wrapping_sub(1) >> 8) as u8
+}
+
+/// Returns `0xFF` if `a == b` and `0x00` otherwise, without branching on the values
+pub fn ct_eq_u8(a: u8, b: u8) -> u8 {
+    ct_is_zero_u8(a ^ b)
+}
+
+/// Returns `0xFF` if `a < b` and `0x00` otherwise, without branching on the values
+pub fn ct_lt_u8(a: u8, b: u8) -> u8 {
+    let difference = (black_box(a) as u16).wrapping_sub(black_box(b) as u16);
+    (difference >> 8) as u8
+}
+
+/// Selects `a` if `mask` is `0xFF` and `b` if `mask` is `0x00`, without branching on `mask`
+///
+/// # Examples
+/// ```
+/// use primitives::{ct_eq_u8, ct_select};
+///
+/// assert_eq!(ct_select(ct_eq_u8(3, 3), 0xAA, 0xBB), 0xAA);
+/// assert_eq!(ct_select(ct_eq_u8(3, 4), 0xAA, 0xBB), 0xBB);
+/// ```
+pub fn ct_select(mask: u8, a: u8, b: u8) -> u8 {
+    let mask = black_box(mask);
+    (a & mask) | (b & !mask)
+}
+
+/// Checks whether all the bytes of `data` are zero in time that only depends on its length
+pub fn ct_is_zero(data: &[u8]) -> bool {
+    let accumulator = data.iter().fold(0, |acc, &b| black_box(acc | b));
+    ct_is_zero_u8(accumulator) == 0xFF
+}
+
+/// Checks whether two byte slices are equal in time that only depends on their lengths. Use it
+/// to compare MACs, tags and any other secret-dependent value
+///
+/// # Examples
+/// ```
+/// use primitives::ct_eq;
+///
+/// assert!(ct_eq("tag".as_bytes(), "tag".as_bytes()));
+/// assert!(!ct_eq("tag".as_bytes(), "tab".as_bytes()));
+/// assert!(!ct_eq("tag".as_bytes(), "tags".as_bytes()));
+/// ```
+pub fn ct_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+
+    let accumulator = a
+        .iter()
+        .zip(b)
+        .fold(0, |acc, (x, y)| black_box(acc | (x ^ y)));
+    ct_is_zero_u8(accumulator) == 0xFF
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ct_masks_exhaustive() {
+        for a in 0..=255u8 {
+            assert_eq!(ct_is_zero_u8(a), if a == 0 { 0xFF } else { 0x00 });
+            for b in 0..=255u8 {
+                assert_eq!(ct_eq_u8(a, b), if a == b { 0xFF } else { 0x00 });
+                assert_eq!(ct_lt_u8(a, b), if a < b { 0xFF } else { 0x00 });
+            }
+        }
+    }
+
+    #[test]
+    fn ct_select_bytes() {
+        assert_eq!(ct_select(0xFF, 1, 2), 1);
+        assert_eq!(ct_select(0x00, 1, 2), 2);
+    }
+
+    #[test]
+    fn ct_slices() {
+        assert!(ct_is_zero(&[0; 32]));
+        assert!(ct_is_zero(&[]));
+        assert!(!ct_is_zero(&[0, 0, 0x80, 0]));
+
+        assert!(ct_eq(&[], &[]));
+        assert!(ct_eq(&[1, 2, 3], &[1, 2, 3]));
+        assert!(!ct_eq(&[1, 2, 3], &[1, 2, 4]));
+        assert!(!ct_eq(&[1, 2, 3], &[0, 2, 3]));
+        assert!(!ct_eq(&[1, 2, 3], &[1, 2]));
+    }
+}
@@ -10,3 +10,35 @@ impl fmt::Display for InvalidBlockSizeError {
         "the block size must not exceed 255 bytes and must greater than 0".fmt(f)
     }
 }
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidPaddingError;
+
+impl std::error::Error for InvalidPaddingError {}
+
+impl fmt::Display for InvalidPaddingError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        "the input text does not have valid PKCS#7 padding".fmt(f)
+    }
+}
+
+/// Errors that can occur while parsing a decimal big integer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseBigIntError {
+    /// The string is empty
+    Empty,
+
+    /// The string contains a character that is not a decimal digit
+    InvalidDigit,
+}
+
+impl std::error::Error for ParseBigIntError {}
+
+impl fmt::Display for ParseBigIntError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseBigIntError::Empty => "cannot parse an integer from an empty string".fmt(f),
+            ParseBigIntError::InvalidDigit => "invalid digit found in string".fmt(f),
+        }
+    }
+}
@@ -0,0 +1,165 @@
+/// A cryptographic hash function with a streaming interface
+pub trait HashFunction: Clone + Default {
+    /// The size in bytes of the blocks processed by the compression function
+    const BLOCK_SIZE: usize;
+
+    /// The size in bytes of the digest
+    const OUTPUT_SIZE: usize;
+
+    /// Feeds more data into the hasher
+    fn update(&mut self, data: &[u8]);
+
+    /// Pads the data processed so far and returns the digest
+    fn finalize(self) -> Vec<u8>;
+
+    /// Computes the digest of the given data
+    fn digest(data: &[u8]) -> Vec<u8> {
+        let mut hasher = Self::default();
+        hasher.update(data);
+        hasher.finalize()
+    }
+}
+
+/// A Merkle–Damgård hash function whose digest is its whole chaining value, so that hashing
+/// can be resumed from a digest. This is what makes secret-prefix MACs built on it vulnerable
+/// to length extension. Truncated variants (SHA-224, SHA-384) do not qualify.
+pub trait MerkleDamgard: HashFunction {
+    /// Returns the padding the hash function appends to a message of the given length in
+    /// bytes before the last compression
+    fn padding(length: u64) -> Vec<u8>;
+
+    /// Creates a hasher that resumes from a digest
+    ///
+    /// # Args
+    /// `digest`: a digest of `OUTPUT_SIZE` bytes
+    ///
+    /// `length`: the number of bytes already processed, including the padding. It must be a
+    /// multiple of `BLOCK_SIZE`
+    ///
+    /// # Panics
+    /// If `digest` is not `OUTPUT_SIZE` bytes long
+    fn from_digest(digest: &[u8], length: u64) -> Self;
+}
+
+/// The byte order of the length field appended by `md_padding`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Endianness {
+    Big,
+    Little,
+}
+
+/// Returns the Merkle–Damgård strengthening padding for a message of the given length in
+/// bytes: a 1 bit, zeros up to `length_size` bytes before the end of a block and the message
+/// length in bits as a `length_size` bytes integer
+///
+/// # Args
+/// `length`: the message length in bytes
+///
+/// `block_size`: the block size of the hash function in bytes
+///
+/// `length_size`: the size in bytes of the length field, at most 16
+///
+/// `endianness`: the byte order of the length field
+pub(crate) fn md_padding(
+    length: u64,
+    block_size: usize,
+    length_size: usize,
+    endianness: Endianness,
+) -> Vec<u8> {
+    let mut padding = vec![0x80];
+    let zeros =
+        (2 * block_size - length_size - 1 - (length % block_size as u64) as usize) % block_size;
+    padding.extend(vec![0; zeros]);
+
+    // the length field is taken modulo 2^(8 * length_size)
+    let bits = length as u128 * 8;
+    match endianness {
+        Endianness::Big => padding.extend(&bits.to_be_bytes()[16 - length_size..]),
+        Endianness::Little => padding.extend(&bits.to_le_bytes()[..length_size]),
+    }
+    padding
+}
+
+/// Accumulates the input of a Merkle–Damgård hash function into blocks of `BLOCK_SIZE` bytes
+/// and counts the bytes processed so far
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct MdBuffer<const BLOCK_SIZE: usize> {
+    buffer: Vec<u8>,
+    length: u64,
+}
+
+impl<const BLOCK_SIZE: usize> MdBuffer<BLOCK_SIZE> {
+    /// Creates an empty buffer for a hasher that already processed `length` bytes, which
+    /// must be a multiple of `BLOCK_SIZE`
+    pub(crate) fn new(length: u64) -> Self {
+        MdBuffer {
+            buffer: Vec::with_capacity(BLOCK_SIZE),
+            length,
+        }
+    }
+
+    /// Returns the number of bytes processed so far
+    pub(crate) fn length(&self) -> u64 {
+        self.length
+    }
+
+    /// Appends data to the buffer and calls `compress` on every block completed
+    pub(crate) fn update(&mut self, data: &[u8], mut compress: impl FnMut(&[u8])) {
+        self.length += data.len() as u64;
+
+        let mut data = data;
+        if !self.buffer.is_empty() {
+            let missing = (BLOCK_SIZE - self.buffer.len()).min(data.len());
+            self.buffer.extend(&data[..missing]);
+            data = &data[missing..];
+
+            if self.buffer.len() < BLOCK_SIZE {
+                return;
+            }
+
+            compress(&self.buffer);
+            self.buffer.clear();
+        }
+
+        let mut blocks = data.chunks_exact(BLOCK_SIZE);
+        for block in blocks.by_ref() {
+            compress(block);
+        }
+        self.buffer.extend(blocks.remainder());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn md_padding_completes_blocks() {
+        for length in 0..300u64 {
+            let padding = md_padding(length, 64, 8, Endianness::Big);
+            assert_eq!((length as usize + padding.len()) % 64, 0);
+            assert!(padding.len() > 8 && padding.len() <= 72);
+            assert_eq!(padding[padding.len() - 8..], (length * 8).to_be_bytes());
+
+            let padding = md_padding(length, 128, 16, Endianness::Little);
+            assert_eq!((length as usize + padding.len()) % 128, 0);
+            assert_eq!(
+                padding[padding.len() - 16..],
+                (length as u128 * 8).to_le_bytes()
+            );
+        }
+    }
+
+    #[test]
+    fn md_buffer_splits_blocks() {
+        let data: Vec<u8> = (0..200).collect();
+        let mut buffer = MdBuffer::<64>::new(0);
+        let mut blocks = vec![];
+        for chunk in data.chunks(7) {
+            buffer.update(chunk, |block| blocks.push(block.to_vec()));
+        }
+
+        assert_eq!(buffer.length(), 200);
+        assert_eq!(blocks, data[..192].chunks(64).collect::<Vec<_>>());
+    }
+}
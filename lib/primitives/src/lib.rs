@@ -1,6 +1,45 @@
+mod bigint;
+pub use bigint::*;
+
+mod biguint;
+pub use biguint::*;
+
+mod constant_time;
+pub use constant_time::*;
+
 mod error;
 pub use error::*;
 
+mod hash;
+pub use hash::*;
+
+mod mac;
+pub use mac::*;
+
+mod md4;
+pub use md4::*;
+
+mod montgomery;
+pub use montgomery::*;
+
+mod mt19937;
+pub use mt19937::*;
+
+mod prime;
+pub use prime::*;
+
+mod rng;
+pub use rng::*;
+
+mod sha1;
+pub use sha1::*;
+
+mod sha2;
+pub use sha2::*;
+
+mod stream_cipher;
+pub use stream_cipher::*;
+
 /// Performs a XOR operation on the given text with the given key
 ///
 /// # Returns
@@ -70,6 +109,55 @@ pub fn pad_pkcs7(text: &[u8], block_size: usize) -> Result<Vec<u8>, InvalidBlock
     Ok(out)
 }
 
+/// Removes the padding bytes from the given text according to PKCS#7 padding rules
+///
+/// # Args
+/// `text`: the padded text
+///
+/// `block_size`: the block size the text was padded to
+///
+/// # Returns
+/// A `Vec<u8>` with the unpadded text or an InvalidPaddingError if the text length is not
+/// a multiple of `block_size` or the padding bytes are malformed
+///
+/// # Examples
+///
+/// ```
+/// use primitives::unpad_pkcs7;
+///
+/// let text = "ICE ICE BABY\x04\x04\x04\x04".as_bytes();
+/// let result = unpad_pkcs7(text, 16).unwrap();
+/// assert_eq!(result, "ICE ICE BABY".as_bytes());
+///
+/// let text = "ICE ICE BABY\x01\x02\x03\x04".as_bytes();
+/// assert!(unpad_pkcs7(text, 16).is_err());
+/// ```
+pub fn unpad_pkcs7(text: &[u8], block_size: usize) -> Result<Vec<u8>, InvalidPaddingError> {
+    if text.is_empty() || block_size == 0 || !text.len().is_multiple_of(block_size) {
+        return Err(InvalidPaddingError);
+    }
+
+    // the padding is checked in constant time so that the function is not a padding oracle
+    // through its timing
+    let padding_byte = text[text.len() - 1];
+    let mut valid = !ct_is_zero_u8(padding_byte);
+    if block_size < 255 {
+        valid &= !ct_lt_u8(block_size as u8, padding_byte);
+    }
+
+    for (i, &b) in text.iter().rev().take(block_size.min(255)).enumerate() {
+        let in_padding = ct_lt_u8(i as u8, padding_byte);
+        valid &= !in_padding | ct_eq_u8(b, padding_byte);
+    }
+
+    if valid != 0xFF {
+        return Err(InvalidPaddingError);
+    }
+
+    let unpadded = &text[..text.len() - padding_byte as usize];
+    Ok(unpadded.to_vec())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -175,4 +263,59 @@ mod tests {
         ];
         assert_eq!(result, expected);
     }
+
+    #[test]
+    fn pkcs7_unpadding_valid() {
+        let text = "ICE ICE BABY\x04\x04\x04\x04".as_bytes();
+        let result = unpad_pkcs7(text, BLOCK_SIZE).unwrap();
+        let expected = "ICE ICE BABY".as_bytes();
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn pkcs7_unpadding_full_block() {
+        let text = [0x10; 16];
+        let result = unpad_pkcs7(&text, BLOCK_SIZE).unwrap();
+        assert_eq!(result, vec![]);
+    }
+
+    #[test]
+    fn pkcs7_unpadding_wrong_padding_bytes() {
+        let text = "ICE ICE BABY\x01\x02\x03\x04".as_bytes();
+        let result = unpad_pkcs7(text, BLOCK_SIZE);
+        assert_eq!(result, Err(InvalidPaddingError));
+    }
+
+    #[test]
+    fn pkcs7_unpadding_zero_padding_byte() {
+        let text = "ICE ICE BABY\x00\x00\x00\x00".as_bytes();
+        let result = unpad_pkcs7(text, BLOCK_SIZE);
+        assert_eq!(result, Err(InvalidPaddingError));
+    }
+
+    #[test]
+    fn pkcs7_unpadding_not_multiple_of_block_size() {
+        let text = "ICE ICE BABY\x03\x03\x03".as_bytes();
+        let result = unpad_pkcs7(text, BLOCK_SIZE);
+        assert_eq!(result, Err(InvalidPaddingError));
+    }
+
+    #[test]
+    fn pkcs7_unpadding_larger_than_block_size() {
+        let text = [0x11; 32];
+        let result = unpad_pkcs7(&text, BLOCK_SIZE);
+        assert_eq!(result, Err(InvalidPaddingError));
+    }
+
+    #[test]
+    fn pkcs7_unpadding_every_valid_length() {
+        for padding in 1..=BLOCK_SIZE {
+            let mut text = vec![b'A'; 2 * BLOCK_SIZE - padding];
+            text.extend(vec![padding as u8; padding]);
+
+            let result = unpad_pkcs7(&text, BLOCK_SIZE).unwrap();
+            let expected = vec![b'A'; 2 * BLOCK_SIZE - padding];
+            assert_eq!(result, expected);
+        }
+    }
 }
@@ -0,0 +1,205 @@
+use std::marker::PhantomData;
+
+use crate::{ct_eq, HashFunction};
+
+const IPAD: u8 = 0x36;
+const OPAD: u8 = 0x5C;
+
+/// A message authentication code
+pub trait Mac {
+    /// Computes the tag of a message under a key
+    fn mac(key: &[u8], message: &[u8]) -> Vec<u8>;
+
+    /// Checks in constant time whether `tag` is the tag of `message` under `key`
+    fn verify(key: &[u8], message: &[u8], tag: &[u8]) -> bool {
+        let expected = Self::mac(key, message);
+        ct_eq(&expected, tag)
+    }
+}
+
+/// A streaming HMAC (RFC 2104) over any hash function
+///
+/// # Examples
+/// ```
+/// use encoding::Encode;
+/// use primitives::{Hmac, Sha256};
+///
+/// let mut hmac = Hmac::<Sha256>::new("key".as_bytes());
+/// hmac.update("The quick brown fox ".as_bytes());
+/// hmac.update("jumps over the lazy dog".as_bytes());
+/// let result = hmac.finalize();
+///
+/// assert_eq!(
+///     result.to_hex(),
+///     "f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8"
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct Hmac<H: HashFunction> {
+    inner: H,
+    outer_key: Vec<u8>,
+}
+
+impl<H: HashFunction> Hmac<H> {
+    /// Creates a new `Hmac` keyed with `key`. Keys longer than the block size of the hash
+    /// function are hashed first
+    pub fn new(key: &[u8]) -> Hmac<H> {
+        let mut block_key = if key.len() > H::BLOCK_SIZE {
+            H::digest(key)
+        } else {
+            key.to_vec()
+        };
+        block_key.resize(H::BLOCK_SIZE, 0);
+
+        let inner_key: Vec<u8> = block_key.iter().map(|b| b ^ IPAD).collect();
+        let outer_key = block_key.iter().map(|b| b ^ OPAD).collect();
+
+        let mut inner = H::default();
+        inner.update(&inner_key);
+
+        Hmac { inner, outer_key }
+    }
+
+    /// Feeds more data into the MAC
+    pub fn update(&mut self, data: &[u8]) {
+        self.inner.update(data);
+    }
+
+    /// Returns the tag of the data processed so far
+    pub fn finalize(self) -> Vec<u8> {
+        let inner = self.inner.finalize();
+
+        let mut outer = H::default();
+        outer.update(&self.outer_key);
+        outer.update(&inner);
+        outer.finalize()
+    }
+}
+
+impl<H: HashFunction> Mac for Hmac<H> {
+    fn mac(key: &[u8], message: &[u8]) -> Vec<u8> {
+        let mut hmac = Hmac::<H>::new(key);
+        hmac.update(message);
+        hmac.finalize()
+    }
+}
+
+/// The naive secret-prefix MAC `H(key || message)`. It is broken by length extension for every
+/// Merkle–Damgård hash function and only exists for contrast with `Hmac`
+///
+/// # Examples
+/// ```
+/// use primitives::{Mac, SecretPrefixMac, Sha1};
+///
+/// let key = "YELLOW SUBMARINE".as_bytes();
+/// let tag = SecretPrefixMac::<Sha1>::mac(key, "message".as_bytes());
+///
+/// assert!(SecretPrefixMac::<Sha1>::verify(key, "message".as_bytes(), &tag));
+/// assert!(!SecretPrefixMac::<Sha1>::verify(key, "massage".as_bytes(), &tag));
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SecretPrefixMac<H: HashFunction> {
+    hash: PhantomData<H>,
+}
+
+impl<H: HashFunction> Mac for SecretPrefixMac<H> {
+    fn mac(key: &[u8], message: &[u8]) -> Vec<u8> {
+        let mut hasher = H::default();
+        hasher.update(key);
+        hasher.update(message);
+        hasher.finalize()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use encoding::{Decode, Encode};
+
+    use crate::{Md4, Sha1, Sha224, Sha256, Sha384, Sha512};
+
+    use super::*;
+
+    #[test]
+    fn hmac_rfc_vectors() {
+        let key = [0x0B; 20];
+        let message = "Hi There".as_bytes();
+
+        assert_eq!(
+            Hmac::<Sha1>::mac(&key, message).to_hex(),
+            "b617318655057264e28bc0b6fb378c8ef146be00"
+        );
+        assert_eq!(
+            Hmac::<Sha224>::mac(&key, message).to_hex(),
+            "896fb1128abbdf196832107cd49df33f47b4b1169912ba4f53684b22"
+        );
+        assert_eq!(
+            Hmac::<Sha256>::mac(&key, message).to_hex(),
+            "b0344c61d8db38535ca8afceaf0bf12b881dc200c9833da726e9376c2e32cff7"
+        );
+        assert_eq!(
+            Hmac::<Sha384>::mac(&key, message).to_hex(),
+            "afd03944d84895626b0825f4ab46907f15f9dadbe4101ec682aa034c7cebc59c\
+             faea9ea9076ede7f4af152e8b2fa9cb6"
+        );
+        assert_eq!(
+            Hmac::<Sha512>::mac(&key, message).to_hex(),
+            "87aa7cdea5ef619d4ff0b4241a1d6cb02379f4e2ce4ec2787ad0b30545e17cde\
+             daa833b7d6b8a702038b274eaea3f4e4be9d914eeb61f1702e696c203a126854"
+        );
+    }
+
+    #[test]
+    fn hmac_key_longer_than_block() {
+        let key = [0xAA; 131];
+        let message = "Test Using Larger Than Block-Size Key - Hash Key First".as_bytes();
+
+        let result = Hmac::<Sha256>::mac(&key, message);
+        let expected =
+            Vec::from_hex("60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54")
+                .unwrap();
+        assert_eq!(result, expected);
+
+        let result = Hmac::<Sha512>::mac(&key, message);
+        let expected = Vec::from_hex(
+            "80b24263c7c1a3ebb71493c1dd7be8b49b46d1f41b4aeec1121b013783f8f352\
+             6b56d037e05f2598bd0fd2215d6a1e5295e64f73f63f0aec8b915a985d786598",
+        )
+        .unwrap();
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn hmac_streaming() {
+        let message = [b'x'; 300];
+
+        let mut hmac = Hmac::<Md4>::new("key".as_bytes());
+        for chunk in message.chunks(17) {
+            hmac.update(chunk);
+        }
+        let result = hmac.finalize();
+
+        assert_eq!(result, Hmac::<Md4>::mac("key".as_bytes(), &message));
+    }
+
+    #[test]
+    fn secret_prefix_mac_is_hash_of_key_and_message() {
+        let result = SecretPrefixMac::<Sha1>::mac("key".as_bytes(), "message".as_bytes());
+        let expected = Sha1::digest("keymessage".as_bytes());
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn mac_verify() {
+        let key = "key".as_bytes();
+        let message = "message".as_bytes();
+        let tag = Hmac::<Sha256>::mac(key, message);
+
+        assert!(Hmac::<Sha256>::verify(key, message, &tag));
+        assert!(!Hmac::<Sha256>::verify(key, "other".as_bytes(), &tag));
+        assert!(!Hmac::<Sha256>::verify(key, message, &tag[..31]));
+
+        let mut tampered = tag.clone();
+        tampered[31] ^= 1;
+        assert!(!Hmac::<Sha256>::verify(key, message, &tampered));
+    }
+}
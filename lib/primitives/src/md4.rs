@@ -0,0 +1,319 @@
+use crate::{hash, Endianness, HashFunction, MdBuffer, MerkleDamgard};
+
+const BLOCK_SIZE: usize = 64;
+
+const INITIAL_STATE: [u32; 4] = [0x67452301, 0xEFCDAB89, 0x98BADCFE, 0x10325476];
+
+/// The left rotations applied in each of the 4 steps of a group in the three rounds
+pub const MD4_SHIFTS: [[u32; 4]; 3] = [[3, 7, 11, 19], [3, 5, 9, 13], [3, 9, 11, 15]];
+
+/// The order in which the message words are processed in each of the three rounds
+pub const MD4_WORD_ORDER: [[usize; 16]; 3] = [
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+    [0, 4, 8, 12, 1, 5, 9, 13, 2, 6, 10, 14, 3, 7, 11, 15],
+    [0, 8, 4, 12, 2, 10, 6, 14, 1, 9, 5, 13, 3, 11, 7, 15],
+];
+
+const ROUND_CONSTANTS: [u32; 3] = [0, 0x5A827999, 0x6ED9EBA1];
+
+/// A streaming MD4 hasher
+///
+/// # Examples
+/// ```
+/// use encoding::Encode;
+/// use primitives::Md4;
+///
+/// let mut hasher = Md4::new();
+/// hasher.update("message ".as_bytes());
+/// hasher.update("digest".as_bytes());
+/// let result = hasher.finalize();
+///
+/// assert_eq!(result.to_hex(), "d9130a8164549fe818874806e1c7014b");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Md4 {
+    state: [u32; 4],
+    buffer: MdBuffer<BLOCK_SIZE>,
+}
+
+impl Md4 {
+    /// Creates a new `Md4` hasher with the standard initial state
+    pub fn new() -> Md4 {
+        Md4::from_state(INITIAL_STATE, 0)
+    }
+
+    /// Creates a new `Md4` hasher that resumes from an arbitrary internal state
+    ///
+    /// # Args
+    /// `state`: the four 32 bit words of the chaining value
+    ///
+    /// `length`: the number of bytes already processed, which must be a multiple of the 64
+    /// bytes block size. It is used to compute the final padding
+    pub fn from_state(state: [u32; 4], length: u64) -> Md4 {
+        Md4 {
+            state,
+            buffer: MdBuffer::new(length),
+        }
+    }
+
+    /// Computes the MD4 digest of the given data
+    ///
+    /// # Examples
+    /// ```
+    /// use encoding::Encode;
+    /// use primitives::Md4;
+    ///
+    /// let result = Md4::digest("abc".as_bytes());
+    /// assert_eq!(result.to_hex(), "a448017aaf21d8525fc10ae87aa6729d");
+    /// ```
+    pub fn digest(data: &[u8]) -> Vec<u8> {
+        let mut hasher = Md4::new();
+        hasher.update(data);
+        hasher.finalize()
+    }
+
+    /// Returns the current chaining value
+    pub fn state(&self) -> [u32; 4] {
+        self.state
+    }
+
+    /// Returns the number of bytes processed so far
+    pub fn length(&self) -> u64 {
+        self.buffer.length()
+    }
+
+    /// Feeds more data into the hasher
+    pub fn update(&mut self, data: &[u8]) {
+        let state = &mut self.state;
+        self.buffer.update(data, |block| md4_compress(state, block));
+    }
+
+    /// Pads the data processed so far and returns the 16 bytes digest
+    pub fn finalize(mut self) -> Vec<u8> {
+        let padding = md_padding(self.buffer.length());
+        self.update(&padding);
+
+        self.state.iter().flat_map(|w| w.to_le_bytes()).collect()
+    }
+}
+
+impl Default for Md4 {
+    fn default() -> Self {
+        Md4::new()
+    }
+}
+
+impl HashFunction for Md4 {
+    const BLOCK_SIZE: usize = BLOCK_SIZE;
+    const OUTPUT_SIZE: usize = 16;
+
+    fn update(&mut self, data: &[u8]) {
+        Md4::update(self, data);
+    }
+
+    fn finalize(self) -> Vec<u8> {
+        Md4::finalize(self)
+    }
+}
+
+impl MerkleDamgard for Md4 {
+    fn padding(length: u64) -> Vec<u8> {
+        md_padding(length)
+    }
+
+    fn from_digest(digest: &[u8], length: u64) -> Self {
+        assert_eq!(digest.len(), 16, "invalid digest size");
+
+        let mut state = [0; 4];
+        for (word, bytes) in state.iter_mut().zip(digest.chunks_exact(4)) {
+            *word = u32::from_le_bytes(bytes.try_into().unwrap());
+        }
+        Md4::from_state(state, length)
+    }
+}
+
+/// Returns the MD4 padding for a message of the given length in bytes: a 1 bit, zeros up to
+/// 56 bytes modulo 64 and the message length in bits as a 64 bit little endian integer
+fn md_padding(length: u64) -> Vec<u8> {
+    hash::md_padding(length, BLOCK_SIZE, 8, Endianness::Little)
+}
+
+/// The round 1 boolean function: `x ? y : z` bitwise
+pub fn md4_f(x: u32, y: u32, z: u32) -> u32 {
+    (x & y) | (!x & z)
+}
+
+/// The round 2 boolean function: the bitwise majority of `x`, `y` and `z`
+pub fn md4_g(x: u32, y: u32, z: u32) -> u32 {
+    (x & y) | (x & z) | (y & z)
+}
+
+/// The round 3 boolean function: the bitwise parity of `x`, `y` and `z`
+pub fn md4_h(x: u32, y: u32, z: u32) -> u32 {
+    x ^ y ^ z
+}
+
+/// Returns the boolean function of the given round applied to `x`, `y` and `z`
+fn md4_round_function(round: usize, x: u32, y: u32, z: u32) -> u32 {
+    match round {
+        0 => md4_f(x, y, z),
+        1 => md4_g(x, y, z),
+        2 => md4_h(x, y, z),
+        _ => panic!("MD4 only has rounds 0, 1 and 2"),
+    }
+}
+
+/// Computes one MD4 step, returning the new value of the chaining variable `a`
+///
+/// # Args
+/// `round`: the round number (0, 1 or 2)
+///
+/// `a`, `b`, `c`, `d`: the chaining variables, in the order the step uses them
+///
+/// `x`: the message word
+///
+/// `s`: the left rotation
+///
+/// # Panics
+/// If `round` is greater than 2
+pub fn md4_step(round: usize, a: u32, b: u32, c: u32, d: u32, x: u32, s: u32) -> u32 {
+    let f = md4_round_function(round, b, c, d);
+
+    a.wrapping_add(f)
+        .wrapping_add(x)
+        .wrapping_add(ROUND_CONSTANTS[round])
+        .rotate_left(s)
+}
+
+/// Inverts one MD4 step, returning the message word that turns `a` into `new_a`. This is the
+/// building block of message modification in collision attacks
+///
+/// # Panics
+/// If `round` is greater than 2
+///
+/// # Examples
+/// ```
+/// use primitives::{md4_step, md4_step_inverse};
+///
+/// let (a, b, c, d) = (0x67452301, 0xEFCDAB89, 0x98BADCFE, 0x10325476);
+/// let new_a = md4_step(0, a, b, c, d, 0xCAFEBABE, 3);
+/// assert_eq!(md4_step_inverse(0, new_a, a, b, c, d, 3), 0xCAFEBABE);
+/// ```
+pub fn md4_step_inverse(round: usize, new_a: u32, a: u32, b: u32, c: u32, d: u32, s: u32) -> u32 {
+    let f = md4_round_function(round, b, c, d);
+
+    new_a
+        .rotate_right(s)
+        .wrapping_sub(a)
+        .wrapping_sub(f)
+        .wrapping_sub(ROUND_CONSTANTS[round])
+}
+
+/// Splits a 64 bytes block into its 16 little endian message words
+pub fn md4_message_words(block: &[u8]) -> [u32; 16] {
+    let mut words = [0u32; 16];
+    for (word, chunk) in words.iter_mut().zip(block.chunks_exact(4)) {
+        *word = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+    }
+    words
+}
+
+/// Processes a 64 bytes block, updating the chaining value
+pub fn md4_compress(state: &mut [u32; 4], block: &[u8]) {
+    let x = md4_message_words(block);
+    let mut v = *state;
+
+    for round in 0..3 {
+        for step in 0..16 {
+            // the chaining variable being updated rotates through a, d, c, b
+            let i = (4 - step % 4) % 4;
+            v[i] = md4_step(
+                round,
+                v[i],
+                v[(i + 1) % 4],
+                v[(i + 2) % 4],
+                v[(i + 3) % 4],
+                x[MD4_WORD_ORDER[round][step]],
+                MD4_SHIFTS[round][step % 4],
+            );
+        }
+    }
+
+    for (s, v) in state.iter_mut().zip(v) {
+        *s = s.wrapping_add(v);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use encoding::Encode;
+
+    use super::*;
+
+    #[test]
+    fn md4_rfc1320_vectors() {
+        let vectors = [
+            ("", "31d6cfe0d16ae931b73c59d7e0c089c0"),
+            ("a", "bde52cb31de33e46245e05fbdbd6fb24"),
+            ("abc", "a448017aaf21d8525fc10ae87aa6729d"),
+            ("message digest", "d9130a8164549fe818874806e1c7014b"),
+            (
+                "abcdefghijklmnopqrstuvwxyz",
+                "d79e1c308aa5bbcdeea8ed63df412da9",
+            ),
+            (
+                "12345678901234567890123456789012345678901234567890123456789012345678901234567890",
+                "e33b4ddc9c38f2199c3e7b164fcc0536",
+            ),
+        ];
+
+        for (input, expected) in vectors {
+            let result = Md4::digest(input.as_bytes()).to_hex();
+            assert_eq!(result, expected);
+        }
+    }
+
+    #[test]
+    fn md4_streaming() {
+        let input =
+            "12345678901234567890123456789012345678901234567890123456789012345678901234567890";
+
+        let mut hasher = Md4::new();
+        for chunk in input.as_bytes().chunks(7) {
+            hasher.update(chunk);
+        }
+        let result = hasher.finalize();
+
+        assert_eq!(result, Md4::digest(input.as_bytes()));
+    }
+
+    #[test]
+    fn md4_resume_from_state() {
+        let message = [b'x'; 150];
+
+        let mut hasher = Md4::new();
+        hasher.update(&message[..128]);
+        let mut resumed = Md4::from_state(hasher.state(), hasher.length());
+        resumed.update(&message[128..]);
+
+        let result = resumed.finalize();
+        let expected = Md4::digest(&message);
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    #[should_panic]
+    fn md4_step_invalid_round() {
+        md4_step(3, 0, 0, 0, 0, 0, 0);
+    }
+
+    #[test]
+    fn md4_step_inverse_all_rounds() {
+        let (a, b, c, d) = (0x01234567, 0x89ABCDEF, 0xFEDCBA98, 0x76543210);
+        for (round, shifts) in MD4_SHIFTS.iter().enumerate() {
+            let new_a = md4_step(round, a, b, c, d, 0xDEADBEEF, shifts[1]);
+            let result = md4_step_inverse(round, new_a, a, b, c, d, shifts[1]);
+            assert_eq!(result, 0xDEADBEEF);
+        }
+    }
+}
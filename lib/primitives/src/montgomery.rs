@@ -0,0 +1,330 @@
+use std::hint::black_box;
+
+use crate::BigUint;
+
+/// Precomputed values for arithmetic in Montgomery form modulo an odd integer `m`.
+///
+/// With `R = 2^(64 * n)` for an `n` limbs modulus, an integer `a` is represented by
+/// `a * R mod m`, and the product of two representations is reduced with word-by-word
+/// Montgomery reduction instead of a long division. All the operations work on fixed-length
+/// limb vectors and end with a branch-free conditional subtraction, so their timing only
+/// depends on the size of the modulus.
+///
+/// # Examples
+/// ```
+/// use primitives::{BigUint, MontgomeryContext};
+///
+/// let modulus = BigUint::from(497u64);
+/// let context = MontgomeryContext::new(&modulus).unwrap();
+///
+/// let base = BigUint::from(4u64);
+/// let exponent = BigUint::from(13u64);
+/// assert_eq!(context.pow(&base, &exponent), BigUint::from(445u64));
+/// assert_eq!(context.pow_ladder(&base, &exponent), BigUint::from(445u64));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MontgomeryContext {
+    modulus: BigUint,
+    /// The limbs of the modulus
+    m: Vec<u64>,
+    /// `-m^-1 mod 2^64`
+    m_prime: u64,
+    /// `R^2 mod m`, used to convert into Montgomery form
+    r2: Vec<u64>,
+    /// `R mod m`, the Montgomery form of 1
+    one: Vec<u64>,
+}
+
+impl MontgomeryContext {
+    /// Creates a context for the given modulus
+    ///
+    /// # Returns
+    /// An `Option` wrapping the context, or `None` if the modulus is even or smaller than 3
+    pub fn new(modulus: &BigUint) -> Option<MontgomeryContext> {
+        if modulus.is_even() || modulus.bits() < 2 {
+            return None;
+        }
+
+        let m = modulus.limbs().to_vec();
+        let n = m.len();
+
+        // Newton iteration for the inverse modulo 2^64, each step doubles the correct bits
+        let mut inverse = 1u64;
+        for _ in 0..6 {
+            inverse = inverse.wrapping_mul(2u64.wrapping_sub(m[0].wrapping_mul(inverse)));
+        }
+
+        let r = &BigUint::one() << (64 * n);
+        let one = fixed_limbs(&(&r % modulus), n);
+        let r2 = fixed_limbs(&(&(&r * &r) % modulus), n);
+
+        Some(MontgomeryContext {
+            modulus: modulus.clone(),
+            m,
+            m_prime: inverse.wrapping_neg(),
+            r2,
+            one,
+        })
+    }
+
+    /// Returns the modulus
+    pub fn modulus(&self) -> &BigUint {
+        &self.modulus
+    }
+
+    /// Converts an integer into Montgomery form
+    pub fn to_montgomery(&self, a: &BigUint) -> BigUint {
+        BigUint::from_limbs(&self.enter(a))
+    }
+
+    /// Converts an integer out of Montgomery form. `a` is reduced modulo `m` first
+    pub fn from_montgomery(&self, a: &BigUint) -> BigUint {
+        BigUint::from_limbs(&self.leave(&self.reduced_limbs(a)))
+    }
+
+    /// Computes the Montgomery product `a * b * R^-1 mod m` of two integers in Montgomery form.
+    /// The operands are reduced modulo `m` first
+    pub fn mul(&self, a: &BigUint, b: &BigUint) -> BigUint {
+        BigUint::from_limbs(&self.mont_mul(&self.reduced_limbs(a), &self.reduced_limbs(b)))
+    }
+
+    /// Computes `base^exponent mod m` with left-to-right sliding window exponentiation
+    pub fn pow(&self, base: &BigUint, exponent: &BigUint) -> BigUint {
+        let bits = exponent.bits();
+        let window = match bits {
+            0..=64 => 3,
+            65..=512 => 4,
+            513..=2048 => 5,
+            _ => 6,
+        };
+
+        // odd powers base^1, base^3, ..., base^(2^window - 1)
+        let base = self.enter(base);
+        let square = self.mont_mul(&base, &base);
+        let mut table = vec![base];
+        for i in 1..(1 << (window - 1)) {
+            let next = self.mont_mul(&table[i - 1], &square);
+            table.push(next);
+        }
+
+        let mut result = self.one.clone();
+        let mut i = bits as isize - 1;
+        while i >= 0 {
+            if !exponent.bit(i as usize) {
+                result = self.mont_mul(&result, &result);
+                i -= 1;
+                continue;
+            }
+
+            // the longest window ending in a 1 bit
+            let mut low = (i - window as isize + 1).max(0);
+            while !exponent.bit(low as usize) {
+                low += 1;
+            }
+
+            let mut value = 0;
+            for j in (low..=i).rev() {
+                result = self.mont_mul(&result, &result);
+                value = (value << 1) | exponent.bit(j as usize) as usize;
+            }
+            result = self.mont_mul(&result, &table[value >> 1]);
+
+            i = low - 1;
+        }
+
+        BigUint::from_limbs(&self.leave(&result))
+    }
+
+    /// Computes `base^exponent mod m` with a Montgomery ladder. Every exponent bit costs one
+    /// multiplication and one squaring and the operands are swapped without branching, so the
+    /// timing only depends on the bit length of the exponent (padded to the bit length of the
+    /// modulus) and not on its value
+    pub fn pow_ladder(&self, base: &BigUint, exponent: &BigUint) -> BigUint {
+        let bits = exponent.bits().max(self.modulus.bits());
+
+        let mut r0 = self.one.clone();
+        let mut r1 = self.enter(base);
+
+        for i in (0..bits).rev() {
+            let mask = (exponent.bit(i) as u64).wrapping_neg();
+            ct_swap(&mut r0, &mut r1, mask);
+            r1 = self.mont_mul(&r0, &r1);
+            r0 = self.mont_mul(&r0, &r0);
+            ct_swap(&mut r0, &mut r1, mask);
+        }
+
+        BigUint::from_limbs(&self.leave(&r0))
+    }
+
+    /// Returns the limbs of `a * R mod m`
+    fn enter(&self, a: &BigUint) -> Vec<u64> {
+        self.mont_mul(&self.reduced_limbs(a), &self.r2)
+    }
+
+    /// Returns the limbs of `a mod m`, which `mont_mul` requires of its operands
+    fn reduced_limbs(&self, a: &BigUint) -> Vec<u64> {
+        fixed_limbs(&(a % &self.modulus), self.m.len())
+    }
+
+    /// Returns the limbs of `a * R^-1 mod m`
+    fn leave(&self, a: &[u64]) -> Vec<u64> {
+        let mut one = vec![0; self.m.len()];
+        one[0] = 1;
+        self.mont_mul(a, &one)
+    }
+
+    /// Montgomery multiplication with the coarsely integrated operand scanning (CIOS) method
+    fn mont_mul(&self, a: &[u64], b: &[u64]) -> Vec<u64> {
+        let n = self.m.len();
+        let mut t = vec![0u64; n + 2];
+
+        for &bi in b {
+            let mut carry = 0u64;
+            for j in 0..n {
+                let sum = t[j] as u128 + a[j] as u128 * bi as u128 + carry as u128;
+                t[j] = sum as u64;
+                carry = (sum >> 64) as u64;
+            }
+            let sum = t[n] as u128 + carry as u128;
+            t[n] = sum as u64;
+            t[n + 1] = (sum >> 64) as u64;
+
+            // add a multiple of m that clears the lowest limb, then shift by one limb
+            let q = t[0].wrapping_mul(self.m_prime);
+            let sum = t[0] as u128 + q as u128 * self.m[0] as u128;
+            let mut carry = (sum >> 64) as u64;
+            for j in 1..n {
+                let sum = t[j] as u128 + q as u128 * self.m[j] as u128 + carry as u128;
+                t[j - 1] = sum as u64;
+                carry = (sum >> 64) as u64;
+            }
+            let sum = t[n] as u128 + carry as u128;
+            t[n - 1] = sum as u64;
+            t[n] = t[n + 1] + (sum >> 64) as u64;
+        }
+
+        // t < 2m, subtract m unless that borrows
+        let mut difference = vec![0u64; n];
+        let mut borrow = 0u64;
+        for j in 0..n {
+            let (d, b1) = t[j].overflowing_sub(self.m[j]);
+            let (d, b2) = d.overflowing_sub(borrow);
+            difference[j] = d;
+            borrow = b1 as u64 | b2 as u64;
+        }
+        let (_, b) = t[n].overflowing_sub(borrow);
+        let keep_t = (b as u64).wrapping_neg();
+        let keep_t = black_box(keep_t);
+
+        (0..n)
+            .map(|j| (t[j] & keep_t) | (difference[j] & !keep_t))
+            .collect()
+    }
+}
+
+/// Returns the limbs of `a` padded with zeros to `n` limbs. `a` must fit in `n` limbs
+fn fixed_limbs(a: &BigUint, n: usize) -> Vec<u64> {
+    let mut limbs = a.limbs().to_vec();
+    limbs.resize(n, 0);
+    limbs
+}
+
+/// Swaps `a` and `b` if `mask` is all ones and leaves them untouched if it is zero, without
+/// branching on `mask`
+fn ct_swap(a: &mut [u64], b: &mut [u64], mask: u64) {
+    let mask = black_box(mask);
+    for (x, y) in a.iter_mut().zip(b.iter_mut()) {
+        let t = (*x ^ *y) & mask;
+        *x ^= t;
+        *y ^= t;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use encoding::Decode;
+
+    use crate::Mt19937;
+
+    use super::*;
+
+    fn random_biguint(rng: &mut Mt19937, bits: usize) -> BigUint {
+        let bytes: Vec<u8> = (0..bits / 8).map(|_| rng.next_u32() as u8).collect();
+        BigUint::from_be_bytes(&bytes)
+    }
+
+    #[test]
+    fn montgomery_rejects_even_modulus() {
+        assert_eq!(MontgomeryContext::new(&BigUint::from(10u64)), None);
+        assert_eq!(MontgomeryContext::new(&BigUint::one()), None);
+    }
+
+    #[test]
+    fn montgomery_round_trip_and_mul() {
+        let modulus = BigUint::from_hex("f123456789abcdef0123456789abcdef1").unwrap();
+        let context = MontgomeryContext::new(&modulus).unwrap();
+
+        let a = BigUint::from_hex("123456789abcdef0123456789abcdef").unwrap();
+        let b = BigUint::from_hex("fedcba9876543210fedcba987654321").unwrap();
+
+        let a_mont = context.to_montgomery(&a);
+        let b_mont = context.to_montgomery(&b);
+        assert_eq!(context.from_montgomery(&a_mont), a);
+
+        let result = context.from_montgomery(&context.mul(&a_mont, &b_mont));
+        let expected = &(&a * &b) % &modulus;
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn montgomery_reduces_wide_operands() {
+        let modulus = BigUint::from_hex("f123456789abcdef0123456789abcdef1").unwrap();
+        let context = MontgomeryContext::new(&modulus).unwrap();
+
+        let a = BigUint::from_hex("123456789abcdef0123456789abcdef").unwrap();
+        let b = BigUint::from_hex("fedcba9876543210fedcba987654321").unwrap();
+        let a_mont = context.to_montgomery(&a);
+        let b_mont = context.to_montgomery(&b);
+        let wide = &a_mont + &(&modulus << 200);
+
+        assert_eq!(context.from_montgomery(&wide), a);
+        assert_eq!(context.mul(&wide, &b_mont), context.mul(&a_mont, &b_mont));
+    }
+
+    #[test]
+    fn montgomery_pow_matches_schoolbook() {
+        let mut rng = Mt19937::new(5489);
+
+        for bits in [64, 128, 512, 1024] {
+            for _ in 0..3 {
+                let mut modulus = random_biguint(&mut rng, bits);
+                modulus.set_bit(0, true);
+                let base = random_biguint(&mut rng, bits + 16);
+                let exponent = random_biguint(&mut rng, bits);
+
+                let context = MontgomeryContext::new(&modulus).unwrap();
+                let expected = base.modpow_schoolbook(&exponent, &modulus);
+
+                assert_eq!(context.pow(&base, &exponent), expected);
+                assert_eq!(context.pow_ladder(&base, &exponent), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn montgomery_pow_edge_cases() {
+        let modulus = BigUint::from(1_000_000_007u64);
+        let context = MontgomeryContext::new(&modulus).unwrap();
+        let base = BigUint::from(123_456u64);
+
+        assert_eq!(context.pow(&base, &BigUint::zero()), BigUint::one());
+        assert_eq!(context.pow_ladder(&base, &BigUint::zero()), BigUint::one());
+        assert_eq!(context.pow(&BigUint::zero(), &base), BigUint::zero());
+        assert_eq!(context.pow(&modulus, &base), BigUint::zero());
+
+        // Fermat's little theorem
+        let exponent = &modulus - 1u64;
+        assert_eq!(context.pow(&base, &exponent), BigUint::one());
+        assert_eq!(context.pow_ladder(&base, &exponent), BigUint::one());
+    }
+}
@@ -0,0 +1,403 @@
+const N: usize = 624;
+const M: usize = 397;
+const MATRIX_A: u32 = 0x9908B0DF;
+const UPPER_MASK: u32 = 0x80000000;
+const LOWER_MASK: u32 = 0x7FFFFFFF;
+
+const N_64: usize = 312;
+const M_64: usize = 156;
+const MATRIX_A_64: u64 = 0xB5026F5AA96619E9;
+const UPPER_MASK_64: u64 = 0xFFFFFFFF80000000;
+const LOWER_MASK_64: u64 = 0x7FFFFFFF;
+
+/// The 32 bit MT19937 Mersenne Twister pseudo random number generator
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mt19937 {
+    state: [u32; N],
+    index: usize,
+}
+
+impl Mt19937 {
+    /// Creates a new `Mt19937` seeded with the given value, like the reference `init_genrand`
+    ///
+    /// # Examples
+    /// ```
+    /// use primitives::Mt19937;
+    ///
+    /// let mut rng = Mt19937::new(5489);
+    /// assert_eq!(rng.next_u32(), 3499211612);
+    /// ```
+    pub fn new(seed: u32) -> Mt19937 {
+        let mut state = [0u32; N];
+        state[0] = seed;
+        for i in 1..N {
+            let prev = state[i - 1];
+            state[i] = 1812433253u32
+                .wrapping_mul(prev ^ (prev >> 30))
+                .wrapping_add(i as u32);
+        }
+
+        Mt19937 { state, index: N }
+    }
+
+    /// Creates a new `Mt19937` seeded with the given key, like the reference `init_by_array`
+    ///
+    /// # Panics
+    /// If `key` is empty
+    ///
+    /// # Examples
+    /// ```
+    /// use primitives::Mt19937;
+    ///
+    /// let mut rng = Mt19937::from_key(&[0x123, 0x234, 0x345, 0x456]);
+    /// assert_eq!(rng.next_u32(), 1067595299);
+    /// ```
+    pub fn from_key(key: &[u32]) -> Mt19937 {
+        assert!(!key.is_empty(), "the key must not be empty");
+        let mut mt = Mt19937::new(19650218);
+        let state = &mut mt.state;
+
+        let mut i = 1;
+        let mut j = 0;
+        for _ in 0..N.max(key.len()) {
+            let prev = state[i - 1];
+            state[i] = (state[i] ^ (prev ^ (prev >> 30)).wrapping_mul(1664525))
+                .wrapping_add(key[j])
+                .wrapping_add(j as u32);
+            i += 1;
+            j += 1;
+            if i >= N {
+                state[0] = state[N - 1];
+                i = 1;
+            }
+            if j >= key.len() {
+                j = 0;
+            }
+        }
+        for _ in 0..N - 1 {
+            let prev = state[i - 1];
+            state[i] =
+                (state[i] ^ (prev ^ (prev >> 30)).wrapping_mul(1566083941)).wrapping_sub(i as u32);
+            i += 1;
+            if i >= N {
+                state[0] = state[N - 1];
+                i = 1;
+            }
+        }
+        state[0] = UPPER_MASK;
+
+        mt
+    }
+
+    /// Creates a new `Mt19937` from a raw internal state
+    ///
+    /// # Args
+    /// `state`: the 624 words of the internal state
+    ///
+    /// `index`: the position of the next word of `state` to be tempered and returned. A value
+    /// of 624 means that the state is twisted before the next output
+    pub fn from_state(state: [u32; N], index: usize) -> Mt19937 {
+        Mt19937 {
+            state,
+            index: index.min(N),
+        }
+    }
+
+    /// Clones a generator from 624 consecutive outputs, which are untempered to recover its
+    /// internal state. The clone continues the sequence right after the last given output.
+    ///
+    /// # Examples
+    /// ```
+    /// use primitives::Mt19937;
+    ///
+    /// let mut rng = Mt19937::new(1337);
+    /// let mut outputs = [0; 624];
+    /// outputs.iter_mut().for_each(|o| *o = rng.next_u32());
+    ///
+    /// let mut clone = Mt19937::clone_from_outputs(&outputs);
+    /// assert_eq!(clone.next_u32(), rng.next_u32());
+    /// ```
+    pub fn clone_from_outputs(outputs: &[u32; N]) -> Mt19937 {
+        let mut state = [0u32; N];
+        state
+            .iter_mut()
+            .zip(outputs)
+            .for_each(|(s, &o)| *s = untemper(o));
+
+        Mt19937 { state, index: N }
+    }
+
+    /// Returns the next pseudo random `u32`, like the reference `genrand_int32`
+    pub fn next_u32(&mut self) -> u32 {
+        if self.index >= N {
+            self.twist();
+        }
+
+        let y = self.state[self.index];
+        self.index += 1;
+
+        temper(y)
+    }
+
+    /// Generates the next `N` words of the internal state
+    fn twist(&mut self) {
+        for i in 0..N {
+            let y = (self.state[i] & UPPER_MASK) | (self.state[(i + 1) % N] & LOWER_MASK);
+            let mag = if y & 1 == 1 { MATRIX_A } else { 0 };
+            self.state[i] = self.state[(i + M) % N] ^ (y >> 1) ^ mag;
+        }
+        self.index = 0;
+    }
+}
+
+/// Applies the MT19937 tempering transform to a word of the internal state
+pub fn temper(y: u32) -> u32 {
+    let mut y = y;
+    y ^= y >> 11;
+    y ^= (y << 7) & 0x9D2C5680;
+    y ^= (y << 15) & 0xEFC60000;
+    y ^= y >> 18;
+    y
+}
+
+/// Inverts the MT19937 tempering transform, recovering the word of the internal state that
+/// produced the given output
+///
+/// # Examples
+/// ```
+/// use primitives::{temper, untemper};
+///
+/// assert_eq!(untemper(temper(0xDEADBEEF)), 0xDEADBEEF);
+/// ```
+pub fn untemper(y: u32) -> u32 {
+    let mut y = y;
+    y ^= y >> 18;
+    y ^= (y << 15) & 0xEFC60000;
+
+    // each step recovers 7 more low bits of the original value
+    let mut x = y;
+    for _ in 0..4 {
+        x = y ^ ((x << 7) & 0x9D2C5680);
+    }
+
+    // each step recovers 11 more high bits of the original value
+    let mut y = x;
+    for _ in 0..2 {
+        y = x ^ (y >> 11);
+    }
+
+    y
+}
+
+/// The 64 bit MT19937-64 Mersenne Twister pseudo random number generator
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mt19937_64 {
+    state: [u64; N_64],
+    index: usize,
+}
+
+impl Mt19937_64 {
+    /// Creates a new `Mt19937_64` seeded with the given value, like the reference
+    /// `init_genrand64`
+    ///
+    /// # Examples
+    /// ```
+    /// use primitives::Mt19937_64;
+    ///
+    /// let mut rng = Mt19937_64::new(5489);
+    /// assert_eq!(rng.next_u64(), 14514284786278117030);
+    /// ```
+    pub fn new(seed: u64) -> Mt19937_64 {
+        let mut state = [0u64; N_64];
+        state[0] = seed;
+        for i in 1..N_64 {
+            let prev = state[i - 1];
+            state[i] = 6364136223846793005u64
+                .wrapping_mul(prev ^ (prev >> 62))
+                .wrapping_add(i as u64);
+        }
+
+        Mt19937_64 { state, index: N_64 }
+    }
+
+    /// Creates a new `Mt19937_64` seeded with the given key, like the reference
+    /// `init_by_array64`
+    ///
+    /// # Panics
+    /// If `key` is empty
+    ///
+    /// # Examples
+    /// ```
+    /// use primitives::Mt19937_64;
+    ///
+    /// let mut rng = Mt19937_64::from_key(&[0x12345, 0x23456, 0x34567, 0x45678]);
+    /// assert_eq!(rng.next_u64(), 7266447313870364031);
+    /// ```
+    pub fn from_key(key: &[u64]) -> Mt19937_64 {
+        assert!(!key.is_empty(), "the key must not be empty");
+        let mut mt = Mt19937_64::new(19650218);
+        let state = &mut mt.state;
+
+        let mut i = 1;
+        let mut j = 0;
+        for _ in 0..N_64.max(key.len()) {
+            let prev = state[i - 1];
+            state[i] = (state[i] ^ (prev ^ (prev >> 62)).wrapping_mul(3935559000370003845))
+                .wrapping_add(key[j])
+                .wrapping_add(j as u64);
+            i += 1;
+            j += 1;
+            if i >= N_64 {
+                state[0] = state[N_64 - 1];
+                i = 1;
+            }
+            if j >= key.len() {
+                j = 0;
+            }
+        }
+        for _ in 0..N_64 - 1 {
+            let prev = state[i - 1];
+            state[i] = (state[i] ^ (prev ^ (prev >> 62)).wrapping_mul(2862933555777941757))
+                .wrapping_sub(i as u64);
+            i += 1;
+            if i >= N_64 {
+                state[0] = state[N_64 - 1];
+                i = 1;
+            }
+        }
+        state[0] = 1 << 63;
+
+        mt
+    }
+
+    /// Returns the next pseudo random `u64`, like the reference `genrand64_int64`
+    pub fn next_u64(&mut self) -> u64 {
+        if self.index >= N_64 {
+            self.twist();
+        }
+
+        let mut x = self.state[self.index];
+        self.index += 1;
+
+        x ^= (x >> 29) & 0x5555555555555555;
+        x ^= (x << 17) & 0x71D67FFFEDA60000;
+        x ^= (x << 37) & 0xFFF7EEE000000000;
+        x ^= x >> 43;
+        x
+    }
+
+    /// Generates the next `N_64` words of the internal state
+    fn twist(&mut self) {
+        for i in 0..N_64 {
+            let x = (self.state[i] & UPPER_MASK_64) | (self.state[(i + 1) % N_64] & LOWER_MASK_64);
+            let mag = if x & 1 == 1 { MATRIX_A_64 } else { 0 };
+            self.state[i] = self.state[(i + M_64) % N_64] ^ (x >> 1) ^ mag;
+        }
+        self.index = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[should_panic]
+    fn mt19937_from_empty_key() {
+        let _ = Mt19937::from_key(&[]);
+    }
+
+    #[test]
+    fn mt19937_init_genrand() {
+        let mut rng = Mt19937::new(5489);
+        let result: Vec<u32> = (0..3).map(|_| rng.next_u32()).collect();
+        let expected = vec![3499211612, 581869302, 3890346734];
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn mt19937_10000th_output() {
+        let mut rng = Mt19937::new(5489);
+        let result = (0..10000).map(|_| rng.next_u32()).last().unwrap();
+        assert_eq!(result, 4123659995);
+    }
+
+    #[test]
+    fn mt19937_init_by_array() {
+        let mut rng = Mt19937::from_key(&[0x123, 0x234, 0x345, 0x456]);
+        let result: Vec<u32> = (0..1000).map(|_| rng.next_u32()).collect();
+        let expected_first = [
+            1067595299, 955945823, 477289528, 4107218783, 4228976476, 3344332714, 3355579695,
+            227628506, 810200273, 2591290167,
+        ];
+        let expected_last = [3896204135, 2416995901, 1397735321, 3460025646];
+        assert_eq!(result[..10], expected_first);
+        assert_eq!(result[996..], expected_last);
+    }
+
+    #[test]
+    fn mt19937_untemper() {
+        for y in [
+            0, 1, 0x80000000, 0xFFFFFFFF, 0x12345678, 0x9D2C5680, 0xEFC60000,
+        ] {
+            assert_eq!(untemper(temper(y)), y);
+            assert_eq!(temper(untemper(y)), y);
+        }
+    }
+
+    #[test]
+    fn mt19937_clone_from_outputs() {
+        let mut rng = Mt19937::from_key(&[0x123, 0x234, 0x345, 0x456]);
+        for _ in 0..100 {
+            rng.next_u32();
+        }
+
+        let mut outputs = [0; N];
+        outputs.iter_mut().for_each(|o| *o = rng.next_u32());
+        let mut clone = Mt19937::clone_from_outputs(&outputs);
+
+        let result: Vec<u32> = (0..2000).map(|_| clone.next_u32()).collect();
+        let expected: Vec<u32> = (0..2000).map(|_| rng.next_u32()).collect();
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn mt19937_64_init_genrand() {
+        let mut rng = Mt19937_64::new(5489);
+        let result: Vec<u64> = (0..3).map(|_| rng.next_u64()).collect();
+        let expected = vec![
+            14514284786278117030,
+            4620546740167642908,
+            13109570281517897720,
+        ];
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn mt19937_64_10000th_output() {
+        let mut rng = Mt19937_64::new(5489);
+        let result = (0..10000).map(|_| rng.next_u64()).last().unwrap();
+        assert_eq!(result, 9981545732273789042);
+    }
+
+    #[test]
+    fn mt19937_64_init_by_array() {
+        let mut rng = Mt19937_64::from_key(&[0x12345, 0x23456, 0x34567, 0x45678]);
+        let result: Vec<u64> = (0..1000).map(|_| rng.next_u64()).collect();
+        let expected_first = [
+            7266447313870364031,
+            4946485549665804864,
+            16945909448695747420,
+            16394063075524226720,
+            4873882236456199058,
+        ];
+        let expected_last = [
+            13004818533162292132,
+            9831652587047067687,
+            7619315254749630976,
+            994412663058993407,
+        ];
+        assert_eq!(result[..5], expected_first);
+        assert_eq!(result[996..], expected_last);
+    }
+}
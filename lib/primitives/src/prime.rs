@@ -0,0 +1,479 @@
+use crate::{BigUint, MontgomeryContext, Rng};
+
+/// The primes used as witnesses by `is_prime` below `DETERMINISTIC_LIMIT`
+const DETERMINISTIC_BASES: [u64; 13] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41];
+
+/// Miller–Rabin with the first 13 primes as bases is exact below this bound
+/// (3317044064679887385961981)
+const DETERMINISTIC_LIMIT: [u64; 2] = [0x51AD_C5B2_2410_A5FD, 0x2_BE69];
+
+/// Trial division bound used to discard most candidates before the expensive tests
+const SIEVE_LIMIT: u64 = 2000;
+
+/// Runs one round of the Miller–Rabin test on an odd `n > 3`
+///
+/// # Returns
+/// `false` if `base` proves that `n` is composite, `true` if `n` is a strong probable prime to
+/// `base`
+pub fn is_strong_probable_prime(n: &BigUint, base: &BigUint) -> bool {
+    let n_minus_one = n - 1u64;
+    let s = n_minus_one.trailing_zeros().unwrap_or(0);
+    let d = &n_minus_one >> s;
+
+    let context = match MontgomeryContext::new(n) {
+        Some(context) => context,
+        None => return false,
+    };
+
+    let mut x = context.pow(base, &d);
+    if x.is_one() || x == n_minus_one || x.is_zero() {
+        return true;
+    }
+
+    for _ in 1..s {
+        x = &(&x * &x) % n;
+        if x == n_minus_one {
+            return true;
+        }
+        if x.is_one() {
+            return false;
+        }
+    }
+
+    false
+}
+
+/// Probabilistic Miller–Rabin test with random bases. A composite passes each round with
+/// probability at most 1/4
+///
+/// # Args
+/// `n`: the integer to test
+///
+/// `rounds`: the number of random bases to try
+///
+/// `rng`: the source of the bases
+pub fn miller_rabin<R: Rng>(n: &BigUint, rounds: usize, rng: &mut R) -> bool {
+    if let Some(result) = small_prime_check(n) {
+        return result;
+    }
+
+    let high = n - 2u64;
+    (0..rounds).all(|_| {
+        let base = BigUint::random_range(rng, &BigUint::from(2u64), &high);
+        is_strong_probable_prime(n, &base)
+    })
+}
+
+/// Runs the strong Lucas probable prime test with the parameters of Selfridge's method A (the
+/// first `D` in 5, -7, 9, -11, ... with Jacobi symbol `(D/n) = -1`, `P = 1`, `Q = (1 - D) / 4`)
+pub fn is_strong_lucas_probable_prime(n: &BigUint) -> bool {
+    if n.bits() <= 2 {
+        return n.bits() == 2;
+    }
+    if n.is_even() {
+        return false;
+    }
+    // D would never be found for a perfect square
+    if n.sqrt().pow(2) == *n {
+        return false;
+    }
+
+    let mut d_abs = 5u64;
+    let mut d_negative = false;
+    loop {
+        let d = signed_mod(d_abs, d_negative, n);
+        match jacobi(&d, n) {
+            -1 => break,
+            0 if BigUint::from(d_abs) != *n => return false,
+            _ => {}
+        }
+        d_abs += 2;
+        d_negative = !d_negative;
+    }
+
+    let d = signed_mod(d_abs, d_negative, n);
+    // Q = (1 - D) / 4
+    let q = if d_negative {
+        signed_mod((1 + d_abs) / 4, false, n)
+    } else {
+        signed_mod((d_abs - 1) / 4, true, n)
+    };
+
+    let n_plus_one = n + 1u64;
+    let s = n_plus_one.trailing_zeros().unwrap_or(0);
+    let k = &n_plus_one >> s;
+
+    // U_1 = 1, V_1 = P = 1, Q^1
+    let mut u = BigUint::one();
+    let mut v = BigUint::one();
+    let mut q_k = q.clone();
+
+    for i in (0..k.bits() - 1).rev() {
+        // U_2k = U_k V_k, V_2k = V_k^2 - 2 Q^k
+        u = &(&u * &v) % n;
+        v = sub_mod(&(&(&v * &v) % n), &(&(&q_k + &q_k) % n), n);
+        q_k = &(&q_k * &q_k) % n;
+
+        if k.bit(i) {
+            // U_k+1 = (P U_k + V_k) / 2, V_k+1 = (D U_k + P V_k) / 2
+            let new_u = half_mod(&(&(&u + &v) % n), n);
+            let new_v = half_mod(&(&(&(&d * &u) + &v) % n), n);
+            u = new_u;
+            v = new_v;
+            q_k = &(&q_k * &q) % n;
+        }
+    }
+
+    if u.is_zero() || v.is_zero() {
+        return true;
+    }
+
+    for _ in 1..s {
+        v = sub_mod(&(&(&v * &v) % n), &(&(&q_k + &q_k) % n), n);
+        if v.is_zero() {
+            return true;
+        }
+        q_k = &(&q_k * &q_k) % n;
+    }
+
+    false
+}
+
+/// The Baillie–PSW test: trial division, a Miller–Rabin round in base 2 and a strong Lucas
+/// test. No composite is known to pass it
+pub fn baillie_psw(n: &BigUint) -> bool {
+    if let Some(result) = small_prime_check(n) {
+        return result;
+    }
+
+    is_strong_probable_prime(n, &BigUint::from(2u64)) && is_strong_lucas_probable_prime(n)
+}
+
+/// Tests whether `n` is prime. The answer is exact below 3317044064679887385961981, where
+/// Miller–Rabin with the first 13 primes as bases is deterministic, and given by Baillie–PSW
+/// above
+///
+/// # Examples
+/// ```
+/// use primitives::{is_prime, BigUint};
+///
+/// assert!(is_prime(&BigUint::from(1_000_000_007u64)));
+/// assert!(!is_prime(&BigUint::from(3_215_031_751u64)));
+/// ```
+pub fn is_prime(n: &BigUint) -> bool {
+    if let Some(result) = small_prime_check(n) {
+        return result;
+    }
+
+    if *n < BigUint::from_limbs(&DETERMINISTIC_LIMIT) {
+        DETERMINISTIC_BASES
+            .iter()
+            .all(|&base| is_strong_probable_prime(n, &BigUint::from(base)))
+    } else {
+        baillie_psw(n)
+    }
+}
+
+/// Generates a random prime of exactly `bits` bits. The two most significant bits are set so
+/// that the product of two such primes has exactly `2 * bits` bits
+///
+/// # Panics
+/// If `bits` is smaller than 3
+pub fn random_prime<R: Rng>(rng: &mut R, bits: usize) -> BigUint {
+    assert!(bits >= 3, "a prime needs at least 3 bits");
+
+    loop {
+        let mut candidate = BigUint::random_bits(rng, bits);
+        candidate.set_bit(bits - 1, true);
+        candidate.set_bit(bits - 2, true);
+        candidate.set_bit(0, true);
+
+        if is_prime(&candidate) {
+            return candidate;
+        }
+    }
+}
+
+/// Generates a random safe prime `p = 2q + 1` of exactly `bits` bits, where `q` is prime too
+///
+/// # Panics
+/// If `bits` is smaller than 3
+pub fn random_safe_prime<R: Rng>(rng: &mut R, bits: usize) -> BigUint {
+    assert!(bits >= 3, "a safe prime needs at least 3 bits");
+
+    loop {
+        let mut q = BigUint::random_bits(rng, bits - 1);
+        q.set_bit(bits - 2, true);
+        q.set_bit(0, true);
+
+        let p = &(&q << 1) + 1u64;
+
+        // sieve both numbers before running the expensive tests: p = 2q + 1 is divisible by a
+        // small prime r when q = (r - 1) / 2 mod r. Small sizes can produce q or p equal to r
+        let sieved = small_primes().iter().all(|&r| {
+            let q_mod = q.rem_u64(r);
+            (q_mod != 0 || q == BigUint::from(r)) && (q_mod != (r - 1) / 2 || p == BigUint::from(r))
+        });
+        if !sieved {
+            continue;
+        }
+
+        if is_prime(&q) && is_prime(&p) {
+            return p;
+        }
+    }
+}
+
+/// Generates DSA-style domain parameters: a prime `q` of `q_bits` bits and a prime `p` of
+/// `p_bits` bits such that `q` divides `p - 1`
+///
+/// # Returns
+/// The pair `(p, q)`
+///
+/// # Panics
+/// If `q_bits` is smaller than 3 or `p_bits` is not larger than `q_bits`
+pub fn random_dsa_parameters<R: Rng>(
+    rng: &mut R,
+    p_bits: usize,
+    q_bits: usize,
+) -> (BigUint, BigUint) {
+    assert!(p_bits > q_bits, "p must be larger than q");
+
+    let q = random_prime(rng, q_bits);
+    let two_q = &q << 1;
+
+    // about 4 * p_bits candidates are needed on average, start over with a new q afterwards
+    for _ in 0..4 * p_bits {
+        let mut x = BigUint::random_bits(rng, p_bits);
+        x.set_bit(p_bits - 1, true);
+
+        // round down to p = 1 mod 2q
+        let p = &(&x - &(&x % &two_q)) + 1u64;
+        if p.bits() == p_bits && is_prime(&p) {
+            return (p, q);
+        }
+    }
+
+    random_dsa_parameters(rng, p_bits, q_bits)
+}
+
+/// Computes the Jacobi symbol `(a/n)` for an odd positive `n`
+pub fn jacobi(a: &BigUint, n: &BigUint) -> i32 {
+    assert!(n.is_odd(), "the Jacobi symbol is defined for odd n");
+
+    let mut a = a % n;
+    let mut n = n.clone();
+    let mut result = 1;
+
+    while !a.is_zero() {
+        let twos = a.trailing_zeros().unwrap_or(0);
+        a = &a >> twos;
+        let n_mod_8 = n.rem_u64(8);
+        if twos % 2 == 1 && (n_mod_8 == 3 || n_mod_8 == 5) {
+            result = -result;
+        }
+
+        std::mem::swap(&mut a, &mut n);
+        if a.rem_u64(4) == 3 && n.rem_u64(4) == 3 {
+            result = -result;
+        }
+        a = &a % &n;
+    }
+
+    if n.is_one() {
+        result
+    } else {
+        0
+    }
+}
+
+/// Decides primality by trial division when it is cheap
+///
+/// # Returns
+/// `Some` answer if `n` is small or has a small factor, `None` otherwise
+fn small_prime_check(n: &BigUint) -> Option<bool> {
+    if let Some(value) = n.to_u64() {
+        if value < 2 {
+            return Some(false);
+        }
+        if value < SIEVE_LIMIT {
+            return Some(small_primes().contains(&value));
+        }
+    }
+
+    if small_primes().iter().any(|&p| n.rem_u64(p) == 0) {
+        return Some(false);
+    }
+
+    None
+}
+
+/// Returns the primes below `SIEVE_LIMIT`
+fn small_primes() -> &'static [u64] {
+    static PRIMES: std::sync::OnceLock<Vec<u64>> = std::sync::OnceLock::new();
+    PRIMES.get_or_init(|| {
+        let mut composite = vec![false; SIEVE_LIMIT as usize];
+        let mut primes = vec![];
+        for i in 2..SIEVE_LIMIT as usize {
+            if !composite[i] {
+                primes.push(i as u64);
+                for j in (i * i..SIEVE_LIMIT as usize).step_by(i) {
+                    composite[j] = true;
+                }
+            }
+        }
+        primes
+    })
+}
+
+/// Returns `value` or `-value` reduced modulo `n`
+fn signed_mod(value: u64, negative: bool, n: &BigUint) -> BigUint {
+    let value = &BigUint::from(value) % n;
+    if negative && !value.is_zero() {
+        n - &value
+    } else {
+        value
+    }
+}
+
+/// Returns `a - b mod n` for `a, b < n`
+fn sub_mod(a: &BigUint, b: &BigUint, n: &BigUint) -> BigUint {
+    if a >= b {
+        a - b
+    } else {
+        &(a + n) - b
+    }
+}
+
+/// Returns `a / 2 mod n` for an odd `n` and `a < n`
+fn half_mod(a: &BigUint, n: &BigUint) -> BigUint {
+    if a.is_even() {
+        a >> 1
+    } else {
+        &(a + n) >> 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Mt19937;
+
+    use super::*;
+
+    fn big(s: &str) -> BigUint {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn is_prime_small_numbers() {
+        let primes: Vec<u64> = (0..200).filter(|&n| is_prime(&BigUint::from(n))).collect();
+        let expected: Vec<u64> = (0..200)
+            .filter(|&n| n >= 2 && (2..n).all(|d| n % d != 0))
+            .collect();
+        assert_eq!(primes, expected);
+    }
+
+    #[test]
+    fn is_prime_pseudoprimes() {
+        // Carmichael numbers and strong pseudoprimes to several bases
+        for n in [
+            561u64,
+            2047,
+            1373653,
+            25326001,
+            3215031751,
+            3825123056546413051,
+        ] {
+            assert!(!is_prime(&BigUint::from(n)), "{}", n);
+        }
+        // strong pseudoprime to all prime bases up to 37
+        assert!(!is_prime(&big("318665857834031151167461")));
+        assert!(is_prime(&big("3317044064679887385961813")));
+    }
+
+    #[test]
+    fn is_prime_large() {
+        let mersenne_127 = &(BigUint::one() << 127) - 1u64;
+        let mersenne_521 = &(BigUint::one() << 521) - 1u64;
+        assert!(is_prime(&mersenne_127));
+        assert!(is_prime(&mersenne_521));
+        assert!(!is_prime(&(&mersenne_127 * &mersenne_521)));
+        assert!(!is_prime(&(&(BigUint::one() << 128) + 1u64)));
+    }
+
+    #[test]
+    fn strong_lucas_pseudoprimes_are_caught_by_baillie_psw() {
+        for n in [5459u64, 5777, 10877, 16109, 18971] {
+            let n = BigUint::from(n);
+            assert!(is_strong_lucas_probable_prime(&n));
+            assert!(!is_strong_probable_prime(&n, &BigUint::from(2u64)));
+            assert!(!baillie_psw(&n));
+        }
+    }
+
+    #[test]
+    fn miller_rabin_random_bases() {
+        let mut rng = Mt19937::new(5489);
+        let mersenne_89 = &(BigUint::one() << 89) - 1u64;
+        assert!(miller_rabin(&mersenne_89, 20, &mut rng));
+        assert!(!miller_rabin(&BigUint::from(3215031751u64), 20, &mut rng));
+    }
+
+    #[test]
+    fn jacobi_symbols() {
+        let cases = [
+            (1, 1, 1),
+            (2, 15, 1),
+            (7, 15, -1),
+            (5, 15, 0),
+            (1001, 9907, -1),
+            (19, 45, 1),
+        ];
+        for (a, n, expected) in cases {
+            assert_eq!(
+                jacobi(&BigUint::from(a as u64), &BigUint::from(n as u64)),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn random_prime_generation() {
+        let mut rng = Mt19937::new(1);
+        let p = random_prime(&mut rng, 256);
+        assert_eq!(p.bits(), 256);
+        assert!(p.bit(254));
+        assert!(is_prime(&p));
+    }
+
+    #[test]
+    fn random_safe_prime_generation() {
+        let mut rng = Mt19937::new(2);
+        let p = random_safe_prime(&mut rng, 128);
+        assert_eq!(p.bits(), 128);
+        assert!(is_prime(&p));
+        assert!(is_prime(&(&p >> 1)));
+    }
+
+    #[test]
+    fn random_safe_prime_small_sizes() {
+        let mut rng = Mt19937::new(4);
+        for bits in 3..=12 {
+            let p = random_safe_prime(&mut rng, bits);
+            assert_eq!(p.bits(), bits);
+            assert!(is_prime(&p));
+            assert!(is_prime(&(&p >> 1)));
+        }
+    }
+
+    #[test]
+    fn random_dsa_parameters_generation() {
+        let mut rng = Mt19937::new(3);
+        let (p, q) = random_dsa_parameters(&mut rng, 512, 160);
+        assert_eq!(p.bits(), 512);
+        assert_eq!(q.bits(), 160);
+        assert!(is_prime(&p));
+        assert!(is_prime(&q));
+        assert!((&(&p - 1u64) % &q).is_zero());
+    }
+}
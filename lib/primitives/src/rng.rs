@@ -0,0 +1,93 @@
+use std::fs::File;
+use std::io::Read;
+
+use crate::{Mt19937, Mt19937_64};
+
+/// A source of random numbers. Key and parameter generation take it as a parameter so that
+/// tests can use a seeded generator and real keys the operating system generator
+pub trait Rng {
+    /// Returns the next random 32 bit integer
+    fn next_u32(&mut self) -> u32;
+
+    /// Returns the next random 64 bit integer
+    fn next_u64(&mut self) -> u64 {
+        ((self.next_u32() as u64) << 32) | self.next_u32() as u64
+    }
+
+    /// Fills `dest` with random bytes
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(4) {
+            let bytes = self.next_u32().to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+    }
+}
+
+impl Rng for Mt19937 {
+    fn next_u32(&mut self) -> u32 {
+        Mt19937::next_u32(self)
+    }
+}
+
+impl Rng for Mt19937_64 {
+    fn next_u32(&mut self) -> u32 {
+        (Mt19937_64::next_u64(self) >> 32) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        Mt19937_64::next_u64(self)
+    }
+}
+
+/// The cryptographically secure generator of the operating system, read from `/dev/urandom`
+///
+/// # Examples
+/// ```
+/// use primitives::{OsRng, Rng};
+///
+/// let mut key = [0; 16];
+/// OsRng.fill_bytes(&mut key);
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OsRng;
+
+impl Rng for OsRng {
+    fn next_u32(&mut self) -> u32 {
+        let mut bytes = [0; 4];
+        self.fill_bytes(&mut bytes);
+        u32::from_le_bytes(bytes)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        File::open("/dev/urandom")
+            .and_then(|mut f| f.read_exact(dest))
+            .expect("failed to read from /dev/urandom");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rng_fill_bytes_uses_little_endian_words() {
+        let mut rng = Mt19937::new(5489);
+        let mut bytes = [0; 6];
+        rng.fill_bytes(&mut bytes);
+
+        let mut reference = Mt19937::new(5489);
+        let first = reference.next_u32().to_le_bytes();
+        let second = reference.next_u32().to_le_bytes();
+        assert_eq!(bytes[..4], first);
+        assert_eq!(bytes[4..], second[..2]);
+    }
+
+    #[test]
+    fn os_rng_is_not_constant() {
+        let mut a = [0; 32];
+        let mut b = [0; 32];
+        OsRng.fill_bytes(&mut a);
+        OsRng.fill_bytes(&mut b);
+        assert_ne!(a, b);
+    }
+}
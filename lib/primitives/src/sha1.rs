@@ -0,0 +1,223 @@
+use crate::{hash, Endianness, HashFunction, MdBuffer, MerkleDamgard};
+
+const BLOCK_SIZE: usize = 64;
+
+const INITIAL_STATE: [u32; 5] = [0x67452301, 0xEFCDAB89, 0x98BADCFE, 0x10325476, 0xC3D2E1F0];
+
+/// A streaming SHA-1 hasher
+///
+/// # Examples
+/// ```
+/// use encoding::Encode;
+/// use primitives::Sha1;
+///
+/// let mut hasher = Sha1::new();
+/// hasher.update("ab".as_bytes());
+/// hasher.update("c".as_bytes());
+/// let result = hasher.finalize();
+///
+/// assert_eq!(result.to_hex(), "a9993e364706816aba3e25717850c26c9cd0d89d");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sha1 {
+    state: [u32; 5],
+    buffer: MdBuffer<BLOCK_SIZE>,
+}
+
+impl Sha1 {
+    /// Creates a new `Sha1` hasher with the standard initial state
+    pub fn new() -> Sha1 {
+        Sha1::from_state(INITIAL_STATE, 0)
+    }
+
+    /// Creates a new `Sha1` hasher that resumes from an arbitrary internal state
+    ///
+    /// # Args
+    /// `state`: the five 32 bit words of the chaining value
+    ///
+    /// `length`: the number of bytes already processed, which must be a multiple of the 64
+    /// bytes block size. It is used to compute the final padding
+    pub fn from_state(state: [u32; 5], length: u64) -> Sha1 {
+        Sha1 {
+            state,
+            buffer: MdBuffer::new(length),
+        }
+    }
+
+    /// Computes the SHA-1 digest of the given data
+    ///
+    /// # Examples
+    /// ```
+    /// use encoding::Encode;
+    /// use primitives::Sha1;
+    ///
+    /// let result = Sha1::digest("".as_bytes());
+    /// assert_eq!(result.to_hex(), "da39a3ee5e6b4b0d3255bfef95601890afd80709");
+    /// ```
+    pub fn digest(data: &[u8]) -> Vec<u8> {
+        let mut hasher = Sha1::new();
+        hasher.update(data);
+        hasher.finalize()
+    }
+
+    /// Returns the current chaining value
+    pub fn state(&self) -> [u32; 5] {
+        self.state
+    }
+
+    /// Returns the number of bytes processed so far
+    pub fn length(&self) -> u64 {
+        self.buffer.length()
+    }
+
+    /// Feeds more data into the hasher
+    pub fn update(&mut self, data: &[u8]) {
+        let state = &mut self.state;
+        self.buffer.update(data, |block| compress(state, block));
+    }
+
+    /// Pads the data processed so far and returns the 20 bytes digest
+    pub fn finalize(mut self) -> Vec<u8> {
+        let padding = md_padding(self.buffer.length());
+        self.update(&padding);
+
+        self.state.iter().flat_map(|w| w.to_be_bytes()).collect()
+    }
+}
+
+impl Default for Sha1 {
+    fn default() -> Self {
+        Sha1::new()
+    }
+}
+
+impl HashFunction for Sha1 {
+    const BLOCK_SIZE: usize = BLOCK_SIZE;
+    const OUTPUT_SIZE: usize = 20;
+
+    fn update(&mut self, data: &[u8]) {
+        Sha1::update(self, data);
+    }
+
+    fn finalize(self) -> Vec<u8> {
+        Sha1::finalize(self)
+    }
+}
+
+impl MerkleDamgard for Sha1 {
+    fn padding(length: u64) -> Vec<u8> {
+        md_padding(length)
+    }
+
+    fn from_digest(digest: &[u8], length: u64) -> Self {
+        assert_eq!(digest.len(), 20, "invalid digest size");
+
+        let mut state = [0; 5];
+        for (word, bytes) in state.iter_mut().zip(digest.chunks_exact(4)) {
+            *word = u32::from_be_bytes(bytes.try_into().unwrap());
+        }
+        Sha1::from_state(state, length)
+    }
+}
+
+/// Returns the SHA-1 padding for a message of the given length in bytes: a 1 bit, zeros up
+/// to 56 bytes modulo 64 and the message length in bits as a 64 bit big endian integer
+fn md_padding(length: u64) -> Vec<u8> {
+    hash::md_padding(length, BLOCK_SIZE, 8, Endianness::Big)
+}
+
+/// Processes a 64 bytes block, updating the chaining value
+fn compress(state: &mut [u32; 5], block: &[u8]) {
+    let mut w = [0u32; 80];
+    for (i, chunk) in block.chunks_exact(4).enumerate() {
+        w[i] = u32::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+    }
+    for i in 16..80 {
+        w[i] = (w[i - 3] ^ w[i - 8] ^ w[i - 14] ^ w[i - 16]).rotate_left(1);
+    }
+
+    let [mut a, mut b, mut c, mut d, mut e] = *state;
+
+    for (i, &wi) in w.iter().enumerate() {
+        let (f, k) = match i {
+            0..=19 => ((b & c) | (!b & d), 0x5A827999),
+            20..=39 => (b ^ c ^ d, 0x6ED9EBA1),
+            40..=59 => ((b & c) | (b & d) | (c & d), 0x8F1BBCDC),
+            _ => (b ^ c ^ d, 0xCA62C1D6),
+        };
+
+        let temp = a
+            .rotate_left(5)
+            .wrapping_add(f)
+            .wrapping_add(e)
+            .wrapping_add(k)
+            .wrapping_add(wi);
+        e = d;
+        d = c;
+        c = b.rotate_left(30);
+        b = a;
+        a = temp;
+    }
+
+    state[0] = state[0].wrapping_add(a);
+    state[1] = state[1].wrapping_add(b);
+    state[2] = state[2].wrapping_add(c);
+    state[3] = state[3].wrapping_add(d);
+    state[4] = state[4].wrapping_add(e);
+}
+
+#[cfg(test)]
+mod tests {
+    use encoding::Encode;
+
+    use super::*;
+
+    #[test]
+    fn sha1_empty() {
+        let result = Sha1::digest(&[]).to_hex();
+        let expected = "da39a3ee5e6b4b0d3255bfef95601890afd80709";
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn sha1_abc() {
+        let result = Sha1::digest("abc".as_bytes()).to_hex();
+        let expected = "a9993e364706816aba3e25717850c26c9cd0d89d";
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn sha1_two_blocks() {
+        let input = "abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq";
+        let result = Sha1::digest(input.as_bytes()).to_hex();
+        let expected = "84983e441c3bd26ebaae4aa1f95129e5e54670f1";
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn sha1_million_a_streaming() {
+        let mut hasher = Sha1::new();
+        let chunk = [b'a'; 1000];
+        for _ in 0..1000 {
+            hasher.update(&chunk[..7]);
+            hasher.update(&chunk[7..]);
+        }
+        let result = hasher.finalize().to_hex();
+        let expected = "34aa973cd4c4daa4f61eeb2bdbad27316534016f";
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn sha1_resume_from_state() {
+        let message = [b'x'; 100];
+
+        let mut hasher = Sha1::new();
+        hasher.update(&message[..64]);
+        let mut resumed = Sha1::from_state(hasher.state(), hasher.length());
+        resumed.update(&message[64..]);
+
+        let result = resumed.finalize();
+        let expected = Sha1::digest(&message);
+        assert_eq!(result, expected);
+    }
+}
@@ -0,0 +1,746 @@
+use crate::{md_padding, Endianness, HashFunction, MdBuffer, MerkleDamgard};
+
+const BLOCK_SIZE_256: usize = 64;
+const BLOCK_SIZE_512: usize = 128;
+
+const K_256: [u32; 64] = [
+    0x428A2F98, 0x71374491, 0xB5C0FBCF, 0xE9B5DBA5, 0x3956C25B, 0x59F111F1, 0x923F82A4, 0xAB1C5ED5,
+    0xD807AA98, 0x12835B01, 0x243185BE, 0x550C7DC3, 0x72BE5D74, 0x80DEB1FE, 0x9BDC06A7, 0xC19BF174,
+    0xE49B69C1, 0xEFBE4786, 0x0FC19DC6, 0x240CA1CC, 0x2DE92C6F, 0x4A7484AA, 0x5CB0A9DC, 0x76F988DA,
+    0x983E5152, 0xA831C66D, 0xB00327C8, 0xBF597FC7, 0xC6E00BF3, 0xD5A79147, 0x06CA6351, 0x14292967,
+    0x27B70A85, 0x2E1B2138, 0x4D2C6DFC, 0x53380D13, 0x650A7354, 0x766A0ABB, 0x81C2C92E, 0x92722C85,
+    0xA2BFE8A1, 0xA81A664B, 0xC24B8B70, 0xC76C51A3, 0xD192E819, 0xD6990624, 0xF40E3585, 0x106AA070,
+    0x19A4C116, 0x1E376C08, 0x2748774C, 0x34B0BCB5, 0x391C0CB3, 0x4ED8AA4A, 0x5B9CCA4F, 0x682E6FF3,
+    0x748F82EE, 0x78A5636F, 0x84C87814, 0x8CC70208, 0x90BEFFFA, 0xA4506CEB, 0xBEF9A3F7, 0xC67178F2,
+];
+
+const K_512: [u64; 80] = [
+    0x428A2F98D728AE22,
+    0x7137449123EF65CD,
+    0xB5C0FBCFEC4D3B2F,
+    0xE9B5DBA58189DBBC,
+    0x3956C25BF348B538,
+    0x59F111F1B605D019,
+    0x923F82A4AF194F9B,
+    0xAB1C5ED5DA6D8118,
+    0xD807AA98A3030242,
+    0x12835B0145706FBE,
+    0x243185BE4EE4B28C,
+    0x550C7DC3D5FFB4E2,
+    0x72BE5D74F27B896F,
+    0x80DEB1FE3B1696B1,
+    0x9BDC06A725C71235,
+    0xC19BF174CF692694,
+    0xE49B69C19EF14AD2,
+    0xEFBE4786384F25E3,
+    0x0FC19DC68B8CD5B5,
+    0x240CA1CC77AC9C65,
+    0x2DE92C6F592B0275,
+    0x4A7484AA6EA6E483,
+    0x5CB0A9DCBD41FBD4,
+    0x76F988DA831153B5,
+    0x983E5152EE66DFAB,
+    0xA831C66D2DB43210,
+    0xB00327C898FB213F,
+    0xBF597FC7BEEF0EE4,
+    0xC6E00BF33DA88FC2,
+    0xD5A79147930AA725,
+    0x06CA6351E003826F,
+    0x142929670A0E6E70,
+    0x27B70A8546D22FFC,
+    0x2E1B21385C26C926,
+    0x4D2C6DFC5AC42AED,
+    0x53380D139D95B3DF,
+    0x650A73548BAF63DE,
+    0x766A0ABB3C77B2A8,
+    0x81C2C92E47EDAEE6,
+    0x92722C851482353B,
+    0xA2BFE8A14CF10364,
+    0xA81A664BBC423001,
+    0xC24B8B70D0F89791,
+    0xC76C51A30654BE30,
+    0xD192E819D6EF5218,
+    0xD69906245565A910,
+    0xF40E35855771202A,
+    0x106AA07032BBD1B8,
+    0x19A4C116B8D2D0C8,
+    0x1E376C085141AB53,
+    0x2748774CDF8EEB99,
+    0x34B0BCB5E19B48A8,
+    0x391C0CB3C5C95A63,
+    0x4ED8AA4AE3418ACB,
+    0x5B9CCA4F7763E373,
+    0x682E6FF3D6B2B8A3,
+    0x748F82EE5DEFB2FC,
+    0x78A5636F43172F60,
+    0x84C87814A1F0AB72,
+    0x8CC702081A6439EC,
+    0x90BEFFFA23631E28,
+    0xA4506CEBDE82BDE9,
+    0xBEF9A3F7B2C67915,
+    0xC67178F2E372532B,
+    0xCA273ECEEA26619C,
+    0xD186B8C721C0C207,
+    0xEADA7DD6CDE0EB1E,
+    0xF57D4F7FEE6ED178,
+    0x06F067AA72176FBA,
+    0x0A637DC5A2C898A6,
+    0x113F9804BEF90DAE,
+    0x1B710B35131C471B,
+    0x28DB77F523047D84,
+    0x32CAAB7B40C72493,
+    0x3C9EBE0A15C9BEBC,
+    0x431D67C49C100D4C,
+    0x4CC5D4BECB3E42B6,
+    0x597F299CFC657E2A,
+    0x5FCB6FAB3AD6FAEC,
+    0x6C44198C4A475817,
+];
+
+const INITIAL_STATE_224: [u32; 8] = [
+    0xC1059ED8, 0x367CD507, 0x3070DD17, 0xF70E5939, 0xFFC00B31, 0x68581511, 0x64F98FA7, 0xBEFA4FA4,
+];
+
+const INITIAL_STATE_256: [u32; 8] = [
+    0x6A09E667, 0xBB67AE85, 0x3C6EF372, 0xA54FF53A, 0x510E527F, 0x9B05688C, 0x1F83D9AB, 0x5BE0CD19,
+];
+
+const INITIAL_STATE_384: [u64; 8] = [
+    0xCBBB9D5DC1059ED8,
+    0x629A292A367CD507,
+    0x9159015A3070DD17,
+    0x152FECD8F70E5939,
+    0x67332667FFC00B31,
+    0x8EB44A8768581511,
+    0xDB0C2E0D64F98FA7,
+    0x47B5481DBEFA4FA4,
+];
+
+const INITIAL_STATE_512: [u64; 8] = [
+    0x6A09E667F3BCC908,
+    0xBB67AE8584CAA73B,
+    0x3C6EF372FE94F82B,
+    0xA54FF53A5F1D36F1,
+    0x510E527FADE682D1,
+    0x9B05688C2B3E6C1F,
+    0x1F83D9ABFB41BD6B,
+    0x5BE0CD19137E2179,
+];
+
+/// A streaming SHA-256 hasher
+///
+/// # Examples
+/// ```
+/// use encoding::Encode;
+/// use primitives::Sha256;
+///
+/// let mut hasher = Sha256::new();
+/// hasher.update("ab".as_bytes());
+/// hasher.update("c".as_bytes());
+/// let result = hasher.finalize();
+///
+/// assert_eq!(
+///     result.to_hex(),
+///     "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sha256 {
+    state: [u32; 8],
+    buffer: MdBuffer<BLOCK_SIZE_256>,
+}
+
+impl Sha256 {
+    /// Creates a new `Sha256` hasher with the standard initial state
+    pub fn new() -> Sha256 {
+        Sha256::from_state(INITIAL_STATE_256, 0)
+    }
+
+    /// Creates a new `Sha256` hasher that resumes from an arbitrary internal state
+    ///
+    /// # Args
+    /// `state`: the eight 32 bit words of the chaining value
+    ///
+    /// `length`: the number of bytes already processed, which must be a multiple of the 64
+    /// bytes block size. It is used to compute the final padding
+    pub fn from_state(state: [u32; 8], length: u64) -> Sha256 {
+        Sha256 {
+            state,
+            buffer: MdBuffer::new(length),
+        }
+    }
+
+    /// Computes the SHA-256 digest of the given data
+    pub fn digest(data: &[u8]) -> Vec<u8> {
+        let mut hasher = Sha256::new();
+        hasher.update(data);
+        hasher.finalize()
+    }
+
+    /// Returns the current chaining value
+    pub fn state(&self) -> [u32; 8] {
+        self.state
+    }
+
+    /// Returns the number of bytes processed so far
+    pub fn length(&self) -> u64 {
+        self.buffer.length()
+    }
+
+    /// Feeds more data into the hasher
+    pub fn update(&mut self, data: &[u8]) {
+        let state = &mut self.state;
+        self.buffer.update(data, |block| compress_256(state, block));
+    }
+
+    /// Pads the data processed so far and returns the 32 bytes digest
+    pub fn finalize(mut self) -> Vec<u8> {
+        let padding = md_padding_256(self.buffer.length());
+        self.update(&padding);
+
+        self.state.iter().flat_map(|w| w.to_be_bytes()).collect()
+    }
+}
+
+impl Default for Sha256 {
+    fn default() -> Self {
+        Sha256::new()
+    }
+}
+
+impl HashFunction for Sha256 {
+    const BLOCK_SIZE: usize = BLOCK_SIZE_256;
+    const OUTPUT_SIZE: usize = 32;
+
+    fn update(&mut self, data: &[u8]) {
+        Sha256::update(self, data);
+    }
+
+    fn finalize(self) -> Vec<u8> {
+        Sha256::finalize(self)
+    }
+}
+
+impl MerkleDamgard for Sha256 {
+    fn padding(length: u64) -> Vec<u8> {
+        md_padding_256(length)
+    }
+
+    fn from_digest(digest: &[u8], length: u64) -> Self {
+        assert_eq!(digest.len(), 32, "invalid digest size");
+
+        let mut state = [0; 8];
+        for (word, bytes) in state.iter_mut().zip(digest.chunks_exact(4)) {
+            *word = u32::from_be_bytes(bytes.try_into().unwrap());
+        }
+        Sha256::from_state(state, length)
+    }
+}
+
+/// A streaming SHA-224 hasher. SHA-224 is SHA-256 with a different initial state and a
+/// digest truncated to 28 bytes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sha224 {
+    inner: Sha256,
+}
+
+impl Sha224 {
+    /// Creates a new `Sha224` hasher with the standard initial state
+    pub fn new() -> Sha224 {
+        Sha224::from_state(INITIAL_STATE_224, 0)
+    }
+
+    /// Creates a new `Sha224` hasher that resumes from an arbitrary internal state
+    ///
+    /// # Args
+    /// `state`: the eight 32 bit words of the chaining value
+    ///
+    /// `length`: the number of bytes already processed, which must be a multiple of the 64
+    /// bytes block size. It is used to compute the final padding
+    pub fn from_state(state: [u32; 8], length: u64) -> Sha224 {
+        Sha224 {
+            inner: Sha256::from_state(state, length),
+        }
+    }
+
+    /// Computes the SHA-224 digest of the given data
+    pub fn digest(data: &[u8]) -> Vec<u8> {
+        let mut hasher = Sha224::new();
+        hasher.update(data);
+        hasher.finalize()
+    }
+
+    /// Returns the current chaining value
+    pub fn state(&self) -> [u32; 8] {
+        self.inner.state()
+    }
+
+    /// Returns the number of bytes processed so far
+    pub fn length(&self) -> u64 {
+        self.inner.length()
+    }
+
+    /// Feeds more data into the hasher
+    pub fn update(&mut self, data: &[u8]) {
+        self.inner.update(data);
+    }
+
+    /// Pads the data processed so far and returns the 28 bytes digest
+    pub fn finalize(self) -> Vec<u8> {
+        let mut digest = self.inner.finalize();
+        digest.truncate(28);
+        digest
+    }
+}
+
+impl Default for Sha224 {
+    fn default() -> Self {
+        Sha224::new()
+    }
+}
+
+impl HashFunction for Sha224 {
+    const BLOCK_SIZE: usize = BLOCK_SIZE_256;
+    const OUTPUT_SIZE: usize = 28;
+
+    fn update(&mut self, data: &[u8]) {
+        Sha224::update(self, data);
+    }
+
+    fn finalize(self) -> Vec<u8> {
+        Sha224::finalize(self)
+    }
+}
+
+/// A streaming SHA-512 hasher
+///
+/// # Examples
+/// ```
+/// use encoding::Encode;
+/// use primitives::Sha512;
+///
+/// let result = Sha512::digest("abc".as_bytes());
+///
+/// assert_eq!(
+///     result.to_hex(),
+///     "ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a\
+///      2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f"
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sha512 {
+    state: [u64; 8],
+    buffer: MdBuffer<BLOCK_SIZE_512>,
+}
+
+impl Sha512 {
+    /// Creates a new `Sha512` hasher with the standard initial state
+    pub fn new() -> Sha512 {
+        Sha512::from_state(INITIAL_STATE_512, 0)
+    }
+
+    /// Creates a new `Sha512` hasher that resumes from an arbitrary internal state
+    ///
+    /// # Args
+    /// `state`: the eight 64 bit words of the chaining value
+    ///
+    /// `length`: the number of bytes already processed, which must be a multiple of the 128
+    /// bytes block size. It is used to compute the final padding
+    pub fn from_state(state: [u64; 8], length: u64) -> Sha512 {
+        Sha512 {
+            state,
+            buffer: MdBuffer::new(length),
+        }
+    }
+
+    /// Computes the SHA-512 digest of the given data
+    pub fn digest(data: &[u8]) -> Vec<u8> {
+        let mut hasher = Sha512::new();
+        hasher.update(data);
+        hasher.finalize()
+    }
+
+    /// Returns the current chaining value
+    pub fn state(&self) -> [u64; 8] {
+        self.state
+    }
+
+    /// Returns the number of bytes processed so far
+    pub fn length(&self) -> u64 {
+        self.buffer.length()
+    }
+
+    /// Feeds more data into the hasher
+    pub fn update(&mut self, data: &[u8]) {
+        let state = &mut self.state;
+        self.buffer.update(data, |block| compress_512(state, block));
+    }
+
+    /// Pads the data processed so far and returns the 64 bytes digest
+    pub fn finalize(mut self) -> Vec<u8> {
+        let padding = md_padding_512(self.buffer.length());
+        self.update(&padding);
+
+        self.state.iter().flat_map(|w| w.to_be_bytes()).collect()
+    }
+}
+
+impl Default for Sha512 {
+    fn default() -> Self {
+        Sha512::new()
+    }
+}
+
+impl HashFunction for Sha512 {
+    const BLOCK_SIZE: usize = BLOCK_SIZE_512;
+    const OUTPUT_SIZE: usize = 64;
+
+    fn update(&mut self, data: &[u8]) {
+        Sha512::update(self, data);
+    }
+
+    fn finalize(self) -> Vec<u8> {
+        Sha512::finalize(self)
+    }
+}
+
+impl MerkleDamgard for Sha512 {
+    fn padding(length: u64) -> Vec<u8> {
+        md_padding_512(length)
+    }
+
+    fn from_digest(digest: &[u8], length: u64) -> Self {
+        assert_eq!(digest.len(), 64, "invalid digest size");
+
+        let mut state = [0; 8];
+        for (word, bytes) in state.iter_mut().zip(digest.chunks_exact(8)) {
+            *word = u64::from_be_bytes(bytes.try_into().unwrap());
+        }
+        Sha512::from_state(state, length)
+    }
+}
+
+/// A streaming SHA-384 hasher. SHA-384 is SHA-512 with a different initial state and a
+/// digest truncated to 48 bytes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sha384 {
+    inner: Sha512,
+}
+
+impl Sha384 {
+    /// Creates a new `Sha384` hasher with the standard initial state
+    pub fn new() -> Sha384 {
+        Sha384::from_state(INITIAL_STATE_384, 0)
+    }
+
+    /// Creates a new `Sha384` hasher that resumes from an arbitrary internal state
+    ///
+    /// # Args
+    /// `state`: the eight 64 bit words of the chaining value
+    ///
+    /// `length`: the number of bytes already processed, which must be a multiple of the 128
+    /// bytes block size. It is used to compute the final padding
+    pub fn from_state(state: [u64; 8], length: u64) -> Sha384 {
+        Sha384 {
+            inner: Sha512::from_state(state, length),
+        }
+    }
+
+    /// Computes the SHA-384 digest of the given data
+    pub fn digest(data: &[u8]) -> Vec<u8> {
+        let mut hasher = Sha384::new();
+        hasher.update(data);
+        hasher.finalize()
+    }
+
+    /// Returns the current chaining value
+    pub fn state(&self) -> [u64; 8] {
+        self.inner.state()
+    }
+
+    /// Returns the number of bytes processed so far
+    pub fn length(&self) -> u64 {
+        self.inner.length()
+    }
+
+    /// Feeds more data into the hasher
+    pub fn update(&mut self, data: &[u8]) {
+        self.inner.update(data);
+    }
+
+    /// Pads the data processed so far and returns the 48 bytes digest
+    pub fn finalize(self) -> Vec<u8> {
+        let mut digest = self.inner.finalize();
+        digest.truncate(48);
+        digest
+    }
+}
+
+impl Default for Sha384 {
+    fn default() -> Self {
+        Sha384::new()
+    }
+}
+
+impl HashFunction for Sha384 {
+    const BLOCK_SIZE: usize = BLOCK_SIZE_512;
+    const OUTPUT_SIZE: usize = 48;
+
+    fn update(&mut self, data: &[u8]) {
+        Sha384::update(self, data);
+    }
+
+    fn finalize(self) -> Vec<u8> {
+        Sha384::finalize(self)
+    }
+}
+
+/// Returns the SHA-224/256 padding for a message of the given length in bytes: a 1 bit,
+/// zeros up to 56 bytes modulo 64 and the message length in bits as a 64 bit big endian
+/// integer
+fn md_padding_256(length: u64) -> Vec<u8> {
+    md_padding(length, BLOCK_SIZE_256, 8, Endianness::Big)
+}
+
+/// Returns the SHA-384/512 padding for a message of the given length in bytes: a 1 bit,
+/// zeros up to 112 bytes modulo 128 and the message length in bits as a 128 bit big endian
+/// integer
+fn md_padding_512(length: u64) -> Vec<u8> {
+    md_padding(length, BLOCK_SIZE_512, 16, Endianness::Big)
+}
+
+/// Processes a 64 bytes block, updating the SHA-224/256 chaining value
+fn compress_256(state: &mut [u32; 8], block: &[u8]) {
+    let mut w = [0u32; 64];
+    for (i, chunk) in block.chunks_exact(4).enumerate() {
+        w[i] = u32::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+    }
+    for i in 16..64 {
+        let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+        let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+        w[i] = w[i - 16]
+            .wrapping_add(s0)
+            .wrapping_add(w[i - 7])
+            .wrapping_add(s1);
+    }
+
+    let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = *state;
+
+    for (&k, &wi) in K_256.iter().zip(&w) {
+        let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+        let ch = (e & f) ^ (!e & g);
+        let temp1 = h
+            .wrapping_add(s1)
+            .wrapping_add(ch)
+            .wrapping_add(k)
+            .wrapping_add(wi);
+        let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+        let maj = (a & b) ^ (a & c) ^ (b & c);
+        let temp2 = s0.wrapping_add(maj);
+
+        h = g;
+        g = f;
+        f = e;
+        e = d.wrapping_add(temp1);
+        d = c;
+        c = b;
+        b = a;
+        a = temp1.wrapping_add(temp2);
+    }
+
+    for (s, v) in state.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+        *s = s.wrapping_add(v);
+    }
+}
+
+/// Processes a 128 bytes block, updating the SHA-384/512 chaining value
+fn compress_512(state: &mut [u64; 8], block: &[u8]) {
+    let mut w = [0u64; 80];
+    for (i, chunk) in block.chunks_exact(8).enumerate() {
+        w[i] = u64::from_be_bytes(chunk.try_into().unwrap());
+    }
+    for i in 16..80 {
+        let s0 = w[i - 15].rotate_right(1) ^ w[i - 15].rotate_right(8) ^ (w[i - 15] >> 7);
+        let s1 = w[i - 2].rotate_right(19) ^ w[i - 2].rotate_right(61) ^ (w[i - 2] >> 6);
+        w[i] = w[i - 16]
+            .wrapping_add(s0)
+            .wrapping_add(w[i - 7])
+            .wrapping_add(s1);
+    }
+
+    let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = *state;
+
+    for (&k, &wi) in K_512.iter().zip(&w) {
+        let s1 = e.rotate_right(14) ^ e.rotate_right(18) ^ e.rotate_right(41);
+        let ch = (e & f) ^ (!e & g);
+        let temp1 = h
+            .wrapping_add(s1)
+            .wrapping_add(ch)
+            .wrapping_add(k)
+            .wrapping_add(wi);
+        let s0 = a.rotate_right(28) ^ a.rotate_right(34) ^ a.rotate_right(39);
+        let maj = (a & b) ^ (a & c) ^ (b & c);
+        let temp2 = s0.wrapping_add(maj);
+
+        h = g;
+        g = f;
+        f = e;
+        e = d.wrapping_add(temp1);
+        d = c;
+        c = b;
+        b = a;
+        a = temp1.wrapping_add(temp2);
+    }
+
+    for (s, v) in state.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+        *s = s.wrapping_add(v);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use encoding::Encode;
+
+    use super::*;
+
+    static ABC: &str = "abc";
+    static TWO_BLOCKS_448: &str = "abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq";
+    static TWO_BLOCKS_896: &str = "abcdefghbcdefghicdefghijdefghijkefghijklfghijklmghijklmn\
+                                   hijklmnoijklmnopjklmnopqklmnopqrlmnopqrsmnopqrstnopqrstu";
+
+    #[test]
+    fn sha224_nist_vectors() {
+        let vectors = [
+            (
+                "",
+                "d14a028c2a3a2bc9476102bb288234c415a2b01f828ea62ac5b3e42f",
+            ),
+            (
+                ABC,
+                "23097d223405d8228642a477bda255b32aadbce4bda0b3f7e36c9da7",
+            ),
+            (
+                TWO_BLOCKS_448,
+                "75388b16512776cc5dba5da1fd890150b0c6455cb4f58b1952522525",
+            ),
+        ];
+        for (input, expected) in vectors {
+            assert_eq!(Sha224::digest(input.as_bytes()).to_hex(), expected);
+        }
+    }
+
+    #[test]
+    fn sha256_nist_vectors() {
+        let vectors = [
+            (
+                "",
+                "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+            ),
+            (
+                ABC,
+                "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+            ),
+            (
+                TWO_BLOCKS_448,
+                "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1",
+            ),
+        ];
+        for (input, expected) in vectors {
+            assert_eq!(Sha256::digest(input.as_bytes()).to_hex(), expected);
+        }
+    }
+
+    #[test]
+    fn sha384_nist_vectors() {
+        let vectors = [
+            (
+                ABC,
+                "cb00753f45a35e8bb5a03d699ac65007272c32ab0eded1631a8b605a43ff5bed\
+                 8086072ba1e7cc2358baeca134c825a7",
+            ),
+            (
+                TWO_BLOCKS_896,
+                "09330c33f71147e83d192fc782cd1b4753111b173b3b05d22fa08086e3b0f712\
+                 fcc7c71a557e2db966c3e9fa91746039",
+            ),
+        ];
+        for (input, expected) in vectors {
+            assert_eq!(Sha384::digest(input.as_bytes()).to_hex(), expected);
+        }
+    }
+
+    #[test]
+    fn sha512_nist_vectors() {
+        let vectors = [
+            (
+                "",
+                "cf83e1357eefb8bdf1542850d66d8007d620e4050b5715dc83f4a921d36ce9ce\
+                 47d0d13c5d85f2b0ff8318d2877eec2f63b931bd47417a81a538327af927da3e",
+            ),
+            (
+                ABC,
+                "ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a\
+                 2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f",
+            ),
+            (
+                TWO_BLOCKS_896,
+                "8e959b75dae313da8cf4f72814fc143f8f7779c6eb9f7fa17299aeadb6889018\
+                 501d289e4900f7e4331b99dec4b5433ac7d329eeb6dd26545e96e55b874be909",
+            ),
+        ];
+        for (input, expected) in vectors {
+            assert_eq!(Sha512::digest(input.as_bytes()).to_hex(), expected);
+        }
+    }
+
+    #[test]
+    fn sha256_million_a_streaming() {
+        let mut hasher = Sha256::new();
+        let chunk = [b'a'; 1000];
+        for _ in 0..1000 {
+            hasher.update(&chunk[..13]);
+            hasher.update(&chunk[13..]);
+        }
+        let result = hasher.finalize().to_hex();
+        let expected = "cdc76e5c9914fb9281a1c7e284d73e67f1809a48a497200e046d39ccc7112cd0";
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn sha512_million_a_streaming() {
+        let mut hasher = Sha512::new();
+        let chunk = [b'a'; 1000];
+        for _ in 0..1000 {
+            hasher.update(&chunk[..13]);
+            hasher.update(&chunk[13..]);
+        }
+        let result = hasher.finalize().to_hex();
+        let expected = "e718483d0ce769644e2e42c7bc15b4638e1f98b13b2044285632a803afa973eb\
+                        de0ff244877ea60a4cb0432ce577c31beb009c5c2c49aa2e4eadb217ad8cc09b";
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn sha256_resume_from_state() {
+        let message = [b'x'; 100];
+
+        let mut hasher = Sha256::new();
+        hasher.update(&message[..64]);
+        let mut resumed = Sha256::from_state(hasher.state(), hasher.length());
+        resumed.update(&message[64..]);
+
+        assert_eq!(resumed.finalize(), Sha256::digest(&message));
+    }
+
+    #[test]
+    fn sha384_resume_from_state() {
+        let message = [b'x'; 200];
+
+        let mut hasher = Sha384::new();
+        hasher.update(&message[..128]);
+        let mut resumed = Sha384::from_state(hasher.state(), hasher.length());
+        resumed.update(&message[128..]);
+
+        assert_eq!(resumed.finalize(), Sha384::digest(&message));
+    }
+}
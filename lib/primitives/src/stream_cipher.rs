@@ -0,0 +1,96 @@
+use crate::Mt19937;
+
+/// Ciphers that encrypt by XORing the data with a keystream
+pub trait StreamCipher {
+    /// XORs the next bytes of the keystream into `data`, encrypting or decrypting it in place
+    fn apply_keystream(&mut self, data: &mut [u8]);
+}
+
+/// A stream cipher whose keystream is the low byte of each output of a MT19937 generator
+/// seeded with a 16 bit key
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mt19937Cipher {
+    rng: Mt19937,
+}
+
+impl Mt19937Cipher {
+    /// Creates a new `Mt19937Cipher` with the given 16 bit key
+    ///
+    /// # Examples
+    /// ```
+    /// use primitives::{Mt19937Cipher, StreamCipher};
+    ///
+    /// let mut data = "attack at dawn".as_bytes().to_vec();
+    ///
+    /// Mt19937Cipher::new(0xBEEF).apply_keystream(&mut data);
+    /// assert_ne!(data, "attack at dawn".as_bytes());
+    ///
+    /// Mt19937Cipher::new(0xBEEF).apply_keystream(&mut data);
+    /// assert_eq!(data, "attack at dawn".as_bytes());
+    /// ```
+    pub fn new(key: u16) -> Mt19937Cipher {
+        Mt19937Cipher::from_seed(key as u32)
+    }
+
+    fn from_seed(seed: u32) -> Mt19937Cipher {
+        Mt19937Cipher {
+            rng: Mt19937::new(seed),
+        }
+    }
+
+    /// Returns the next `len` bytes of the keystream
+    pub fn keystream(&mut self, len: usize) -> Vec<u8> {
+        (0..len).map(|_| self.rng.next_u32() as u8).collect()
+    }
+}
+
+impl StreamCipher for Mt19937Cipher {
+    fn apply_keystream(&mut self, data: &mut [u8]) {
+        for u in data.iter_mut() {
+            *u ^= self.rng.next_u32() as u8;
+        }
+    }
+}
+
+/// Generates a 16 bytes password reset token from the MT19937 keystream seeded with the given
+/// UNIX timestamp
+///
+/// # Examples
+/// ```
+/// use primitives::generate_reset_token;
+///
+/// let token = generate_reset_token(1_700_000_000);
+/// assert_eq!(token.len(), 16);
+/// assert_eq!(token, generate_reset_token(1_700_000_000));
+/// ```
+pub fn generate_reset_token(timestamp: u32) -> Vec<u8> {
+    Mt19937Cipher::from_seed(timestamp).keystream(16)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mt19937_cipher_keystream_is_low_bytes() {
+        let mut rng = Mt19937::new(1234);
+        let expected: Vec<u8> = (0..32).map(|_| rng.next_u32() as u8).collect();
+
+        let mut result = vec![0; 32];
+        Mt19937Cipher::new(1234).apply_keystream(&mut result);
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn mt19937_cipher_keystream_continues() {
+        let mut cipher = Mt19937Cipher::new(1234);
+        let mut first = vec![0; 10];
+        let mut second = vec![0; 10];
+        cipher.apply_keystream(&mut first);
+        cipher.apply_keystream(&mut second);
+
+        let expected = Mt19937Cipher::new(1234).keystream(20);
+        assert_eq!([first, second].concat(), expected);
+    }
+}
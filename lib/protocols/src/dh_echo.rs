@@ -0,0 +1,544 @@
+use aes::{Aes128, Mode, BLOCKSIZE};
+use dh::{DhGroup, DiffieHellman, Kdf};
+use primitives::{pad_pkcs7, unpad_pkcs7, BigUint, Rng};
+
+use crate::{decode_fields, encode_fields, ProtocolError, Transport, Wire};
+
+/// The messages of the Diffie–Hellman echo protocol
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DhEchoMessage {
+    /// The client sends the group and its public key in one go (direct handshake)
+    Parameters {
+        p: BigUint,
+        g: BigUint,
+        public_key: BigUint,
+    },
+
+    /// The client proposes a group (negotiated handshake)
+    Negotiate { p: BigUint, g: BigUint },
+
+    /// The server accepts the proposed group
+    Ack,
+
+    /// A public key
+    PublicKey(BigUint),
+
+    /// An AES-128-CBC encrypted message
+    Encrypted { ciphertext: Vec<u8>, iv: Vec<u8> },
+}
+
+impl Wire for DhEchoMessage {
+    fn encode(&self) -> Vec<u8> {
+        match self {
+            DhEchoMessage::Parameters { p, g, public_key } => encode_fields(
+                0,
+                &[
+                    &p.to_be_bytes(),
+                    &g.to_be_bytes(),
+                    &public_key.to_be_bytes(),
+                ],
+            ),
+            DhEchoMessage::Negotiate { p, g } => {
+                encode_fields(1, &[&p.to_be_bytes(), &g.to_be_bytes()])
+            }
+            DhEchoMessage::Ack => encode_fields(2, &[]),
+            DhEchoMessage::PublicKey(key) => encode_fields(3, &[&key.to_be_bytes()]),
+            DhEchoMessage::Encrypted { ciphertext, iv } => encode_fields(4, &[ciphertext, iv]),
+        }
+    }
+
+    fn decode(bytes: &[u8]) -> Result<Self, ProtocolError> {
+        let (tag, fields) = decode_fields(bytes)?;
+        let integer = |i: usize| BigUint::from_be_bytes(&fields[i]);
+
+        let message = match (tag, fields.len()) {
+            (0, 3) => DhEchoMessage::Parameters {
+                p: integer(0),
+                g: integer(1),
+                public_key: integer(2),
+            },
+            (1, 2) => DhEchoMessage::Negotiate {
+                p: integer(0),
+                g: integer(1),
+            },
+            (2, 0) => DhEchoMessage::Ack,
+            (3, 1) => DhEchoMessage::PublicKey(integer(0)),
+            (4, 2) => DhEchoMessage::Encrypted {
+                ciphertext: fields[0].clone(),
+                iv: fields[1].clone(),
+            },
+            _ => return Err(ProtocolError::MalformedMessage),
+        };
+
+        Ok(message)
+    }
+}
+
+/// How the client and the server agree on the group
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Handshake {
+    /// C->S: p, g, A / S->C: B
+    Direct,
+
+    /// C->S: p, g / S->C: ACK / C->S: A / S->C: B
+    Negotiated,
+}
+
+/// The settings of a party of the Diffie–Hellman echo protocol
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DhEchoConfig {
+    /// The handshake used by the client. Servers accept both
+    pub handshake: Handshake,
+
+    /// Whether the public key received from the peer is validated
+    pub validate_public_keys: bool,
+}
+
+impl Default for DhEchoConfig {
+    fn default() -> Self {
+        DhEchoConfig {
+            handshake: Handshake::Direct,
+            validate_public_keys: true,
+        }
+    }
+}
+
+/// Runs the client of the Diffie–Hellman echo protocol: after a key exchange, every message
+/// is sent encrypted with AES-128-CBC under `SHA1(s)[0:16]` and the server sends it back
+/// re-encrypted under a fresh IV
+///
+/// # Args
+/// `transport`: the link to the server, closed when the function returns
+///
+/// `group`: the group proposed to the server
+///
+/// `messages`: the plaintexts to send
+///
+/// `rng`: the source of the private key and of the IVs
+///
+/// `config`: the handshake and validation settings
+///
+/// # Returns
+/// A `Result` wrapping the plaintexts echoed by the server or a `ProtocolError`
+///
+/// # Examples
+/// ```
+/// use std::thread;
+///
+/// use dh::DhGroup;
+/// use primitives::OsRng;
+/// use protocols::{channel_pair, dh_echo_client, dh_echo_server, DhEchoConfig};
+///
+/// let (client, server) = channel_pair();
+/// let server = thread::spawn(move || dh_echo_server(server, &mut OsRng, true));
+///
+/// let messages = vec![b"hello".to_vec()];
+/// let config = DhEchoConfig::default();
+/// let echoes = dh_echo_client(client, DhGroup::modp_1536(), &messages, &mut OsRng, config);
+/// assert_eq!(echoes.unwrap(), messages);
+/// assert_eq!(server.join().unwrap().unwrap(), messages);
+/// ```
+pub fn dh_echo_client<T, R>(
+    mut transport: T,
+    group: DhGroup,
+    messages: &[Vec<u8>],
+    rng: &mut R,
+    config: DhEchoConfig,
+) -> Result<Vec<Vec<u8>>, ProtocolError>
+where
+    T: Transport<DhEchoMessage>,
+    R: Rng,
+{
+    let mut dh = DiffieHellman::new(group.clone(), rng);
+    dh.set_public_key_validation(config.validate_public_keys);
+    let (p, g) = (group.p().clone(), group.g().clone());
+
+    match config.handshake {
+        Handshake::Direct => {
+            let public_key = dh.public_key().clone();
+            transport.send(DhEchoMessage::Parameters { p, g, public_key })?;
+        }
+        Handshake::Negotiated => {
+            transport.send(DhEchoMessage::Negotiate { p, g })?;
+            if transport.recv()? != DhEchoMessage::Ack {
+                return Err(ProtocolError::UnexpectedMessage);
+            }
+            transport.send(DhEchoMessage::PublicKey(dh.public_key().clone()))?;
+        }
+    }
+
+    let key = match transport.recv()? {
+        DhEchoMessage::PublicKey(peer_key) => dh.derive_aes_key(&peer_key, Kdf::Sha1)?,
+        _ => return Err(ProtocolError::UnexpectedMessage),
+    };
+
+    let mut echoes = vec![];
+    for message in messages {
+        transport.send(encrypt_message(&key, message, rng))?;
+        echoes.push(decrypt_message(&key, &transport.recv()?)?);
+    }
+
+    Ok(echoes)
+}
+
+/// Runs the server of the Diffie–Hellman echo protocol until the client disconnects. The
+/// modulus proposed by the client must be odd and greater than 3. The rest of the group is
+/// only checked with validation, which also requires `1 < g < p`; the primality of `p` is
+/// never checked
+///
+/// # Args
+/// `transport`: the link to the client
+///
+/// `rng`: the source of the private key and of the IVs
+///
+/// `validate_public_keys`: whether the generator and the public key of the client are validated
+///
+/// # Returns
+/// A `Result` wrapping the plaintexts received from the client or a `ProtocolError`, which is
+/// `ProtocolError::MalformedMessage` if the proposed group is rejected
+pub fn dh_echo_server<T, R>(
+    mut transport: T,
+    rng: &mut R,
+    validate_public_keys: bool,
+) -> Result<Vec<Vec<u8>>, ProtocolError>
+where
+    T: Transport<DhEchoMessage>,
+    R: Rng,
+{
+    let (group, peer_key) = match transport.recv()? {
+        DhEchoMessage::Parameters { p, g, public_key } => {
+            (proposed_group(p, g, validate_public_keys)?, public_key)
+        }
+        DhEchoMessage::Negotiate { p, g } => {
+            let group = proposed_group(p, g, validate_public_keys)?;
+            transport.send(DhEchoMessage::Ack)?;
+            match transport.recv()? {
+                DhEchoMessage::PublicKey(public_key) => (group, public_key),
+                _ => return Err(ProtocolError::UnexpectedMessage),
+            }
+        }
+        _ => return Err(ProtocolError::UnexpectedMessage),
+    };
+
+    let mut dh = DiffieHellman::new(group, rng);
+    dh.set_public_key_validation(validate_public_keys);
+    let key = dh.derive_aes_key(&peer_key, Kdf::Sha1)?;
+    transport.send(DhEchoMessage::PublicKey(dh.public_key().clone()))?;
+
+    let mut received = vec![];
+    loop {
+        let message = match transport.recv() {
+            Ok(message) => message,
+            Err(ProtocolError::Disconnected) => return Ok(received),
+            Err(e) => return Err(e),
+        };
+        let plaintext = decrypt_message(&key, &message)?;
+        transport.send(encrypt_message(&key, &plaintext, rng))?;
+        received.push(plaintext);
+    }
+}
+
+/// Checks the group proposed by a client. Without validation only the modulus is checked, since
+/// the arithmetic modulo an even or tiny `p` panics
+fn proposed_group(p: BigUint, g: BigUint, validate: bool) -> Result<DhGroup, ProtocolError> {
+    if validate {
+        return DhGroup::new(p, g, None).map_err(|_| ProtocolError::MalformedMessage);
+    }
+    if p.is_even() || p <= BigUint::from(3u64) {
+        return Err(ProtocolError::MalformedMessage);
+    }
+    Ok(DhGroup::new_unchecked(p, g, None))
+}
+
+/// Encrypts a plaintext with AES-128-CBC, PKCS#7 padding and a random IV
+pub fn encrypt_message<R: Rng>(key: &[u8], plaintext: &[u8], rng: &mut R) -> DhEchoMessage {
+    let mut iv = vec![0; BLOCKSIZE];
+    rng.fill_bytes(&mut iv);
+
+    let cipher = Aes128::new(key.to_vec(), Mode::CBC, Some(iv.clone())).unwrap();
+    let ciphertext = cipher
+        .encrypt(&pad_pkcs7(plaintext, BLOCKSIZE).unwrap())
+        .unwrap();
+
+    DhEchoMessage::Encrypted { ciphertext, iv }
+}
+
+/// Decrypts a message produced by `encrypt_message`
+///
+/// # Returns
+/// A `Result` wrapping the plaintext, `ProtocolError::UnexpectedMessage` if the message is not
+/// `DhEchoMessage::Encrypted` or `ProtocolError::DecryptionFailed` if it does not decrypt to a
+/// correctly padded plaintext
+pub fn decrypt_message(key: &[u8], message: &DhEchoMessage) -> Result<Vec<u8>, ProtocolError> {
+    let DhEchoMessage::Encrypted { ciphertext, iv } = message else {
+        return Err(ProtocolError::UnexpectedMessage);
+    };
+
+    let cipher = Aes128::new(key.to_vec(), Mode::CBC, Some(iv.clone()))
+        .map_err(|_| ProtocolError::DecryptionFailed)?;
+    let plaintext = cipher
+        .decrypt(ciphertext)
+        .map_err(|_| ProtocolError::DecryptionFailed)?;
+
+    unpad_pkcs7(&plaintext, BLOCKSIZE).map_err(|_| ProtocolError::DecryptionFailed)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::TcpListener;
+    use std::thread;
+
+    use dh::{derive_aes_key, DhError};
+    use primitives::Mt19937;
+
+    use crate::{channel_pair, run_mitm, Action, Direction, TcpTransport};
+
+    use super::*;
+
+    type EchoResult = Result<Vec<Vec<u8>>, ProtocolError>;
+    type MitmLog = Vec<(Direction, DhEchoMessage)>;
+
+    fn messages() -> Vec<Vec<u8>> {
+        vec![
+            b"YELLOW SUBMARINE".to_vec(),
+            b"attack at dawn".to_vec(),
+            vec![],
+        ]
+    }
+
+    /// Runs the client and the server on their own threads with a man-in-the-middle between
+    /// them, returning the results of the client, the server and the man-in-the-middle log
+    fn run_with_mitm<F>(
+        config: DhEchoConfig,
+        server_validation: bool,
+        interceptor: F,
+    ) -> (EchoResult, EchoResult, MitmLog)
+    where
+        F: FnMut(
+            Direction,
+            &DhEchoMessage,
+            &mut crate::Outbox<DhEchoMessage>,
+        ) -> Action<DhEchoMessage>,
+    {
+        let (client, mitm_client) = channel_pair();
+        let (mitm_server, server) = channel_pair();
+
+        let server =
+            thread::spawn(move || dh_echo_server(server, &mut Mt19937::new(2), server_validation));
+        let client = thread::spawn(move || {
+            let group = DhGroup::modp_1536();
+            dh_echo_client(client, group, &messages(), &mut Mt19937::new(1), config)
+        });
+
+        let log = run_mitm(mitm_client, mitm_server, interceptor).unwrap();
+        (client.join().unwrap(), server.join().unwrap(), log)
+    }
+
+    /// Decrypts every message sent by the client with the given key
+    fn decrypt_client_messages(
+        log: &[(Direction, DhEchoMessage)],
+        secret: &BigUint,
+    ) -> Vec<Vec<u8>> {
+        let key = derive_aes_key(secret, Kdf::Sha1);
+        log.iter()
+            .filter(|(direction, _)| *direction == Direction::ClientToServer)
+            .filter_map(|(_, message)| decrypt_message(&key, message).ok())
+            .collect()
+    }
+
+    #[test]
+    fn dh_echo_honest() {
+        for handshake in [Handshake::Direct, Handshake::Negotiated] {
+            let config = DhEchoConfig {
+                handshake,
+                validate_public_keys: true,
+            };
+            let (client, server, log) = run_with_mitm(config, true, |_, _, _| Action::Forward);
+
+            assert_eq!(client.unwrap(), messages());
+            assert_eq!(server.unwrap(), messages());
+            assert!(decrypt_client_messages(&log, &BigUint::zero()).is_empty());
+        }
+    }
+
+    #[test]
+    fn dh_echo_over_tcp() {
+        let server_listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let server_address = server_listener.local_addr().unwrap();
+        let mitm_listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let mitm_address = mitm_listener.local_addr().unwrap();
+
+        let server = thread::spawn(move || {
+            let transport = TcpTransport::accept(&server_listener).unwrap();
+            dh_echo_server(transport, &mut Mt19937::new(2), true)
+        });
+        let client = thread::spawn(move || {
+            let transport = TcpTransport::connect(mitm_address).unwrap();
+            let group = DhGroup::modp_1536();
+            let config = DhEchoConfig::default();
+            dh_echo_client(transport, group, &messages(), &mut Mt19937::new(1), config)
+        });
+
+        let mitm_client = TcpTransport::<DhEchoMessage>::accept(&mitm_listener).unwrap();
+        let mitm_server = TcpTransport::connect(server_address).unwrap();
+        let log = run_mitm(mitm_client, mitm_server, |_, _, _| Action::Forward).unwrap();
+
+        assert_eq!(client.join().unwrap().unwrap(), messages());
+        assert_eq!(server.join().unwrap().unwrap(), messages());
+        assert_eq!(log.len(), 2 + 2 * messages().len());
+    }
+
+    /// Replaces both public keys with p, which makes both shared secrets 0
+    fn parameter_injection(direction: Direction, message: &DhEchoMessage) -> Action<DhEchoMessage> {
+        match (direction, message) {
+            (Direction::ClientToServer, DhEchoMessage::Parameters { p, g, .. }) => {
+                Action::Replace(DhEchoMessage::Parameters {
+                    p: p.clone(),
+                    g: g.clone(),
+                    public_key: p.clone(),
+                })
+            }
+            (Direction::ServerToClient, DhEchoMessage::PublicKey(_)) => {
+                Action::Replace(DhEchoMessage::PublicKey(DhGroup::modp_1536().p().clone()))
+            }
+            _ => Action::Forward,
+        }
+    }
+
+    #[test]
+    fn dh_echo_parameter_injection() {
+        let config = DhEchoConfig {
+            handshake: Handshake::Direct,
+            validate_public_keys: false,
+        };
+        let (client, server, log) = run_with_mitm(config, false, |direction, message, _| {
+            parameter_injection(direction, message)
+        });
+
+        // the conversation works and the man-in-the-middle reads all of it
+        assert_eq!(client.unwrap(), messages());
+        assert_eq!(server.unwrap(), messages());
+        assert_eq!(decrypt_client_messages(&log, &BigUint::zero()), messages());
+    }
+
+    #[test]
+    fn dh_echo_parameter_injection_validated() {
+        let (client, server, _) =
+            run_with_mitm(DhEchoConfig::default(), true, |direction, message, _| {
+                parameter_injection(direction, message)
+            });
+
+        assert_eq!(client, Err(ProtocolError::Disconnected));
+        assert_eq!(server, Err(ProtocolError::Dh(DhError::PublicKeyOutOfRange)));
+    }
+
+    /// Replaces the generator proposed to the server, returning the results of the client and
+    /// the server and the man-in-the-middle log
+    fn malicious_g(
+        g: BigUint,
+        client_validation: bool,
+        server_validation: bool,
+    ) -> (EchoResult, EchoResult, MitmLog) {
+        let config = DhEchoConfig {
+            handshake: Handshake::Negotiated,
+            validate_public_keys: client_validation,
+        };
+        run_with_mitm(config, server_validation, |direction, message, _| {
+            match (direction, message) {
+                (Direction::ClientToServer, DhEchoMessage::Negotiate { p, .. }) => {
+                    Action::Replace(DhEchoMessage::Negotiate {
+                        p: p.clone(),
+                        g: g.clone(),
+                    })
+                }
+                _ => Action::Forward,
+            }
+        })
+    }
+
+    #[test]
+    fn dh_echo_malicious_g() {
+        let p = DhGroup::modp_1536().p().clone();
+        let cases = [
+            // B = 1, so s = 1
+            (BigUint::one(), vec![BigUint::one()]),
+            // B = 0, so s = 0
+            (p.clone(), vec![BigUint::zero()]),
+            // B = ±1, so s = ±1
+            (&p - 1u64, vec![BigUint::one(), &p - 1u64]),
+        ];
+
+        for (g, secrets) in cases {
+            let (client, _, log) = malicious_g(g.clone(), false, false);
+
+            // the server can't decrypt under its own key, which ends the conversation, but the
+            // man-in-the-middle has already read the first message
+            assert!(client.is_err());
+            let decrypted: Vec<_> = secrets
+                .iter()
+                .flat_map(|secret| decrypt_client_messages(&log, secret))
+                .collect();
+            assert_eq!(decrypted, messages()[..1]);
+
+            let (client, _, _) = malicious_g(g, true, false);
+            assert_eq!(client, Err(ProtocolError::Dh(DhError::PublicKeyOutOfRange)));
+        }
+    }
+
+    #[test]
+    fn dh_echo_degenerate_g_rejected_with_validation() {
+        let p = DhGroup::modp_1536().p().clone();
+
+        // a validating server refuses g = 1 and g = p before answering
+        for g in [BigUint::one(), p] {
+            let (client, server, log) = malicious_g(g, false, true);
+
+            assert_eq!(client, Err(ProtocolError::Disconnected));
+            assert_eq!(server, Err(ProtocolError::MalformedMessage));
+            assert_eq!(log.len(), 1);
+        }
+    }
+
+    #[test]
+    fn dh_echo_server_rejects_invalid_groups() {
+        let cases: [(u64, u64, bool); 9] = [
+            (0, 2, false),
+            (3, 2, false),
+            (1000, 2, false),
+            (0, 2, true),
+            (3, 2, true),
+            (1000, 2, true),
+            (23, 0, true),
+            (23, 1, true),
+            (23, 23, true),
+        ];
+
+        for (p, g, validate) in cases {
+            let (mut client, server) = channel_pair();
+            client
+                .send(DhEchoMessage::Parameters {
+                    p: BigUint::from(p),
+                    g: BigUint::from(g),
+                    public_key: BigUint::from(2u64),
+                })
+                .unwrap();
+
+            let result = dh_echo_server(server, &mut Mt19937::new(2), validate);
+            assert_eq!(result, Err(ProtocolError::MalformedMessage));
+        }
+    }
+
+    #[test]
+    fn dh_echo_message_encoding() {
+        let message = DhEchoMessage::Parameters {
+            p: BigUint::from(37u64),
+            g: BigUint::zero(),
+            public_key: BigUint::from(1u64 << 40),
+        };
+        assert_eq!(DhEchoMessage::decode(&message.encode()), Ok(message));
+        assert_eq!(
+            DhEchoMessage::decode(&encode_fields(2, &[b"x"])),
+            Err(ProtocolError::MalformedMessage)
+        );
+    }
+}
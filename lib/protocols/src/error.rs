@@ -0,0 +1,56 @@
+use std::fmt;
+
+use dh::DhError;
+
+/// Errors that can occur while running a protocol
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProtocolError {
+    /// The other end of the transport is gone
+    Disconnected,
+
+    /// A message could not be decoded from the wire
+    MalformedMessage,
+
+    /// The peer sent a message that is not valid at this point of the protocol
+    UnexpectedMessage,
+
+    /// The peer could not prove that it knows the password or the verifier
+    AuthenticationFailed,
+
+    /// A received ciphertext did not decrypt to a correctly padded plaintext
+    DecryptionFailed,
+
+    /// The peer sent an invalid Diffie–Hellman public key
+    Dh(DhError),
+
+    /// An I/O error occurred on a TCP transport
+    Io(String),
+}
+
+impl std::error::Error for ProtocolError {}
+
+impl fmt::Display for ProtocolError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ProtocolError::Disconnected => "the peer disconnected".fmt(f),
+            ProtocolError::MalformedMessage => "the message could not be decoded".fmt(f),
+            ProtocolError::UnexpectedMessage => "the peer sent an unexpected message".fmt(f),
+            ProtocolError::AuthenticationFailed => "the authentication failed".fmt(f),
+            ProtocolError::DecryptionFailed => "the ciphertext could not be decrypted".fmt(f),
+            ProtocolError::Dh(e) => write!(f, "invalid Diffie-Hellman exchange: {}", e),
+            ProtocolError::Io(e) => write!(f, "I/O error: {}", e),
+        }
+    }
+}
+
+impl From<DhError> for ProtocolError {
+    fn from(e: DhError) -> Self {
+        ProtocolError::Dh(e)
+    }
+}
+
+impl From<std::io::Error> for ProtocolError {
+    fn from(e: std::io::Error) -> Self {
+        ProtocolError::Io(e.to_string())
+    }
+}
@@ -0,0 +1,14 @@
+mod error;
+pub use error::*;
+
+mod transport;
+pub use transport::*;
+
+mod mitm;
+pub use mitm::*;
+
+mod dh_echo;
+pub use dh_echo::*;
+
+mod srp;
+pub use srp::*;
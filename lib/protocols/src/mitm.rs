@@ -0,0 +1,163 @@
+use std::time::Duration;
+
+use crate::{ProtocolError, Transport};
+
+/// How long the man-in-the-middle waits on one side before polling the other
+const POLL_INTERVAL: Duration = Duration::from_millis(1);
+
+/// The direction of a message through the man-in-the-middle
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    ClientToServer,
+    ServerToClient,
+}
+
+/// What the man-in-the-middle does with an intercepted message
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Action<M> {
+    /// Deliver the message unchanged
+    Forward,
+
+    /// Discard the message
+    Drop,
+
+    /// Deliver another message in its place
+    Replace(M),
+}
+
+/// Messages the man-in-the-middle sends on its own initiative, after handling the intercepted
+/// message
+#[derive(Debug)]
+pub struct Outbox<M> {
+    messages: Vec<(Direction, M)>,
+}
+
+impl<M> Outbox<M> {
+    /// Queues a message to send in the given direction
+    pub fn inject(&mut self, direction: Direction, message: M) {
+        self.messages.push((direction, message));
+    }
+}
+
+/// Runs a man-in-the-middle between a client and a server until one of them disconnects.
+///
+/// Every message is passed to `interceptor` along with its direction and an `Outbox` for
+/// injecting extra messages, and is then forwarded, dropped or replaced according to the
+/// returned `Action`.
+///
+/// # Args
+/// `client`: the transport connected to the client
+///
+/// `server`: the transport connected to the server
+///
+/// `interceptor`: a closure that decides the fate of every message
+///
+/// # Returns
+/// A `Result` wrapping every intercepted message (before any replacement) in the order they
+/// were received, or a `ProtocolError` if a transport failed for another reason than a
+/// disconnection
+pub fn run_mitm<M, C, S, F>(
+    mut client: C,
+    mut server: S,
+    mut interceptor: F,
+) -> Result<Vec<(Direction, M)>, ProtocolError>
+where
+    M: Clone,
+    C: Transport<M>,
+    S: Transport<M>,
+    F: FnMut(Direction, &M, &mut Outbox<M>) -> Action<M>,
+{
+    let mut log = vec![];
+
+    loop {
+        for direction in [Direction::ClientToServer, Direction::ServerToClient] {
+            let received = match direction {
+                Direction::ClientToServer => client.recv_timeout(POLL_INTERVAL),
+                Direction::ServerToClient => server.recv_timeout(POLL_INTERVAL),
+            };
+            let message = match received {
+                Ok(Some(message)) => message,
+                Ok(None) => continue,
+                Err(ProtocolError::Disconnected) => return Ok(log),
+                Err(e) => return Err(e),
+            };
+
+            let mut outbox = Outbox { messages: vec![] };
+            let deliveries = match interceptor(direction, &message, &mut outbox) {
+                Action::Forward => vec![(direction, message.clone())],
+                Action::Drop => vec![],
+                Action::Replace(replacement) => vec![(direction, replacement)],
+            };
+            log.push((direction, message));
+
+            for (direction, message) in deliveries.into_iter().chain(outbox.messages) {
+                let sent = match direction {
+                    Direction::ClientToServer => server.send(message),
+                    Direction::ServerToClient => client.send(message),
+                };
+                match sent {
+                    Ok(()) => {}
+                    Err(ProtocolError::Disconnected) => return Ok(log),
+                    Err(e) => return Err(e),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use crate::channel_pair;
+
+    use super::*;
+
+    #[test]
+    fn mitm_forward_drop_replace_inject() {
+        let (client, mitm_client) = channel_pair::<u32>();
+        let (mitm_server, server) = channel_pair::<u32>();
+
+        let server = thread::spawn(move || {
+            let mut server = server;
+            let mut received = vec![];
+            while let Ok(message) = server.recv() {
+                received.push(message);
+                server.send(message * 10).unwrap();
+            }
+            received
+        });
+
+        let client = thread::spawn(move || {
+            let mut client = client;
+            let mut replies = vec![];
+            for message in [1, 2, 3] {
+                client.send(message).unwrap();
+                if message != 2 {
+                    replies.push(client.recv().unwrap());
+                }
+            }
+            replies.push(client.recv().unwrap());
+            replies
+        });
+
+        let log = run_mitm(
+            mitm_client,
+            mitm_server,
+            |direction, &message, outbox| match (direction, message) {
+                (Direction::ClientToServer, 2) => Action::Drop,
+                (Direction::ClientToServer, 3) => {
+                    outbox.inject(Direction::ServerToClient, 99);
+                    Action::Replace(4)
+                }
+                _ => Action::Forward,
+            },
+        )
+        .unwrap();
+
+        assert_eq!(client.join().unwrap(), vec![10, 99, 40]);
+        assert_eq!(server.join().unwrap(), vec![1, 4]);
+        assert_eq!(log[0], (Direction::ClientToServer, 1));
+        assert!(log.contains(&(Direction::ClientToServer, 2)));
+    }
+}
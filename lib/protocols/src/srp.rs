@@ -0,0 +1,785 @@
+use dh::{DhError, DhGroup};
+use primitives::{ct_eq, BigUint, Hmac, Mac, Rng, Sha256};
+
+use crate::{decode_fields, encode_fields, ProtocolError, Transport, Wire};
+
+/// The size of the salts generated for new verifiers
+pub const SRP_SALT_SIZE: usize = 16;
+
+/// The messages of the SRP login protocol
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SrpMessage {
+    /// C->S: I, A
+    Hello {
+        identity: String,
+        public_key: BigUint,
+    },
+
+    /// S->C: salt, B (SRP-6a)
+    Challenge { salt: Vec<u8>, public_key: BigUint },
+
+    /// S->C: salt, B, u (simplified SRP)
+    SimplifiedChallenge {
+        salt: Vec<u8>,
+        public_key: BigUint,
+        u: BigUint,
+    },
+
+    /// C->S: HMAC(K, salt)
+    Proof(Vec<u8>),
+
+    /// S->C: HMAC(K, PAD(A) || client proof)
+    Accepted(Vec<u8>),
+
+    /// S->C: the login failed
+    Rejected,
+}
+
+impl Wire for SrpMessage {
+    fn encode(&self) -> Vec<u8> {
+        match self {
+            SrpMessage::Hello {
+                identity,
+                public_key,
+            } => encode_fields(0, &[identity.as_bytes(), &public_key.to_be_bytes()]),
+            SrpMessage::Challenge { salt, public_key } => {
+                encode_fields(1, &[salt, &public_key.to_be_bytes()])
+            }
+            SrpMessage::SimplifiedChallenge {
+                salt,
+                public_key,
+                u,
+            } => encode_fields(2, &[salt, &public_key.to_be_bytes(), &u.to_be_bytes()]),
+            SrpMessage::Proof(proof) => encode_fields(3, &[proof]),
+            SrpMessage::Accepted(proof) => encode_fields(4, &[proof]),
+            SrpMessage::Rejected => encode_fields(5, &[]),
+        }
+    }
+
+    fn decode(bytes: &[u8]) -> Result<Self, ProtocolError> {
+        let (tag, mut fields) = decode_fields(bytes)?;
+        let integer = |i: usize| BigUint::from_be_bytes(&fields[i]);
+
+        let message = match (tag, fields.len()) {
+            (0, 2) => SrpMessage::Hello {
+                public_key: integer(1),
+                identity: String::from_utf8(fields.swap_remove(0))
+                    .map_err(|_| ProtocolError::MalformedMessage)?,
+            },
+            (1, 2) => SrpMessage::Challenge {
+                public_key: integer(1),
+                salt: fields.swap_remove(0),
+            },
+            (2, 3) => SrpMessage::SimplifiedChallenge {
+                public_key: integer(1),
+                u: integer(2),
+                salt: fields.swap_remove(0),
+            },
+            (3, 1) => SrpMessage::Proof(fields.swap_remove(0)),
+            (4, 1) => SrpMessage::Accepted(fields.swap_remove(0)),
+            (5, 0) => SrpMessage::Rejected,
+            _ => return Err(ProtocolError::MalformedMessage),
+        };
+
+        Ok(message)
+    }
+}
+
+/// The flavour of SRP spoken by a party
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SrpVariant {
+    /// SRP-6a (RFC 5054): `B = kv + g^b`, `u = H(PAD(A) || PAD(B))`
+    Srp6a,
+
+    /// Simplified SRP: `B = g^b` and `u` is a random 128 bit number chosen by the server. The
+    /// password no longer enters `B`, which enables offline dictionary attacks by a fake server
+    Simplified,
+}
+
+/// The settings of a party of the SRP login protocol
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SrpConfig {
+    /// The flavour of SRP. Clients and servers must agree on it
+    pub variant: SrpVariant,
+
+    /// Whether the ephemeral public key received from the peer is checked to be non-zero
+    /// modulo N
+    pub validate_public_keys: bool,
+}
+
+impl Default for SrpConfig {
+    fn default() -> Self {
+        SrpConfig {
+            variant: SrpVariant::Srp6a,
+            validate_public_keys: true,
+        }
+    }
+}
+
+/// The record a server stores for a user instead of the password
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SrpVerifier {
+    identity: String,
+    salt: Vec<u8>,
+    verifier: BigUint,
+}
+
+impl SrpVerifier {
+    /// Creates the verifier of a password with a random salt
+    pub fn new<R: Rng>(group: &DhGroup, identity: &str, password: &str, rng: &mut R) -> Self {
+        let mut salt = vec![0; SRP_SALT_SIZE];
+        rng.fill_bytes(&mut salt);
+        SrpVerifier::from_salt(group, identity, password, salt)
+    }
+
+    /// Creates the verifier `v = g^x mod N` of a password with the given salt
+    pub fn from_salt(group: &DhGroup, identity: &str, password: &str, salt: Vec<u8>) -> Self {
+        let x = srp_private_key(&salt, identity, password);
+        SrpVerifier {
+            identity: identity.to_string(),
+            verifier: group.g().modpow_ct(&x, group.p()),
+            salt,
+        }
+    }
+
+    pub fn identity(&self) -> &str {
+        &self.identity
+    }
+
+    pub fn salt(&self) -> &[u8] {
+        &self.salt
+    }
+
+    pub fn verifier(&self) -> &BigUint {
+        &self.verifier
+    }
+}
+
+/// The outcome of a successful login on the server side
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SrpSession {
+    pub identity: String,
+    pub key: Vec<u8>,
+}
+
+/// Computes the private key `x = H(salt || H(I || ":" || P))`
+pub fn srp_private_key(salt: &[u8], identity: &str, password: &str) -> BigUint {
+    let mut credentials = Sha256::default();
+    credentials.update(identity.as_bytes());
+    credentials.update(b":");
+    credentials.update(password.as_bytes());
+
+    let mut hash = Sha256::default();
+    hash.update(salt);
+    hash.update(&credentials.finalize());
+    BigUint::from_be_bytes(&hash.finalize())
+}
+
+/// Computes the SRP-6a multiplier `k = H(N || PAD(g))`
+pub fn srp_multiplier(group: &DhGroup) -> BigUint {
+    BigUint::from_be_bytes(&hash_padded(group, &[group.p(), group.g()]))
+}
+
+/// Computes the SRP-6a scrambling parameter `u = H(PAD(A) || PAD(B))`
+pub fn srp_scrambler(group: &DhGroup, client_key: &BigUint, server_key: &BigUint) -> BigUint {
+    BigUint::from_be_bytes(&hash_padded(group, &[client_key, server_key]))
+}
+
+/// Derives the session key `K = H(S)` from the premaster secret
+pub fn srp_session_key(secret: &BigUint) -> Vec<u8> {
+    Sha256::digest(&secret.to_be_bytes())
+}
+
+/// Computes the proof of the client `HMAC(K, salt)`
+pub fn srp_client_proof(key: &[u8], salt: &[u8]) -> Vec<u8> {
+    Hmac::<Sha256>::mac(key, salt)
+}
+
+/// Computes the proof of the server `HMAC(K, PAD(A) || M)`
+fn srp_server_proof(group: &DhGroup, key: &[u8], client_key: &BigUint, proof: &[u8]) -> Vec<u8> {
+    let mut hmac = Hmac::<Sha256>::new(key);
+    hmac.update(&pad(group, client_key));
+    hmac.update(proof);
+    hmac.finalize()
+}
+
+/// Left-pads an integer to the byte length of N
+fn pad(group: &DhGroup, n: &BigUint) -> Vec<u8> {
+    let length = group.p().bits().div_ceil(8);
+    n.to_be_bytes_padded(length)
+        .unwrap_or_else(|| n.to_be_bytes())
+}
+
+fn hash_padded(group: &DhGroup, integers: &[&BigUint]) -> Vec<u8> {
+    let mut hash = Sha256::default();
+    for n in integers {
+        hash.update(&pad(group, n));
+    }
+    hash.finalize()
+}
+
+/// Runs the client side of an SRP login
+///
+/// # Args
+/// `transport`: the link to the server
+///
+/// `group`: the group `(N, g)` shared with the server
+///
+/// `identity`: the user name `I`
+///
+/// `password`: the password `P`
+///
+/// `rng`: the source of the ephemeral private key
+///
+/// `config`: the variant and validation settings
+///
+/// # Returns
+/// A `Result` wrapping the session key `K`, `ProtocolError::AuthenticationFailed` if the
+/// server rejected the login or could not prove that it knows the verifier, or another
+/// `ProtocolError`
+///
+/// # Examples
+/// ```
+/// use std::thread;
+///
+/// use dh::DhGroup;
+/// use primitives::OsRng;
+/// use protocols::{channel_pair, srp_client, srp_server, SrpConfig, SrpVerifier};
+///
+/// let group = DhGroup::modp_1536();
+/// let user = SrpVerifier::new(&group, "alice", "hunter2", &mut OsRng);
+///
+/// let (client, server) = channel_pair();
+/// let server_group = group.clone();
+/// let server = thread::spawn(move || {
+///     srp_server(server, &server_group, &[user], &mut OsRng, SrpConfig::default())
+/// });
+///
+/// let key = srp_client(client, &group, "alice", "hunter2", &mut OsRng, SrpConfig::default());
+/// assert_eq!(key.unwrap(), server.join().unwrap().unwrap().key);
+/// ```
+pub fn srp_client<T, R>(
+    mut transport: T,
+    group: &DhGroup,
+    identity: &str,
+    password: &str,
+    rng: &mut R,
+    config: SrpConfig,
+) -> Result<Vec<u8>, ProtocolError>
+where
+    T: Transport<SrpMessage>,
+    R: Rng,
+{
+    let (n, g) = (group.p(), group.g());
+    let a = BigUint::random_range(rng, &BigUint::one(), n);
+    let client_key = g.modpow_ct(&a, n);
+    transport.send(SrpMessage::Hello {
+        identity: identity.to_string(),
+        public_key: client_key.clone(),
+    })?;
+
+    let (salt, server_key, u) = match (config.variant, transport.recv()?) {
+        (SrpVariant::Srp6a, SrpMessage::Challenge { salt, public_key }) => {
+            let u = srp_scrambler(group, &client_key, &public_key);
+            (salt, public_key, u)
+        }
+        (
+            SrpVariant::Simplified,
+            SrpMessage::SimplifiedChallenge {
+                salt,
+                public_key,
+                u,
+            },
+        ) => (salt, public_key, u),
+        (_, SrpMessage::Rejected) => return Err(ProtocolError::AuthenticationFailed),
+        _ => return Err(ProtocolError::UnexpectedMessage),
+    };
+
+    if config.validate_public_keys && ((&server_key % n).is_zero() || u.is_zero()) {
+        return Err(DhError::PublicKeyOutOfRange.into());
+    }
+
+    // S = (B - kg^x)^(a + ux), with k = 0 for simplified SRP
+    let x = srp_private_key(&salt, identity, password);
+    let base = match config.variant {
+        SrpVariant::Srp6a => {
+            let kgx = (srp_multiplier(group) * g.modpow_ct(&x, n)) % n;
+            (&server_key % n + n - kgx) % n
+        }
+        SrpVariant::Simplified => &server_key % n,
+    };
+    let secret = base.modpow_ct(&(a + u * x), n);
+
+    let key = srp_session_key(&secret);
+    let proof = srp_client_proof(&key, &salt);
+    transport.send(SrpMessage::Proof(proof.clone()))?;
+
+    match transport.recv()? {
+        SrpMessage::Accepted(server_proof)
+            if ct_eq(
+                &server_proof,
+                &srp_server_proof(group, &key, &client_key, &proof),
+            ) =>
+        {
+            Ok(key)
+        }
+        SrpMessage::Accepted(_) | SrpMessage::Rejected => Err(ProtocolError::AuthenticationFailed),
+        _ => Err(ProtocolError::UnexpectedMessage),
+    }
+}
+
+/// Runs the server side of one SRP login
+///
+/// # Args
+/// `transport`: the link to the client
+///
+/// `group`: the group `(N, g)` shared with the client
+///
+/// `users`: the verifiers of the registered users
+///
+/// `rng`: the source of the ephemeral private key and, for simplified SRP, of `u`
+///
+/// `config`: the variant and validation settings. Without validation, a client sending
+/// `A ≡ 0 mod N` logs in as anyone
+///
+/// # Returns
+/// A `Result` wrapping the identity and session key of the client,
+/// `ProtocolError::AuthenticationFailed` if the identity is unknown or the proof of the client
+/// is wrong, or another `ProtocolError`
+pub fn srp_server<T, R>(
+    mut transport: T,
+    group: &DhGroup,
+    users: &[SrpVerifier],
+    rng: &mut R,
+    config: SrpConfig,
+) -> Result<SrpSession, ProtocolError>
+where
+    T: Transport<SrpMessage>,
+    R: Rng,
+{
+    let (n, g) = (group.p(), group.g());
+    let (identity, client_key) = match transport.recv()? {
+        SrpMessage::Hello {
+            identity,
+            public_key,
+        } => (identity, public_key),
+        _ => return Err(ProtocolError::UnexpectedMessage),
+    };
+
+    let Some(user) = users.iter().find(|user| user.identity == identity) else {
+        transport.send(SrpMessage::Rejected)?;
+        return Err(ProtocolError::AuthenticationFailed);
+    };
+    if config.validate_public_keys && (&client_key % n).is_zero() {
+        transport.send(SrpMessage::Rejected)?;
+        return Err(DhError::PublicKeyOutOfRange.into());
+    }
+
+    let b = BigUint::random_range(rng, &BigUint::one(), n);
+    let v = &user.verifier;
+    let salt = user.salt.clone();
+    let u = match config.variant {
+        SrpVariant::Srp6a => {
+            let server_key = (srp_multiplier(group) * v + g.modpow_ct(&b, n)) % n;
+            let u = srp_scrambler(group, &client_key, &server_key);
+            transport.send(SrpMessage::Challenge {
+                salt: salt.clone(),
+                public_key: server_key,
+            })?;
+            u
+        }
+        SrpVariant::Simplified => {
+            let u = BigUint::random_bits(rng, 128);
+            transport.send(SrpMessage::SimplifiedChallenge {
+                salt: salt.clone(),
+                public_key: g.modpow_ct(&b, n),
+                u: u.clone(),
+            })?;
+            u
+        }
+    };
+
+    // S = (Av^u)^b
+    let secret = ((&client_key % n) * v.modpow_ct(&u, n) % n).modpow_ct(&b, n);
+    let key = srp_session_key(&secret);
+
+    let proof = match transport.recv()? {
+        SrpMessage::Proof(proof) => proof,
+        _ => return Err(ProtocolError::UnexpectedMessage),
+    };
+    if !ct_eq(&proof, &srp_client_proof(&key, &salt)) {
+        transport.send(SrpMessage::Rejected)?;
+        return Err(ProtocolError::AuthenticationFailed);
+    }
+
+    let server_proof = srp_server_proof(group, &key, &client_key, &proof);
+    transport.send(SrpMessage::Accepted(server_proof))?;
+
+    Ok(SrpSession { identity, key })
+}
+
+/// Logs in as `identity` without the password by sending an ephemeral public key `A = cN`,
+/// which forces the premaster secret computed by the server to 0. Only works against servers
+/// that skip the validation of `A`, for both variants of SRP
+///
+/// # Args
+/// `transport`: the link to the server
+///
+/// `identity`: the user to impersonate
+///
+/// `multiple`: the factor `c` in `A = cN`, 0 included
+///
+/// # Returns
+/// A `Result` wrapping the session key shared with the server, or
+/// `ProtocolError::AuthenticationFailed` if the server rejected the login
+pub fn srp_zero_key_login<T: Transport<SrpMessage>>(
+    mut transport: T,
+    group: &DhGroup,
+    identity: &str,
+    multiple: u64,
+) -> Result<Vec<u8>, ProtocolError> {
+    transport.send(SrpMessage::Hello {
+        identity: identity.to_string(),
+        public_key: group.p() * multiple,
+    })?;
+
+    let salt = match transport.recv()? {
+        SrpMessage::Challenge { salt, .. } | SrpMessage::SimplifiedChallenge { salt, .. } => salt,
+        SrpMessage::Rejected => return Err(ProtocolError::AuthenticationFailed),
+        _ => return Err(ProtocolError::UnexpectedMessage),
+    };
+
+    let key = srp_session_key(&BigUint::zero());
+    transport.send(SrpMessage::Proof(srp_client_proof(&key, &salt)))?;
+
+    match transport.recv()? {
+        SrpMessage::Accepted(_) => Ok(key),
+        SrpMessage::Rejected => Err(ProtocolError::AuthenticationFailed),
+        _ => Err(ProtocolError::UnexpectedMessage),
+    }
+}
+
+/// What a fake simplified SRP server learns from a login attempt, enough to test passwords
+/// offline
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SrpCapture {
+    pub identity: String,
+    pub salt: Vec<u8>,
+    pub client_key: BigUint,
+    pub server_private_key: BigUint,
+    pub u: BigUint,
+    pub proof: Vec<u8>,
+}
+
+impl SrpCapture {
+    /// Checks whether `password` produces the captured proof, by computing
+    /// `S = (A * v^u)^b` with `v = g^x` as the server would
+    pub fn check_password(&self, group: &DhGroup, password: &str) -> bool {
+        let n = group.p();
+        let x = srp_private_key(&self.salt, &self.identity, password);
+        let v = group.g().modpow(&x, n);
+        let secret =
+            (&self.client_key * v.modpow(&self.u, n) % n).modpow(&self.server_private_key, n);
+        let key = srp_session_key(&secret);
+
+        srp_client_proof(&key, &self.salt) == self.proof
+    }
+}
+
+/// Impersonates a simplified SRP server to capture the proof of a client. In simplified SRP
+/// the client cannot tell that the server does not know its verifier until after it sent its
+/// proof, which is then rejected
+///
+/// # Args
+/// `transport`: the link to the client
+///
+/// `group`: the group `(N, g)` expected by the client
+///
+/// `rng`: the source of the salt, `b` and `u`
+///
+/// # Returns
+/// A `Result` wrapping the captured values or a `ProtocolError`
+pub fn srp_impersonate_server<T, R>(
+    mut transport: T,
+    group: &DhGroup,
+    rng: &mut R,
+) -> Result<SrpCapture, ProtocolError>
+where
+    T: Transport<SrpMessage>,
+    R: Rng,
+{
+    let (identity, client_key) = match transport.recv()? {
+        SrpMessage::Hello {
+            identity,
+            public_key,
+        } => (identity, public_key),
+        _ => return Err(ProtocolError::UnexpectedMessage),
+    };
+
+    let mut salt = vec![0; SRP_SALT_SIZE];
+    rng.fill_bytes(&mut salt);
+    let b = BigUint::random_range(rng, &BigUint::one(), group.p());
+    let u = BigUint::random_bits(rng, 128);
+    transport.send(SrpMessage::SimplifiedChallenge {
+        salt: salt.clone(),
+        public_key: group.g().modpow(&b, group.p()),
+        u: u.clone(),
+    })?;
+
+    let proof = match transport.recv()? {
+        SrpMessage::Proof(proof) => proof,
+        _ => return Err(ProtocolError::UnexpectedMessage),
+    };
+    transport.send(SrpMessage::Rejected)?;
+
+    Ok(SrpCapture {
+        identity,
+        salt,
+        client_key,
+        server_private_key: b,
+        u,
+        proof,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::TcpListener;
+    use std::thread;
+
+    use primitives::Mt19937;
+
+    use crate::{channel_pair, TcpTransport};
+
+    use super::*;
+
+    fn users(group: &DhGroup) -> Vec<SrpVerifier> {
+        let mut rng = Mt19937::new(3);
+        vec![
+            SrpVerifier::new(group, "alice@example.com", "correct horse", &mut rng),
+            SrpVerifier::new(group, "bob@example.com", "battery staple", &mut rng),
+        ]
+    }
+
+    /// Runs a server on its own thread and a client on the current one
+    fn login<F>(
+        config: SrpConfig,
+        client: F,
+    ) -> (
+        Result<Vec<u8>, ProtocolError>,
+        Result<SrpSession, ProtocolError>,
+    )
+    where
+        F: FnOnce(crate::ChannelTransport<SrpMessage>, &DhGroup) -> Result<Vec<u8>, ProtocolError>,
+    {
+        let group = DhGroup::modp_1536();
+        let (client_side, server_side) = channel_pair();
+
+        let server_group = group.clone();
+        let server = thread::spawn(move || {
+            let users = users(&server_group);
+            srp_server(
+                server_side,
+                &server_group,
+                &users,
+                &mut Mt19937::new(4),
+                config,
+            )
+        });
+
+        let result = client(client_side, &group);
+        (result, server.join().unwrap())
+    }
+
+    #[test]
+    fn srp_login() {
+        for variant in [SrpVariant::Srp6a, SrpVariant::Simplified] {
+            let config = SrpConfig {
+                variant,
+                validate_public_keys: true,
+            };
+            let (client, server) = login(config, |transport, group| {
+                let mut rng = Mt19937::new(5);
+                srp_client(
+                    transport,
+                    group,
+                    "bob@example.com",
+                    "battery staple",
+                    &mut rng,
+                    config,
+                )
+            });
+
+            let session = server.unwrap();
+            assert_eq!(session.identity, "bob@example.com");
+            assert_eq!(client.unwrap(), session.key);
+        }
+    }
+
+    #[test]
+    fn srp_wrong_password() {
+        for variant in [SrpVariant::Srp6a, SrpVariant::Simplified] {
+            let config = SrpConfig {
+                variant,
+                validate_public_keys: true,
+            };
+            let (client, server) = login(config, |transport, group| {
+                let mut rng = Mt19937::new(5);
+                srp_client(
+                    transport,
+                    group,
+                    "bob@example.com",
+                    "battery",
+                    &mut rng,
+                    config,
+                )
+            });
+
+            assert_eq!(client, Err(ProtocolError::AuthenticationFailed));
+            assert_eq!(server, Err(ProtocolError::AuthenticationFailed));
+        }
+    }
+
+    #[test]
+    fn srp_unknown_identity() {
+        let config = SrpConfig::default();
+        let (client, server) = login(config, |transport, group| {
+            let mut rng = Mt19937::new(5);
+            srp_client(
+                transport,
+                group,
+                "eve@example.com",
+                "battery",
+                &mut rng,
+                config,
+            )
+        });
+
+        assert_eq!(client, Err(ProtocolError::AuthenticationFailed));
+        assert_eq!(server, Err(ProtocolError::AuthenticationFailed));
+    }
+
+    #[test]
+    fn srp_login_over_tcp() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let group = DhGroup::modp_1536();
+
+        let server_group = group.clone();
+        let server = thread::spawn(move || {
+            let transport = TcpTransport::accept(&listener).unwrap();
+            let users = users(&server_group);
+            let config = SrpConfig::default();
+            srp_server(
+                transport,
+                &server_group,
+                &users,
+                &mut Mt19937::new(4),
+                config,
+            )
+        });
+
+        let transport = TcpTransport::connect(address).unwrap();
+        let mut rng = Mt19937::new(5);
+        let config = SrpConfig::default();
+        let key = srp_client(
+            transport,
+            &group,
+            "alice@example.com",
+            "correct horse",
+            &mut rng,
+            config,
+        );
+
+        assert_eq!(key.unwrap(), server.join().unwrap().unwrap().key);
+    }
+
+    #[test]
+    fn srp_zero_key_bypass() {
+        for variant in [SrpVariant::Srp6a, SrpVariant::Simplified] {
+            for multiple in [0, 1, 2, 5] {
+                let config = SrpConfig {
+                    variant,
+                    validate_public_keys: false,
+                };
+                let (client, server) = login(config, |transport, group| {
+                    srp_zero_key_login(transport, group, "alice@example.com", multiple)
+                });
+
+                let session = server.unwrap();
+                assert_eq!(session.identity, "alice@example.com");
+                assert_eq!(client.unwrap(), session.key);
+
+                let config = SrpConfig {
+                    variant,
+                    validate_public_keys: true,
+                };
+                let (client, server) = login(config, |transport, group| {
+                    srp_zero_key_login(transport, group, "alice@example.com", multiple)
+                });
+
+                assert_eq!(client, Err(ProtocolError::AuthenticationFailed));
+                assert_eq!(server, Err(ProtocolError::Dh(DhError::PublicKeyOutOfRange)));
+            }
+        }
+    }
+
+    #[test]
+    fn srp_message_encoding() {
+        let messages = [
+            SrpMessage::Hello {
+                identity: "alice".to_string(),
+                public_key: BigUint::from(12345u64),
+            },
+            SrpMessage::SimplifiedChallenge {
+                salt: vec![1, 2, 3],
+                public_key: BigUint::zero(),
+                u: BigUint::from(7u64),
+            },
+            SrpMessage::Accepted(vec![]),
+            SrpMessage::Rejected,
+        ];
+        for message in messages {
+            assert_eq!(SrpMessage::decode(&message.encode()), Ok(message));
+        }
+
+        let invalid_identity = encode_fields(0, &[&[0xff], &[1]]);
+        assert_eq!(
+            SrpMessage::decode(&invalid_identity),
+            Err(ProtocolError::MalformedMessage)
+        );
+    }
+
+    #[test]
+    fn srp_impersonation_captures_proof() {
+        let group = DhGroup::modp_1536();
+        let (client_side, server_side) = channel_pair();
+
+        let client_group = group.clone();
+        let client = thread::spawn(move || {
+            let config = SrpConfig {
+                variant: SrpVariant::Simplified,
+                validate_public_keys: true,
+            };
+            let mut rng = Mt19937::new(5);
+            srp_client(
+                client_side,
+                &client_group,
+                "alice",
+                "sunshine",
+                &mut rng,
+                config,
+            )
+        });
+
+        let capture = srp_impersonate_server(server_side, &group, &mut Mt19937::new(6)).unwrap();
+        assert_eq!(
+            client.join().unwrap(),
+            Err(ProtocolError::AuthenticationFailed)
+        );
+
+        assert_eq!(capture.identity, "alice");
+        assert!(capture.check_password(&group, "sunshine"));
+        assert!(!capture.check_password(&group, "moonshine"));
+    }
+}
@@ -0,0 +1,293 @@
+use std::io::{ErrorKind, Read, Write};
+use std::marker::PhantomData;
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::time::Duration;
+
+use crate::ProtocolError;
+
+/// The largest frame a `TcpTransport` sends or accepts, in bytes
+pub const MAX_FRAME_SIZE: usize = 1 << 20;
+
+/// One end of a bidirectional link between two actors
+pub trait Transport<M>: Send {
+    /// Sends a message to the other end
+    fn send(&mut self, message: M) -> Result<(), ProtocolError>;
+
+    /// Waits for the next message from the other end
+    fn recv(&mut self) -> Result<M, ProtocolError>;
+
+    /// Waits at most `timeout` for the next message from the other end
+    ///
+    /// # Returns
+    /// A `Result` wrapping the message, or `None` if nothing arrived in time, or a
+    /// `ProtocolError` if the other end is gone
+    fn recv_timeout(&mut self, timeout: Duration) -> Result<Option<M>, ProtocolError>;
+}
+
+/// Messages that can be sent over a byte stream
+pub trait Wire: Sized {
+    /// Serializes the message
+    fn encode(&self) -> Vec<u8>;
+
+    /// Deserializes a message produced by `encode`
+    fn decode(bytes: &[u8]) -> Result<Self, ProtocolError>;
+}
+
+/// An in-process transport built on a pair of channels
+#[derive(Debug)]
+pub struct ChannelTransport<M> {
+    sender: Sender<M>,
+    receiver: Receiver<M>,
+}
+
+/// Creates the two connected ends of an in-process link
+///
+/// # Examples
+/// ```
+/// use protocols::{channel_pair, Transport};
+///
+/// let (mut alice, mut bob) = channel_pair();
+/// alice.send("hello").unwrap();
+/// assert_eq!(bob.recv().unwrap(), "hello");
+/// ```
+pub fn channel_pair<M>() -> (ChannelTransport<M>, ChannelTransport<M>) {
+    let (a_sender, b_receiver) = mpsc::channel();
+    let (b_sender, a_receiver) = mpsc::channel();
+
+    (
+        ChannelTransport {
+            sender: a_sender,
+            receiver: a_receiver,
+        },
+        ChannelTransport {
+            sender: b_sender,
+            receiver: b_receiver,
+        },
+    )
+}
+
+impl<M: Send> Transport<M> for ChannelTransport<M> {
+    fn send(&mut self, message: M) -> Result<(), ProtocolError> {
+        self.sender
+            .send(message)
+            .map_err(|_| ProtocolError::Disconnected)
+    }
+
+    fn recv(&mut self) -> Result<M, ProtocolError> {
+        self.receiver
+            .recv()
+            .map_err(|_| ProtocolError::Disconnected)
+    }
+
+    fn recv_timeout(&mut self, timeout: Duration) -> Result<Option<M>, ProtocolError> {
+        match self.receiver.recv_timeout(timeout) {
+            Ok(message) => Ok(Some(message)),
+            Err(RecvTimeoutError::Timeout) => Ok(None),
+            Err(RecvTimeoutError::Disconnected) => Err(ProtocolError::Disconnected),
+        }
+    }
+}
+
+/// A transport over a TCP connection. Every message is sent as a 4 bytes big endian length
+/// followed by its `Wire` encoding, which can't be longer than `MAX_FRAME_SIZE` bytes
+#[derive(Debug)]
+pub struct TcpTransport<M> {
+    stream: TcpStream,
+    message: PhantomData<fn() -> M>,
+}
+
+impl<M: Wire> TcpTransport<M> {
+    /// Wraps an established connection
+    pub fn new(stream: TcpStream) -> TcpTransport<M> {
+        TcpTransport {
+            stream,
+            message: PhantomData,
+        }
+    }
+
+    /// Connects to a listening actor
+    pub fn connect<A: ToSocketAddrs>(address: A) -> Result<TcpTransport<M>, ProtocolError> {
+        Ok(TcpTransport::new(TcpStream::connect(address)?))
+    }
+
+    /// Waits for an actor to connect
+    pub fn accept(listener: &TcpListener) -> Result<TcpTransport<M>, ProtocolError> {
+        let (stream, _) = listener.accept()?;
+        Ok(TcpTransport::new(stream))
+    }
+
+    fn read_frame(&mut self) -> Result<M, ProtocolError> {
+        let mut length = [0; 4];
+        self.stream
+            .read_exact(&mut length)
+            .map_err(map_read_error)?;
+
+        let length = u32::from_be_bytes(length) as usize;
+        if length > MAX_FRAME_SIZE {
+            return Err(ProtocolError::MalformedMessage);
+        }
+
+        let mut frame = vec![0; length];
+        self.stream.read_exact(&mut frame).map_err(map_read_error)?;
+
+        M::decode(&frame)
+    }
+}
+
+impl<M: Wire> Transport<M> for TcpTransport<M> {
+    fn send(&mut self, message: M) -> Result<(), ProtocolError> {
+        let frame = message.encode();
+        if frame.len() > MAX_FRAME_SIZE {
+            return Err(ProtocolError::MalformedMessage);
+        }
+
+        let mut bytes = (frame.len() as u32).to_be_bytes().to_vec();
+        bytes.extend(frame);
+        self.stream.write_all(&bytes).map_err(map_read_error)
+    }
+
+    fn recv(&mut self) -> Result<M, ProtocolError> {
+        self.stream.set_read_timeout(None)?;
+        self.read_frame()
+    }
+
+    fn recv_timeout(&mut self, timeout: Duration) -> Result<Option<M>, ProtocolError> {
+        // wait for the first byte with a timeout, then read the whole frame blocking
+        self.stream.set_read_timeout(Some(timeout))?;
+        let available = self.stream.peek(&mut [0]);
+        self.stream.set_read_timeout(None)?;
+
+        match available {
+            Ok(0) => Err(ProtocolError::Disconnected),
+            Ok(_) => self.read_frame().map(Some),
+            Err(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => Ok(None),
+            Err(e) => Err(map_read_error(e)),
+        }
+    }
+}
+
+/// Maps the errors of a closed connection to `ProtocolError::Disconnected`
+fn map_read_error(e: std::io::Error) -> ProtocolError {
+    match e.kind() {
+        ErrorKind::UnexpectedEof
+        | ErrorKind::ConnectionReset
+        | ErrorKind::ConnectionAborted
+        | ErrorKind::BrokenPipe => ProtocolError::Disconnected,
+        _ => ProtocolError::from(e),
+    }
+}
+
+/// Serializes a message as a tag byte followed by length-prefixed fields
+pub fn encode_fields(tag: u8, fields: &[&[u8]]) -> Vec<u8> {
+    let mut bytes = vec![tag];
+    for field in fields {
+        bytes.extend((field.len() as u32).to_be_bytes());
+        bytes.extend(*field);
+    }
+    bytes
+}
+
+/// Deserializes a message produced by `encode_fields`
+///
+/// # Returns
+/// A `Result` wrapping the tag and the fields, or `ProtocolError::MalformedMessage` if the
+/// bytes are truncated
+pub fn decode_fields(bytes: &[u8]) -> Result<(u8, Vec<Vec<u8>>), ProtocolError> {
+    let (&tag, mut rest) = bytes.split_first().ok_or(ProtocolError::MalformedMessage)?;
+
+    let mut fields = vec![];
+    while !rest.is_empty() {
+        if rest.len() < 4 {
+            return Err(ProtocolError::MalformedMessage);
+        }
+        let (length, tail) = rest.split_at(4);
+        let length = u32::from_be_bytes(length.try_into().unwrap()) as usize;
+        if tail.len() < length {
+            return Err(ProtocolError::MalformedMessage);
+        }
+        let (field, tail) = tail.split_at(length);
+        fields.push(field.to_vec());
+        rest = tail;
+    }
+
+    Ok((tag, fields))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use super::*;
+
+    impl Wire for String {
+        fn encode(&self) -> Vec<u8> {
+            encode_fields(0, &[self.as_bytes()])
+        }
+
+        fn decode(bytes: &[u8]) -> Result<Self, ProtocolError> {
+            let (_, fields) = decode_fields(bytes)?;
+            String::from_utf8(fields[0].clone()).map_err(|_| ProtocolError::MalformedMessage)
+        }
+    }
+
+    #[test]
+    fn fields_round_trip() {
+        let bytes = encode_fields(7, &[b"abc", b"", b"de"]);
+        let result = decode_fields(&bytes).unwrap();
+        let expected = (7, vec![b"abc".to_vec(), vec![], b"de".to_vec()]);
+        assert_eq!(result, expected);
+
+        assert_eq!(
+            decode_fields(&bytes[..bytes.len() - 1]),
+            Err(ProtocolError::MalformedMessage)
+        );
+    }
+
+    #[test]
+    fn channel_transport_disconnect() {
+        let (mut a, b) = channel_pair::<u8>();
+        assert_eq!(a.recv_timeout(Duration::from_millis(1)), Ok(None));
+        drop(b);
+        assert_eq!(a.recv(), Err(ProtocolError::Disconnected));
+    }
+
+    #[test]
+    fn tcp_transport_round_trip() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+
+        let server = thread::spawn(move || {
+            let mut transport = TcpTransport::<String>::accept(&listener).unwrap();
+            let message = transport.recv().unwrap();
+            transport.send(message.to_uppercase()).unwrap();
+        });
+
+        let mut client = TcpTransport::<String>::connect(address).unwrap();
+        assert_eq!(client.recv_timeout(Duration::from_millis(1)), Ok(None));
+        client.send("hello".to_string()).unwrap();
+        assert_eq!(client.recv().unwrap(), "HELLO");
+
+        server.join().unwrap();
+        assert_eq!(client.recv(), Err(ProtocolError::Disconnected));
+    }
+
+    #[test]
+    fn tcp_transport_rejects_oversized_frames() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+
+        let server = thread::spawn(move || {
+            let mut transport = TcpTransport::<String>::accept(&listener).unwrap();
+            transport.recv()
+        });
+
+        let mut client = TcpStream::connect(address).unwrap();
+        client.write_all(&u32::MAX.to_be_bytes()).unwrap();
+        assert_eq!(server.join().unwrap(), Err(ProtocolError::MalformedMessage));
+
+        let mut client = TcpTransport::<String>::new(client);
+        let message = "a".repeat(MAX_FRAME_SIZE);
+        assert_eq!(client.send(message), Err(ProtocolError::MalformedMessage));
+    }
+}
@@ -0,0 +1,49 @@
+use std::fmt;
+
+use crate::MIN_KEY_SIZE;
+
+/// Errors that can occur while generating or using RSA keys
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RsaError {
+    /// The modulus must have at least `MIN_KEY_SIZE` bits, and enough suitable primes of half
+    /// its size must exist
+    InvalidKeySize,
+
+    /// The public exponent must be odd and at least 3
+    InvalidExponent,
+
+    /// The primes must be distinct, odd and such that `e` is invertible modulo `lcm(p-1, q-1)`
+    InvalidPrimes,
+
+    /// The message or ciphertext representative must be smaller than the modulus
+    MessageOutOfRange,
+
+    /// The message is too long to be padded for the modulus
+    MessageTooLong,
+
+    /// The decrypted block is not correctly padded
+    InvalidPadding,
+
+    /// The serialized key is truncated or has the wrong number of components
+    InvalidKeyEncoding,
+}
+
+impl std::error::Error for RsaError {}
+
+impl fmt::Display for RsaError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RsaError::InvalidKeySize => write!(
+                f,
+                "the modulus is too small: it must have at least {} bits and enough suitable primes",
+                MIN_KEY_SIZE
+            ),
+            RsaError::InvalidExponent => "the public exponent must be odd and at least 3".fmt(f),
+            RsaError::InvalidPrimes => "the primes do not form a valid RSA key".fmt(f),
+            RsaError::MessageOutOfRange => "the message must be smaller than the modulus".fmt(f),
+            RsaError::MessageTooLong => "the message is too long for the modulus".fmt(f),
+            RsaError::InvalidPadding => "the decrypted block is not correctly padded".fmt(f),
+            RsaError::InvalidKeyEncoding => "the serialized key is malformed".fmt(f),
+        }
+    }
+}
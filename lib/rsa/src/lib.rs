@@ -0,0 +1,494 @@
+mod error;
+pub use error::*;
+
+mod oaep;
+pub use oaep::*;
+
+mod pkcs1;
+pub use pkcs1::*;
+
+mod pss;
+pub use pss::*;
+
+use encoding::Encode;
+use primitives::{random_prime, BigUint, HashFunction, Rng};
+
+/// The public exponent used when none is specified, 2^16 + 1
+pub static DEFAULT_PUBLIC_EXPONENT: u64 = 65537;
+
+/// The smallest supported modulus size in bits. It is the smallest size for which two distinct
+/// primes of half the size exist for the usual small exponents
+pub static MIN_KEY_SIZE: usize = 12;
+
+/// The number of primes `RsaPrivateKey::generate` draws for each factor before giving up. It
+/// only runs out when the key is so small that few primes of half its size suit `e`
+const MAX_PRIME_ATTEMPTS: usize = 1000;
+
+/// An RSA public key `(n, e)`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RsaPublicKey {
+    n: BigUint,
+    e: BigUint,
+}
+
+impl RsaPublicKey {
+    /// Creates a new `RsaPublicKey`
+    ///
+    /// # Returns
+    /// A `Result` wrapping the key or `RsaError::InvalidExponent` if `e` is even, smaller than 3
+    /// or not smaller than `n`
+    pub fn new(n: BigUint, e: BigUint) -> Result<RsaPublicKey, RsaError> {
+        if e.is_even() || e < BigUint::from(3u64) || e >= n {
+            return Err(RsaError::InvalidExponent);
+        }
+        Ok(RsaPublicKey { n, e })
+    }
+
+    pub fn n(&self) -> &BigUint {
+        &self.n
+    }
+
+    pub fn e(&self) -> &BigUint {
+        &self.e
+    }
+
+    /// Returns the size of the modulus in bytes
+    pub fn size(&self) -> usize {
+        self.n.bits().div_ceil(8)
+    }
+
+    /// Textbook RSA encryption `c = m^e mod n`
+    ///
+    /// # Returns
+    /// A `Result` wrapping the ciphertext or `RsaError::MessageOutOfRange` if `m >= n`
+    pub fn encrypt(&self, m: &BigUint) -> Result<BigUint, RsaError> {
+        if *m >= self.n {
+            return Err(RsaError::MessageOutOfRange);
+        }
+        Ok(m.modpow(&self.e, &self.n))
+    }
+
+    /// Serializes the key as the length-prefixed big endian integers `n` and `e`
+    pub fn to_bytes(&self) -> Vec<u8> {
+        encode_integers(&[&self.n, &self.e])
+    }
+
+    /// Deserializes a key produced by `to_bytes`
+    pub fn from_bytes(bytes: &[u8]) -> Result<RsaPublicKey, RsaError> {
+        match decode_integers(bytes)?.as_slice() {
+            [n, e] => RsaPublicKey::new(n.clone(), e.clone()),
+            _ => Err(RsaError::InvalidKeyEncoding),
+        }
+    }
+}
+
+impl Encode for RsaPublicKey {
+    fn to_hex(&self) -> String {
+        self.to_bytes().to_hex()
+    }
+
+    fn to_base64(&self) -> String {
+        self.to_bytes().to_base64()
+    }
+}
+
+/// An RSA private key, with the CRT parameters used to speed up decryption
+///
+/// # Examples
+/// ```
+/// use primitives::{BigUint, OsRng};
+/// use rsa::RsaPrivateKey;
+///
+/// let key = RsaPrivateKey::generate(&mut OsRng, 512, &BigUint::from(3u64)).unwrap();
+/// let m = BigUint::from(42u64);
+/// let c = key.public_key().encrypt(&m).unwrap();
+///
+/// assert_eq!(key.decrypt(&c).unwrap(), m);
+/// assert_eq!(key.decrypt_crt(&c).unwrap(), m);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RsaPrivateKey {
+    public_key: RsaPublicKey,
+    d: BigUint,
+    p: BigUint,
+    q: BigUint,
+    dp: BigUint,
+    dq: BigUint,
+    qinv: BigUint,
+}
+
+impl RsaPrivateKey {
+    /// Generates a key with a modulus of exactly `bits` bits, made of two random primes `p` and
+    /// `q` such that `e` is invertible modulo `p - 1` and `q - 1`
+    ///
+    /// # Args
+    /// `rng`: the source of the primes
+    ///
+    /// `bits`: the size of the modulus
+    ///
+    /// `e`: the public exponent, e.g. 3 or `DEFAULT_PUBLIC_EXPONENT`
+    ///
+    /// # Returns
+    /// A `Result` wrapping the key, or an `RsaError` if the size or the exponent is invalid.
+    /// `RsaError::InvalidKeySize` is also returned when no suitable primes are found after
+    /// `MAX_PRIME_ATTEMPTS` draws, which can only happen for tiny keys
+    pub fn generate<R: Rng>(
+        rng: &mut R,
+        bits: usize,
+        e: &BigUint,
+    ) -> Result<RsaPrivateKey, RsaError> {
+        if bits < MIN_KEY_SIZE {
+            return Err(RsaError::InvalidKeySize);
+        }
+        if e.is_even() || *e < BigUint::from(3u64) || e.bits() >= bits {
+            return Err(RsaError::InvalidExponent);
+        }
+
+        let mut prime = |bits: usize, other: Option<&BigUint>| {
+            (0..MAX_PRIME_ATTEMPTS)
+                .map(|_| random_prime(rng, bits))
+                .find(|p| Some(p) != other && (p - 1u64).gcd(e).is_one())
+                .ok_or(RsaError::InvalidKeySize)
+        };
+
+        let p = prime(bits / 2, None)?;
+        let q = prime(bits - bits / 2, Some(&p))?;
+        RsaPrivateKey::from_primes(p, q, e.clone())
+    }
+
+    /// Creates a key from its primes and public exponent. The private exponent is
+    /// `d = e^-1 mod lcm(p - 1, q - 1)`
+    ///
+    /// # Returns
+    /// A `Result` wrapping the key or an `RsaError` if the parameters are invalid
+    pub fn from_primes(p: BigUint, q: BigUint, e: BigUint) -> Result<RsaPrivateKey, RsaError> {
+        let two = BigUint::from(2u64);
+        if p == q || p <= two || q <= two || p.is_even() || q.is_even() {
+            return Err(RsaError::InvalidPrimes);
+        }
+
+        let n = &p * &q;
+        if n.bits() < MIN_KEY_SIZE {
+            return Err(RsaError::InvalidKeySize);
+        }
+        let public_key = RsaPublicKey::new(n, e)?;
+
+        let p1 = &p - 1u64;
+        let q1 = &q - 1u64;
+        let d = public_key
+            .e
+            .modinv(&p1.lcm(&q1))
+            .ok_or(RsaError::InvalidPrimes)?;
+        let qinv = q.modinv(&p).ok_or(RsaError::InvalidPrimes)?;
+
+        Ok(RsaPrivateKey {
+            dp: &d % &p1,
+            dq: &d % &q1,
+            public_key,
+            d,
+            p,
+            q,
+            qinv,
+        })
+    }
+
+    pub fn public_key(&self) -> &RsaPublicKey {
+        &self.public_key
+    }
+
+    pub fn d(&self) -> &BigUint {
+        &self.d
+    }
+
+    pub fn p(&self) -> &BigUint {
+        &self.p
+    }
+
+    pub fn q(&self) -> &BigUint {
+        &self.q
+    }
+
+    /// Returns the size of the modulus in bytes
+    pub fn size(&self) -> usize {
+        self.public_key.size()
+    }
+
+    /// Textbook RSA decryption `m = c^d mod n`
+    ///
+    /// # Returns
+    /// A `Result` wrapping the message or `RsaError::MessageOutOfRange` if `c >= n`
+    pub fn decrypt(&self, c: &BigUint) -> Result<BigUint, RsaError> {
+        if *c >= self.public_key.n {
+            return Err(RsaError::MessageOutOfRange);
+        }
+        Ok(c.modpow(&self.d, &self.public_key.n))
+    }
+
+    /// Textbook RSA decryption computed modulo `p` and `q` and recombined with Garner's
+    /// formula, about 3 times faster than `decrypt`
+    ///
+    /// # Returns
+    /// A `Result` wrapping the message or `RsaError::MessageOutOfRange` if `c >= n`
+    pub fn decrypt_crt(&self, c: &BigUint) -> Result<BigUint, RsaError> {
+        if *c >= self.public_key.n {
+            return Err(RsaError::MessageOutOfRange);
+        }
+
+        let m1 = (c % &self.p).modpow(&self.dp, &self.p);
+        let m2 = (c % &self.q).modpow(&self.dq, &self.q);
+        // h = qinv * (m1 - m2) mod p
+        let h = (&self.qinv * ((m1 + &self.p - &m2 % &self.p) % &self.p)) % &self.p;
+        Ok(m2 + h * &self.q)
+    }
+
+    /// Serializes the key as the length-prefixed big endian integers `n`, `e`, `d`, `p` and `q`
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let RsaPublicKey { n, e } = &self.public_key;
+        encode_integers(&[n, e, &self.d, &self.p, &self.q])
+    }
+
+    /// Deserializes a key produced by `to_bytes`. The CRT parameters are recomputed
+    pub fn from_bytes(bytes: &[u8]) -> Result<RsaPrivateKey, RsaError> {
+        match decode_integers(bytes)?.as_slice() {
+            [n, e, d, p, q] => {
+                let key = RsaPrivateKey::from_primes(p.clone(), q.clone(), e.clone())?;
+                if key.public_key.n != *n || key.d != *d {
+                    return Err(RsaError::InvalidKeyEncoding);
+                }
+                Ok(key)
+            }
+            _ => Err(RsaError::InvalidKeyEncoding),
+        }
+    }
+}
+
+impl Encode for RsaPrivateKey {
+    fn to_hex(&self) -> String {
+        self.to_bytes().to_hex()
+    }
+
+    fn to_base64(&self) -> String {
+        self.to_bytes().to_base64()
+    }
+}
+
+/// Converts an integer to a big endian byte string of exactly `length` bytes (I2OSP in RFC 8017)
+///
+/// # Returns
+/// A `Result` wrapping the bytes or `RsaError::MessageOutOfRange` if the integer does not fit
+pub fn i2osp(x: &BigUint, length: usize) -> Result<Vec<u8>, RsaError> {
+    x.to_be_bytes_padded(length)
+        .ok_or(RsaError::MessageOutOfRange)
+}
+
+/// Converts a big endian byte string to an integer (OS2IP in RFC 8017)
+pub fn os2ip(bytes: &[u8]) -> BigUint {
+    BigUint::from_be_bytes(bytes)
+}
+
+/// The MGF1 mask generation function (RFC 8017 B.2.1): the concatenation of
+/// `H(seed || counter)` for successive 4 bytes big endian counters, truncated to `length` bytes
+pub fn mgf1<H: HashFunction>(seed: &[u8], length: usize) -> Vec<u8> {
+    let mut mask = Vec::with_capacity(length + H::OUTPUT_SIZE);
+    let mut counter = 0u32;
+    while mask.len() < length {
+        let mut hash = H::default();
+        hash.update(seed);
+        hash.update(&counter.to_be_bytes());
+        mask.extend(hash.finalize());
+        counter += 1;
+    }
+    mask.truncate(length);
+    mask
+}
+
+fn encode_integers(integers: &[&BigUint]) -> Vec<u8> {
+    let mut bytes = vec![];
+    for n in integers {
+        let n = n.to_be_bytes();
+        bytes.extend((n.len() as u32).to_be_bytes());
+        bytes.extend(n);
+    }
+    bytes
+}
+
+fn decode_integers(mut bytes: &[u8]) -> Result<Vec<BigUint>, RsaError> {
+    let mut integers = vec![];
+    while !bytes.is_empty() {
+        if bytes.len() < 4 {
+            return Err(RsaError::InvalidKeyEncoding);
+        }
+        let (length, rest) = bytes.split_at(4);
+        let length = u32::from_be_bytes(length.try_into().unwrap()) as usize;
+        if rest.len() < length {
+            return Err(RsaError::InvalidKeyEncoding);
+        }
+        let (n, rest) = rest.split_at(length);
+        integers.push(BigUint::from_be_bytes(n));
+        bytes = rest;
+    }
+    Ok(integers)
+}
+
+/// Keys shared by the tests of the padding schemes
+#[cfg(test)]
+pub(crate) mod test_keys {
+    use encoding::Decode;
+    use primitives::BigUint;
+
+    use crate::{RsaPrivateKey, DEFAULT_PUBLIC_EXPONENT};
+
+    /// A 1024 bit key generated with the Python cryptography package, against which the
+    /// reference signatures and ciphertexts were computed
+    pub(crate) fn reference_key() -> RsaPrivateKey {
+        let p = BigUint::from_hex(concat!(
+            "e32c0f0deca1b5b86a516c142a4bf61a8c790f55dd9d662168ea9c36649601535102fc4119f6646c31",
+            "8f3d2540caf0776f35548d0ff6b901bbcdf512070abe9b"
+        ))
+        .unwrap();
+        let q = BigUint::from_hex(concat!(
+            "de24f795cecf1ba25b0028faa620e2a80ea6ecbc393cc1376cfd6f13d7e3ab9e06b0467c65685826d0",
+            "f2d166a25ea75fca424fa5559663d038cb2d39bd5d9da7"
+        ))
+        .unwrap();
+        RsaPrivateKey::from_primes(p, q, BigUint::from(DEFAULT_PUBLIC_EXPONENT)).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use encoding::Decode;
+    use primitives::Mt19937;
+
+    use super::*;
+
+    #[test]
+    fn rsa_textbook_example() {
+        let p = BigUint::from(61u64);
+        let q = BigUint::from(53u64);
+        let key = RsaPrivateKey::from_primes(p, q, BigUint::from(17u64)).unwrap();
+
+        assert_eq!(*key.public_key().n(), BigUint::from(3233u64));
+        assert_eq!(*key.d(), BigUint::from(413u64));
+
+        let m = BigUint::from(65u64);
+        let c = key.public_key().encrypt(&m).unwrap();
+        assert_eq!(c, BigUint::from(2790u64));
+        assert_eq!(key.decrypt(&c).unwrap(), m);
+        assert_eq!(key.decrypt_crt(&c).unwrap(), m);
+    }
+
+    #[test]
+    fn rsa_generate() {
+        let mut rng = Mt19937::new(1);
+        for (bits, e) in [(512, 3), (1024, DEFAULT_PUBLIC_EXPONENT), (777, 65)] {
+            let key = RsaPrivateKey::generate(&mut rng, bits, &BigUint::from(e)).unwrap();
+            assert_eq!(key.public_key().n().bits(), bits);
+            assert_eq!(*key.public_key().e(), BigUint::from(e));
+
+            let m = BigUint::random_below(&mut rng, key.public_key().n());
+            let c = key.public_key().encrypt(&m).unwrap();
+            assert_eq!(key.decrypt(&c).unwrap(), m);
+            assert_eq!(key.decrypt_crt(&c).unwrap(), m);
+        }
+    }
+
+    #[test]
+    fn rsa_generate_minimum_size() {
+        let mut rng = Mt19937::new(1);
+        for e in [3u64, 5, 7, 13] {
+            let key = RsaPrivateKey::generate(&mut rng, MIN_KEY_SIZE, &BigUint::from(e)).unwrap();
+            assert_eq!(key.public_key().n().bits(), MIN_KEY_SIZE);
+
+            let m = BigUint::from(42u64);
+            let c = key.public_key().encrypt(&m).unwrap();
+            assert_eq!(key.decrypt_crt(&c).unwrap(), m);
+        }
+
+        // 6 bit primes with the top two bits set are 53, 59 and 61, and e = 3 * 13 is not
+        // invertible modulo 52 and 60
+        let result = RsaPrivateKey::generate(&mut rng, MIN_KEY_SIZE, &BigUint::from(39u64));
+        assert_eq!(result, Err(RsaError::InvalidKeySize));
+    }
+
+    #[test]
+    fn rsa_invalid_parameters() {
+        let mut rng = Mt19937::new(1);
+        let e = BigUint::from(DEFAULT_PUBLIC_EXPONENT);
+        assert_eq!(
+            RsaPrivateKey::generate(&mut rng, MIN_KEY_SIZE - 1, &e),
+            Err(RsaError::InvalidKeySize)
+        );
+        assert_eq!(
+            RsaPrivateKey::generate(&mut rng, 512, &BigUint::from(4u64)),
+            Err(RsaError::InvalidExponent)
+        );
+        assert_eq!(
+            RsaPrivateKey::generate(&mut rng, 17, &BigUint::from(1u64 << 17 | 1)),
+            Err(RsaError::InvalidExponent)
+        );
+
+        // 3 divides 7 - 1
+        let result = RsaPrivateKey::from_primes(
+            BigUint::from(7u64),
+            BigUint::from(65537u64),
+            BigUint::from(3u64),
+        );
+        assert_eq!(result, Err(RsaError::InvalidPrimes));
+
+        let key = RsaPrivateKey::generate(&mut rng, 256, &e).unwrap();
+        let n = key.public_key().n();
+        assert_eq!(
+            key.public_key().encrypt(n),
+            Err(RsaError::MessageOutOfRange)
+        );
+        assert_eq!(key.decrypt_crt(n), Err(RsaError::MessageOutOfRange));
+    }
+
+    #[test]
+    fn mgf1_sha1() {
+        let seed = Vec::from_hex("aafd12f659cae63489b479e5076ddec2f06cb58f").unwrap();
+        let result = mgf1::<primitives::Sha1>(&seed, 107);
+        let expected = Vec::from_hex(concat!(
+            "06e1deb2369aa5a5c707d82c8e4e93248ac783dee0b2c04626f5aff93edcfb25c9c2b3ff8ae10e839a",
+            "2ddb4cdcfe4ff47728b4a1b7c1362baad29ab48d2869d5024121435811591be392f982fb3e87d095ae",
+            "b40448db972f3ac14eaff49c8c3b7cfc951a51ecd1dde61264"
+        ))
+        .unwrap();
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn rsa_key_export() {
+        let key = RsaPrivateKey::from_primes(
+            BigUint::from(61u64),
+            BigUint::from(53u64),
+            BigUint::from(17u64),
+        )
+        .unwrap();
+
+        let public_key = key.public_key();
+        assert_eq!(public_key.to_hex(), "000000020ca10000000111");
+        assert_eq!(
+            RsaPublicKey::from_bytes(&Vec::from_base64(&public_key.to_base64()).unwrap()),
+            Ok(public_key.clone())
+        );
+
+        let key = RsaPrivateKey::generate(&mut Mt19937::new(2), 512, &BigUint::from(3u64)).unwrap();
+        assert_eq!(
+            RsaPrivateKey::from_bytes(&Vec::from_hex(&key.to_hex()).unwrap()),
+            Ok(key.clone())
+        );
+
+        let mut bytes = key.to_bytes();
+        bytes.pop();
+        assert_eq!(
+            RsaPrivateKey::from_bytes(&bytes),
+            Err(RsaError::InvalidKeyEncoding)
+        );
+        assert_eq!(
+            RsaPublicKey::from_bytes(&key.to_bytes()),
+            Err(RsaError::InvalidKeyEncoding)
+        );
+    }
+}
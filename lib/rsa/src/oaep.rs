@@ -0,0 +1,215 @@
+use primitives::{ct_eq, ct_eq_u8, ct_is_zero_u8, xor, HashFunction, Rng};
+
+use crate::{i2osp, mgf1, os2ip, RsaError, RsaPrivateKey, RsaPublicKey};
+
+/// Encodes a message into an OAEP block (RFC 8017 7.1.1):
+/// `00 || maskedSeed || maskedDB` with `DB = H(L) || 00...00 || 01 || M`
+///
+/// # Args
+/// `message`: the message, at most `k - 2 * hLen - 2` bytes long
+///
+/// `label`: the label `L` associated with the message, usually empty
+///
+/// `k`: the size of the modulus in bytes
+///
+/// `rng`: the source of the seed
+///
+/// # Returns
+/// A `Result` wrapping the `k` bytes block or `RsaError::MessageTooLong`
+pub fn pad_oaep<H: HashFunction, R: Rng>(
+    message: &[u8],
+    label: &[u8],
+    k: usize,
+    rng: &mut R,
+) -> Result<Vec<u8>, RsaError> {
+    let h_len = H::OUTPUT_SIZE;
+    if message.len() + 2 * h_len + 2 > k {
+        return Err(RsaError::MessageTooLong);
+    }
+
+    let mut db = H::digest(label);
+    db.resize(k - message.len() - h_len - 2, 0x00);
+    db.push(0x01);
+    db.extend(message);
+
+    let mut seed = vec![0; h_len];
+    rng.fill_bytes(&mut seed);
+
+    let masked_db = xor(&db, &mgf1::<H>(&seed, db.len()));
+    let masked_seed = xor(&seed, &mgf1::<H>(&masked_db, h_len));
+
+    let mut block = vec![0x00];
+    block.extend(masked_seed);
+    block.extend(masked_db);
+    Ok(block)
+}
+
+/// Decodes an OAEP block. All the checks are performed in constant time and every failure
+/// returns the same error, so that the decoder is not a padding oracle (Manger's attack)
+///
+/// # Returns
+/// A `Result` wrapping the message or `RsaError::InvalidPadding`
+pub fn unpad_oaep<H: HashFunction>(block: &[u8], label: &[u8]) -> Result<Vec<u8>, RsaError> {
+    let h_len = H::OUTPUT_SIZE;
+    if block.len() < 2 * h_len + 2 {
+        return Err(RsaError::InvalidPadding);
+    }
+
+    let (masked_seed, masked_db) = block[1..].split_at(h_len);
+    let seed = xor(masked_seed, &mgf1::<H>(masked_db, h_len));
+    let db = xor(masked_db, &mgf1::<H>(&seed, masked_db.len()));
+
+    let mut valid =
+        ct_is_zero_u8(block[0]) & ((ct_eq(&db[..h_len], &H::digest(label)) as u8) * 0xff);
+
+    // find the 01 separator without branching on the padding
+    let mut found = 0u8;
+    let mut separator = 0;
+    for (i, &b) in db[h_len..].iter().enumerate() {
+        let is_one = ct_eq_u8(b, 0x01);
+        let first_one = is_one & !found;
+        separator |= (first_one & 1) as usize * i;
+        valid &= found | ct_is_zero_u8(b) | is_one;
+        found |= is_one;
+    }
+    valid &= found;
+
+    if valid != 0xff {
+        return Err(RsaError::InvalidPadding);
+    }
+    Ok(db[h_len + separator + 1..].to_vec())
+}
+
+/// Encrypts a message with RSAES-OAEP
+///
+/// # Args
+/// `key`: the public key of the recipient
+///
+/// `message`: the message, at most `k - 2 * hLen - 2` bytes long
+///
+/// `label`: the label `L` associated with the message, usually empty
+///
+/// `rng`: the source of the seed
+///
+/// # Returns
+/// A `Result` wrapping the `k` bytes ciphertext or `RsaError::MessageTooLong`
+///
+/// # Examples
+/// ```
+/// use primitives::{BigUint, OsRng, Sha256};
+/// use rsa::{oaep_decrypt, oaep_encrypt, RsaPrivateKey, DEFAULT_PUBLIC_EXPONENT};
+///
+/// let e = BigUint::from(DEFAULT_PUBLIC_EXPONENT);
+/// let key = RsaPrivateKey::generate(&mut OsRng, 1024, &e).unwrap();
+///
+/// let ciphertext = oaep_encrypt::<Sha256, _>(key.public_key(), b"hi", b"", &mut OsRng).unwrap();
+/// assert_eq!(oaep_decrypt::<Sha256>(&key, &ciphertext, b"").unwrap(), b"hi");
+/// ```
+pub fn oaep_encrypt<H: HashFunction, R: Rng>(
+    key: &RsaPublicKey,
+    message: &[u8],
+    label: &[u8],
+    rng: &mut R,
+) -> Result<Vec<u8>, RsaError> {
+    let block = pad_oaep::<H, R>(message, label, key.size(), rng)?;
+    i2osp(&key.encrypt(&os2ip(&block))?, key.size())
+}
+
+/// Decrypts a RSAES-OAEP ciphertext
+///
+/// # Returns
+/// A `Result` wrapping the message, `RsaError::MessageOutOfRange` if the ciphertext is not
+/// smaller than the modulus or `RsaError::InvalidPadding`
+pub fn oaep_decrypt<H: HashFunction>(
+    key: &RsaPrivateKey,
+    ciphertext: &[u8],
+    label: &[u8],
+) -> Result<Vec<u8>, RsaError> {
+    let block = i2osp(&key.decrypt_crt(&os2ip(ciphertext))?, key.size())?;
+    unpad_oaep::<H>(&block, label)
+}
+
+#[cfg(test)]
+mod tests {
+    use encoding::Decode;
+    use primitives::{BigUint, Mt19937, Sha1, Sha256};
+
+    use crate::test_keys::reference_key;
+    use crate::{pkcs1_v15_encrypt, DEFAULT_PUBLIC_EXPONENT};
+
+    use super::*;
+
+    #[test]
+    fn oaep_reference_ciphertexts() {
+        let key = reference_key();
+        let sha1_ciphertext = Vec::from_hex(concat!(
+            "6efb4709337737b0fbf03c88de460764daacf854c7ca735f5287ad282f34b2ee2f117dff2ee1b67d64",
+            "73d919572d51faed73b45f93b9bff6e8ba9ce407ee08fbf480aeb3c2d16361a8f84749c1f729ecca52",
+            "6c6cb936321a4bcabfd7c8e39faea1f42e1fd589562dcb6ca251434942df0ca1fb96042ef5298df7ac",
+            "a79478c64b"
+        ))
+        .unwrap();
+        let sha256_ciphertext = Vec::from_hex(concat!(
+            "ac5a3cba9bccf2d6356a4872aca8aa1368a155024f088b4c5a00a55b47bb1d1c348ca1f8b685cf040b",
+            "91d9c4507b3bff3ca1fb4fd5f001a7632555d258715890e1023165b081937c5ee129b3e068cf9870a0",
+            "bfe27578015934590d8a4aa2ffb34f0ed04a93bac6803f979a7842c804981da5910882dd490b163063",
+            "28a1d58bd5"
+        ))
+        .unwrap();
+
+        let result = oaep_decrypt::<Sha1>(&key, &sha1_ciphertext, b"");
+        assert_eq!(result.unwrap(), b"OAEP test message");
+        let result = oaep_decrypt::<Sha256>(&key, &sha256_ciphertext, b"label");
+        assert_eq!(result.unwrap(), b"OAEP test message");
+
+        let result = oaep_decrypt::<Sha256>(&key, &sha256_ciphertext, b"");
+        assert_eq!(result, Err(RsaError::InvalidPadding));
+        let result = oaep_decrypt::<Sha1>(&key, &sha256_ciphertext, b"label");
+        assert_eq!(result, Err(RsaError::InvalidPadding));
+    }
+
+    #[test]
+    fn oaep_round_trip() {
+        let mut rng = Mt19937::new(1);
+        let e = BigUint::from(DEFAULT_PUBLIC_EXPONENT);
+        let key = RsaPrivateKey::generate(&mut rng, 1024, &e).unwrap();
+
+        // k - 2 * hLen - 2 = 62 bytes with SHA-256
+        for message in [vec![], vec![0x00; 3], vec![0x01; 62]] {
+            let ciphertext =
+                oaep_encrypt::<Sha256, _>(key.public_key(), &message, b"", &mut rng).unwrap();
+            assert_eq!(
+                oaep_decrypt::<Sha256>(&key, &ciphertext, b"").unwrap(),
+                message
+            );
+        }
+
+        let result = oaep_encrypt::<Sha256, _>(key.public_key(), &[0; 63], b"", &mut rng);
+        assert_eq!(result, Err(RsaError::MessageTooLong));
+    }
+
+    #[test]
+    fn oaep_rejects_pkcs1_v15_blocks() {
+        let mut rng = Mt19937::new(2);
+        let e = BigUint::from(DEFAULT_PUBLIC_EXPONENT);
+        let key = RsaPrivateKey::generate(&mut rng, 1024, &e).unwrap();
+
+        // the blocks accepted by a "00 02" oracle are rejected by OAEP
+        let ciphertext = pkcs1_v15_encrypt(key.public_key(), b"message", &mut rng).unwrap();
+        assert_eq!(
+            oaep_decrypt::<Sha1>(&key, &ciphertext, b""),
+            Err(RsaError::InvalidPadding)
+        );
+
+        // a corrupted block is rejected wherever the corruption is
+        let block = pad_oaep::<Sha1, _>(b"message", b"", 128, &mut rng).unwrap();
+        for i in [0, 1, 30, 127] {
+            let mut corrupted = block.clone();
+            corrupted[i] ^= 0x80;
+            assert_eq!(
+                unpad_oaep::<Sha1>(&corrupted, b""),
+                Err(RsaError::InvalidPadding)
+            );
+        }
+    }
+}
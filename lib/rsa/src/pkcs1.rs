@@ -0,0 +1,550 @@
+use primitives::{ct_eq, ct_eq_u8, ct_is_zero_u8, HashFunction, Rng, Sha1, Sha256};
+
+use crate::{i2osp, os2ip, RsaError, RsaPrivateKey, RsaPublicKey};
+
+/// The minimum number of padding bytes in a PKCS#1 v1.5 block
+const MIN_PADDING_SIZE: usize = 8;
+
+/// Hash functions with an ASN.1 DigestInfo encoding for PKCS#1 v1.5 signatures
+pub trait DigestInfo: HashFunction {
+    /// The DER encoding of the DigestInfo structure up to the digest itself
+    const DIGEST_INFO_PREFIX: &'static [u8];
+}
+
+impl DigestInfo for Sha1 {
+    const DIGEST_INFO_PREFIX: &'static [u8] = &[
+        0x30, 0x21, 0x30, 0x09, 0x06, 0x05, 0x2b, 0x0e, 0x03, 0x02, 0x1a, 0x05, 0x00, 0x04, 0x14,
+    ];
+}
+
+impl DigestInfo for Sha256 {
+    const DIGEST_INFO_PREFIX: &'static [u8] = &[
+        0x30, 0x31, 0x30, 0x0d, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x01,
+        0x05, 0x00, 0x04, 0x20,
+    ];
+}
+
+/// How thoroughly a PKCS#1 v1.5 block is checked. Only `Strict` is secure, the other levels
+/// reproduce parsers that were broken in the wild
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pkcs1Strictness {
+    /// Every byte of the block is checked
+    Strict,
+
+    /// The block is parsed from the left and the bytes after the digest of a signature are
+    /// ignored, which enables Bleichenbacher's e=3 signature forgery. For encryption blocks the
+    /// message extends to the end of the block, so this is the same as `Strict`
+    SkipTrailingGarbage,
+
+    /// Only the leading `00 01` or `00 02` is checked. The padding bytes, their length and, for
+    /// signatures, the bytes after the digest are ignored. An encryption block with no
+    /// separator decodes to an empty message. This is the padding oracle of Bleichenbacher's
+    /// 1998 attack
+    HeaderOnly,
+}
+
+/// Pads a message into a PKCS#1 v1.5 encryption block (type 2):
+/// `00 02 || PS || 00 || M`, where PS is at least 8 random non-zero bytes
+///
+/// # Args
+/// `message`: the message, at most `k - 11` bytes long
+///
+/// `k`: the size of the modulus in bytes
+///
+/// `rng`: the source of the padding bytes
+///
+/// # Returns
+/// A `Result` wrapping the `k` bytes block or `RsaError::MessageTooLong`
+pub fn pad_pkcs1_v15_encryption<R: Rng>(
+    message: &[u8],
+    k: usize,
+    rng: &mut R,
+) -> Result<Vec<u8>, RsaError> {
+    if message.len() + MIN_PADDING_SIZE + 3 > k {
+        return Err(RsaError::MessageTooLong);
+    }
+
+    let mut padding = vec![0; k - message.len() - 3];
+    for byte in padding.iter_mut() {
+        while *byte == 0 {
+            let mut random = [0];
+            rng.fill_bytes(&mut random);
+            *byte = random[0];
+        }
+    }
+
+    let mut block = vec![0x00, 0x02];
+    block.extend(padding);
+    block.push(0x00);
+    block.extend(message);
+    Ok(block)
+}
+
+/// Extracts the message from a PKCS#1 v1.5 encryption block. With `Strict`, all the checks
+/// are performed in constant time and every failure returns the same error; the other levels
+/// exit at the first mismatch, like the parsers they reproduce
+///
+/// # Args
+/// `block`: the decrypted block, including its leading zero byte
+///
+/// `strictness`: how thoroughly the padding is checked
+///
+/// # Returns
+/// A `Result` wrapping the message or `RsaError::InvalidPadding`
+///
+/// # Examples
+/// ```
+/// use rsa::{unpad_pkcs1_v15_encryption, Pkcs1Strictness, RsaError};
+///
+/// let block = [0, 2, 1, 0, b'h', b'i'];
+/// let result = unpad_pkcs1_v15_encryption(&block, Pkcs1Strictness::Strict);
+/// assert_eq!(result, Err(RsaError::InvalidPadding));
+///
+/// let result = unpad_pkcs1_v15_encryption(&block, Pkcs1Strictness::HeaderOnly);
+/// assert_eq!(result, Ok(b"hi".to_vec()));
+/// ```
+pub fn unpad_pkcs1_v15_encryption(
+    block: &[u8],
+    strictness: Pkcs1Strictness,
+) -> Result<Vec<u8>, RsaError> {
+    if strictness == Pkcs1Strictness::Strict {
+        return unpad_pkcs1_v15_encryption_strict(block);
+    }
+
+    if block.len() < 2 || block[0] != 0x00 || block[1] != 0x02 {
+        return Err(RsaError::InvalidPadding);
+    }
+
+    let separator = block[2..].iter().position(|&b| b == 0x00).map(|i| i + 2);
+    match (strictness, separator) {
+        (Pkcs1Strictness::HeaderOnly, None) => Ok(vec![]),
+        (Pkcs1Strictness::HeaderOnly, Some(i)) => Ok(block[i + 1..].to_vec()),
+        (_, Some(i)) if i >= 2 + MIN_PADDING_SIZE => Ok(block[i + 1..].to_vec()),
+        _ => Err(RsaError::InvalidPadding),
+    }
+}
+
+/// Checks a PKCS#1 v1.5 encryption block without branching on its content, so that the
+/// decoder is not the padding oracle of Bleichenbacher's attack
+fn unpad_pkcs1_v15_encryption_strict(block: &[u8]) -> Result<Vec<u8>, RsaError> {
+    if block.len() < MIN_PADDING_SIZE + 3 {
+        return Err(RsaError::InvalidPadding);
+    }
+
+    let mut valid = ct_is_zero_u8(block[0]) & ct_eq_u8(block[1], 0x02);
+
+    // find the 00 separator without branching on the padding
+    let mut found = 0u8;
+    let mut separator = 0;
+    for (i, &b) in block[2..].iter().enumerate() {
+        let is_zero = ct_is_zero_u8(b);
+        let first_zero = is_zero & !found;
+        separator |= (first_zero & 1) as usize * i;
+        if i < MIN_PADDING_SIZE {
+            valid &= !is_zero;
+        }
+        found |= is_zero;
+    }
+    valid &= found;
+
+    if valid != 0xff {
+        return Err(RsaError::InvalidPadding);
+    }
+    Ok(block[2 + separator + 1..].to_vec())
+}
+
+/// Hashes a message and encodes its digest into a PKCS#1 v1.5 signature block (type 1):
+/// `00 01 || FF...FF || 00 || DigestInfo || H(M)`, with at least 8 `FF` bytes
+///
+/// # Returns
+/// A `Result` wrapping the `k` bytes block or `RsaError::MessageTooLong` if the modulus is too
+/// small for the digest
+pub fn pad_pkcs1_v15_signature<H: DigestInfo>(
+    message: &[u8],
+    k: usize,
+) -> Result<Vec<u8>, RsaError> {
+    let digest_info_size = H::DIGEST_INFO_PREFIX.len() + H::OUTPUT_SIZE;
+    if digest_info_size + MIN_PADDING_SIZE + 3 > k {
+        return Err(RsaError::MessageTooLong);
+    }
+
+    let mut block = vec![0x00, 0x01];
+    block.resize(k - digest_info_size - 1, 0xff);
+    block.push(0x00);
+    block.extend(H::DIGEST_INFO_PREFIX);
+    block.extend(H::digest(message));
+    Ok(block)
+}
+
+/// Checks that a PKCS#1 v1.5 signature block holds the digest of a message
+///
+/// # Args
+/// `block`: the block recovered from the signature, including its leading zero byte
+///
+/// `message`: the signed message
+///
+/// `strictness`: how thoroughly the block is checked
+pub fn check_pkcs1_v15_signature<H: DigestInfo>(
+    block: &[u8],
+    message: &[u8],
+    strictness: Pkcs1Strictness,
+) -> bool {
+    if strictness == Pkcs1Strictness::Strict {
+        return match pad_pkcs1_v15_signature::<H>(message, block.len()) {
+            Ok(expected) => ct_eq(block, &expected),
+            Err(_) => false,
+        };
+    }
+
+    if block.len() < 2 || block[0] != 0x00 || block[1] != 0x01 {
+        return false;
+    }
+
+    let padding_size = match strictness {
+        Pkcs1Strictness::HeaderOnly => block[2..].iter().position(|&b| b == 0x00),
+        _ => block[2..].iter().position(|&b| b != 0xff),
+    };
+    let Some(padding_size) = padding_size else {
+        return false;
+    };
+
+    let rest = &block[2 + padding_size..];
+    let mut expected = vec![0x00];
+    expected.extend(H::DIGEST_INFO_PREFIX);
+    expected.extend(H::digest(message));
+
+    rest.len() >= expected.len() && ct_eq(&rest[..expected.len()], &expected)
+}
+
+/// Encrypts a message with RSAES-PKCS1-v1_5
+///
+/// # Returns
+/// A `Result` wrapping the `k` bytes ciphertext or `RsaError::MessageTooLong` if the message
+/// is longer than `k - 11` bytes
+pub fn pkcs1_v15_encrypt<R: Rng>(
+    key: &RsaPublicKey,
+    message: &[u8],
+    rng: &mut R,
+) -> Result<Vec<u8>, RsaError> {
+    let block = pad_pkcs1_v15_encryption(message, key.size(), rng)?;
+    i2osp(&key.encrypt(&os2ip(&block))?, key.size())
+}
+
+/// Decrypts a RSAES-PKCS1-v1_5 ciphertext, checking the padding with the given strictness
+///
+/// # Returns
+/// A `Result` wrapping the message, `RsaError::MessageOutOfRange` if the ciphertext is not
+/// smaller than the modulus or `RsaError::InvalidPadding`
+///
+/// # Examples
+/// ```
+/// use primitives::{BigUint, OsRng};
+/// use rsa::{pkcs1_v15_decrypt, pkcs1_v15_encrypt, Pkcs1Strictness, RsaPrivateKey};
+///
+/// let key = RsaPrivateKey::generate(&mut OsRng, 512, &BigUint::from(3u64)).unwrap();
+/// let ciphertext = pkcs1_v15_encrypt(key.public_key(), b"kick it", &mut OsRng).unwrap();
+///
+/// let result = pkcs1_v15_decrypt(&key, &ciphertext, Pkcs1Strictness::Strict);
+/// assert_eq!(result.unwrap(), b"kick it");
+/// ```
+pub fn pkcs1_v15_decrypt(
+    key: &RsaPrivateKey,
+    ciphertext: &[u8],
+    strictness: Pkcs1Strictness,
+) -> Result<Vec<u8>, RsaError> {
+    let block = i2osp(&key.decrypt_crt(&os2ip(ciphertext))?, key.size())?;
+    unpad_pkcs1_v15_encryption(&block, strictness)
+}
+
+/// Signs a message with RSASSA-PKCS1-v1_5
+///
+/// # Returns
+/// A `Result` wrapping the `k` bytes signature or `RsaError::MessageTooLong` if the modulus is
+/// too small for the digest
+pub fn pkcs1_v15_sign<H: DigestInfo>(
+    key: &RsaPrivateKey,
+    message: &[u8],
+) -> Result<Vec<u8>, RsaError> {
+    let block = pad_pkcs1_v15_signature::<H>(message, key.size())?;
+    i2osp(&key.decrypt_crt(&os2ip(&block))?, key.size())
+}
+
+/// Verifies a RSASSA-PKCS1-v1_5 signature, checking the recovered block with the given
+/// strictness
+pub fn pkcs1_v15_verify<H: DigestInfo>(
+    key: &RsaPublicKey,
+    message: &[u8],
+    signature: &[u8],
+    strictness: Pkcs1Strictness,
+) -> bool {
+    let block = key
+        .encrypt(&os2ip(signature))
+        .and_then(|m| i2osp(&m, key.size()));
+
+    match block {
+        Ok(block) => check_pkcs1_v15_signature::<H>(&block, message, strictness),
+        Err(_) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use encoding::Decode;
+    use primitives::{BigUint, Mt19937};
+
+    use crate::test_keys::reference_key;
+
+    use super::*;
+
+    #[test]
+    fn pkcs1_v15_reference_signatures() {
+        let key = reference_key();
+        let message = b"PKCS #1 v1.5 test message";
+        let sha1_signature = Vec::from_hex(concat!(
+            "978b894808a32aa7bd54f2d7c119345b674000aac86de1f6703d79085a52569543202ff47bbdd042cd",
+            "2705eb4e26bc3ab671840cb0d257cfdeb4e9a8d514108b0c0ab84058e5d851aadc225639516137436a",
+            "42f2a8e25acaf36d608341be1d932fb78c0dae85c321afba92fc91479c9d07ce789f6490adb1aa013f",
+            "af5f6b71dd"
+        ))
+        .unwrap();
+        let sha256_signature = Vec::from_hex(concat!(
+            "a0cdd27ea62d786fff068c3d700fa9bdaa41b1df43b982dcc4936e45a6bb5f3010b3e97feada9655b6",
+            "90182b2f49d7ca25ed46cd8e410e95a3e60d54f95d049be0bf08c1597847c05f1b52d9ea1d6879c889",
+            "6218872a3c35edfa5a786065d6850a851f5fa18da704ecf9f08d94fa9ae3a9388d80ef8627d0b40639",
+            "d291f5dd0c"
+        ))
+        .unwrap();
+
+        assert_eq!(
+            pkcs1_v15_sign::<Sha1>(&key, message).unwrap(),
+            sha1_signature
+        );
+        assert_eq!(
+            pkcs1_v15_sign::<Sha256>(&key, message).unwrap(),
+            sha256_signature
+        );
+
+        let public_key = key.public_key();
+        let strict = Pkcs1Strictness::Strict;
+        assert!(pkcs1_v15_verify::<Sha1>(
+            public_key,
+            message,
+            &sha1_signature,
+            strict
+        ));
+        assert!(pkcs1_v15_verify::<Sha256>(
+            public_key,
+            message,
+            &sha256_signature,
+            strict
+        ));
+        assert!(!pkcs1_v15_verify::<Sha256>(
+            public_key,
+            message,
+            &sha1_signature,
+            strict
+        ));
+        assert!(!pkcs1_v15_verify::<Sha256>(
+            public_key,
+            b"other message",
+            &sha256_signature,
+            strict
+        ));
+    }
+
+    #[test]
+    fn pkcs1_v15_reference_decryption() {
+        let ciphertext = Vec::from_hex(concat!(
+            "8b461d45f16cb41d2201b0a8bece752fa1becf29a7e597a6e33c29478eaf97d05b99c3e3f58f7f43b8",
+            "fe0b1ffb6c8fb6706ee04afeaaa9ab51d46b008424d1cb9e9a3303ff722a2a711b080d0e567a3e8b71",
+            "83c3b3c4aeedd2add6c2b578485c11054dc51d648bf52799d1bf3b2f785f41980258c6380d5d8b9171",
+            "e956820840"
+        ))
+        .unwrap();
+
+        let result = pkcs1_v15_decrypt(&reference_key(), &ciphertext, Pkcs1Strictness::Strict);
+        assert_eq!(result.unwrap(), b"v1.5 encrypted");
+    }
+
+    #[test]
+    fn pkcs1_v15_encryption_round_trip() {
+        let mut rng = Mt19937::new(1);
+        let key = RsaPrivateKey::generate(&mut rng, 512, &BigUint::from(3u64)).unwrap();
+
+        for length in [0, 1, 53] {
+            let message = vec![0x42; length];
+            let ciphertext = pkcs1_v15_encrypt(key.public_key(), &message, &mut rng).unwrap();
+            assert_eq!(ciphertext.len(), 64);
+
+            let result = pkcs1_v15_decrypt(&key, &ciphertext, Pkcs1Strictness::Strict);
+            assert_eq!(result.unwrap(), message);
+        }
+
+        let result = pkcs1_v15_encrypt(key.public_key(), &[0; 54], &mut rng);
+        assert_eq!(result, Err(RsaError::MessageTooLong));
+    }
+
+    #[test]
+    fn pkcs1_v15_encryption_strictness() {
+        let strict = Pkcs1Strictness::Strict;
+        let lenient = Pkcs1Strictness::SkipTrailingGarbage;
+        let header_only = Pkcs1Strictness::HeaderOnly;
+
+        let mut block = vec![0x00, 0x02];
+        block.extend([0xaa; 8]);
+        block.extend([0x00, b'm']);
+        for strictness in [strict, lenient, header_only] {
+            assert_eq!(
+                unpad_pkcs1_v15_encryption(&block, strictness),
+                Ok(vec![b'm'])
+            );
+        }
+
+        // padding too short
+        let short = [0x00, 0x02, 0xaa, 0x00, b'm'];
+        // no separator
+        let unterminated = [
+            0x00, 0x02, 0xaa, 0xaa, 0xaa, 0xaa, 0xaa, 0xaa, 0xaa, 0xaa, 0xaa,
+        ];
+        for (block, expected) in [(&short[..], vec![b'm']), (&unterminated[..], vec![])] {
+            for strictness in [strict, lenient] {
+                let result = unpad_pkcs1_v15_encryption(block, strictness);
+                assert_eq!(result, Err(RsaError::InvalidPadding));
+            }
+            assert_eq!(unpad_pkcs1_v15_encryption(block, header_only), Ok(expected));
+        }
+
+        let wrong_type = [
+            0x00, 0x01, 0xaa, 0xaa, 0xaa, 0xaa, 0xaa, 0xaa, 0xaa, 0xaa, 0x00,
+        ];
+        assert_eq!(
+            unpad_pkcs1_v15_encryption(&wrong_type, header_only),
+            Err(RsaError::InvalidPadding)
+        );
+    }
+
+    #[test]
+    fn pkcs1_v15_strict_encryption_boundaries() {
+        let strict = Pkcs1Strictness::Strict;
+        let unpad = |padding: &[u8], message: &[u8]| {
+            let mut block = vec![0x00, 0x02];
+            block.extend(padding);
+            block.push(0x00);
+            block.extend(message);
+            unpad_pkcs1_v15_encryption(&block, strict)
+        };
+
+        assert_eq!(unpad(&[0xaa; 8], b""), Ok(vec![]));
+        assert_eq!(unpad(&[0xaa; 8], b"\x00m"), Ok(b"\x00m".to_vec()));
+        assert_eq!(unpad(&[0xaa; 7], b"mm"), Err(RsaError::InvalidPadding));
+
+        let mut padding = [0xaa; 9];
+        padding[7] = 0x00;
+        assert_eq!(unpad(&padding, b"m"), Err(RsaError::InvalidPadding));
+
+        let mut block = vec![0x01, 0x02];
+        block.extend([0xaa; 8]);
+        block.extend([0x00, b'm']);
+        let result = unpad_pkcs1_v15_encryption(&block, strict);
+        assert_eq!(result, Err(RsaError::InvalidPadding));
+        assert_eq!(
+            unpad_pkcs1_v15_encryption(&[], strict),
+            Err(RsaError::InvalidPadding)
+        );
+    }
+
+    /// Forges a signature for `e = 3` by taking the cube root of a block made of a valid
+    /// prefix followed by garbage
+    fn forge_signature(key: &RsaPublicKey, prefix: &[u8]) -> Vec<u8> {
+        let mut block = prefix.to_vec();
+        block.resize(key.size(), 0xff);
+        i2osp(&os2ip(&block).nth_root(3), key.size()).unwrap()
+    }
+
+    #[test]
+    fn pkcs1_v15_signature_strictness() {
+        // the garbage must span more than a third of the block for the cube root to keep the
+        // prefix
+        let mut rng = Mt19937::new(2);
+        let key = RsaPrivateKey::generate(&mut rng, 1536, &BigUint::from(3u64)).unwrap();
+        let public_key = key.public_key();
+        let message = b"hi mom";
+
+        let strict = Pkcs1Strictness::Strict;
+        let lenient = Pkcs1Strictness::SkipTrailingGarbage;
+        let header_only = Pkcs1Strictness::HeaderOnly;
+
+        let signature = pkcs1_v15_sign::<Sha256>(&key, message).unwrap();
+        for strictness in [strict, lenient, header_only] {
+            assert!(pkcs1_v15_verify::<Sha256>(
+                public_key, message, &signature, strictness
+            ));
+        }
+
+        // 00 01 FF 00 DigestInfo H(M) garbage
+        let mut prefix = vec![0x00, 0x01, 0xff, 0x00];
+        prefix.extend(Sha256::DIGEST_INFO_PREFIX);
+        prefix.extend(Sha256::digest(message));
+        let forgery = forge_signature(public_key, &prefix);
+        assert!(!pkcs1_v15_verify::<Sha256>(
+            public_key, message, &forgery, strict
+        ));
+        assert!(pkcs1_v15_verify::<Sha256>(
+            public_key, message, &forgery, lenient
+        ));
+        assert!(pkcs1_v15_verify::<Sha256>(
+            public_key,
+            message,
+            &forgery,
+            header_only
+        ));
+
+        // 00 01 00 DigestInfo H(M) garbage: no FF padding at all
+        let mut prefix = vec![0x00, 0x01, 0x00];
+        prefix.extend(Sha1::DIGEST_INFO_PREFIX);
+        prefix.extend(Sha1::digest(message));
+        let forgery = forge_signature(public_key, &prefix);
+        assert!(!pkcs1_v15_verify::<Sha1>(
+            public_key, message, &forgery, strict
+        ));
+        assert!(pkcs1_v15_verify::<Sha1>(
+            public_key, message, &forgery, lenient
+        ));
+        assert!(pkcs1_v15_verify::<Sha1>(
+            public_key,
+            message,
+            &forgery,
+            header_only
+        ));
+
+        // 00 01 AB 00 DigestInfo H(M) garbage: padding bytes not FF
+        let mut prefix = vec![0x00, 0x01, 0xab, 0x00];
+        prefix.extend(Sha1::DIGEST_INFO_PREFIX);
+        prefix.extend(Sha1::digest(message));
+        let forgery = forge_signature(public_key, &prefix);
+        assert!(!pkcs1_v15_verify::<Sha1>(
+            public_key, message, &forgery, lenient
+        ));
+        assert!(pkcs1_v15_verify::<Sha1>(
+            public_key,
+            message,
+            &forgery,
+            header_only
+        ));
+    }
+
+    #[test]
+    fn pkcs1_v15_signature_key_too_small() {
+        let key = RsaPrivateKey::generate(&mut Mt19937::new(3), 256, &BigUint::from(3u64)).unwrap();
+        assert_eq!(
+            pkcs1_v15_sign::<Sha256>(&key, b"message"),
+            Err(RsaError::MessageTooLong)
+        );
+        assert!(!pkcs1_v15_verify::<Sha256>(
+            key.public_key(),
+            b"message",
+            &[0; 32],
+            Pkcs1Strictness::HeaderOnly
+        ));
+    }
+}
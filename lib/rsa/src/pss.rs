@@ -0,0 +1,262 @@
+use primitives::{ct_eq, ct_is_zero, xor, HashFunction, Rng};
+
+use crate::{i2osp, mgf1, os2ip, RsaError, RsaPrivateKey, RsaPublicKey};
+
+/// The last byte of every PSS encoded message
+const TRAILER: u8 = 0xbc;
+
+/// Computes `H(00 00 00 00 00 00 00 00 || H(M) || salt)`
+fn pss_hash<H: HashFunction>(message: &[u8], salt: &[u8]) -> Vec<u8> {
+    let mut hash = H::default();
+    hash.update(&[0; 8]);
+    hash.update(&H::digest(message));
+    hash.update(salt);
+    hash.finalize()
+}
+
+/// Encodes a message with EMSA-PSS (RFC 8017 9.1.1): `maskedDB || H || BC` with
+/// `DB = 00...00 || 01 || salt`
+///
+/// # Args
+/// `message`: the message to sign
+///
+/// `em_bits`: the maximal bit length of the encoded message, `modBits - 1` for signatures
+///
+/// `salt_length`: the length of the random salt, usually the digest size
+///
+/// `rng`: the source of the salt
+///
+/// # Returns
+/// A `Result` wrapping the `ceil(em_bits / 8)` bytes encoded message or
+/// `RsaError::MessageTooLong` if it cannot hold the digest and salt
+pub fn encode_pss<H: HashFunction, R: Rng>(
+    message: &[u8],
+    em_bits: usize,
+    salt_length: usize,
+    rng: &mut R,
+) -> Result<Vec<u8>, RsaError> {
+    let h_len = H::OUTPUT_SIZE;
+    let em_len = em_bits.div_ceil(8);
+    if em_len < h_len + salt_length + 2 {
+        return Err(RsaError::MessageTooLong);
+    }
+
+    let mut salt = vec![0; salt_length];
+    rng.fill_bytes(&mut salt);
+    let hash = pss_hash::<H>(message, &salt);
+
+    let mut db = vec![0x00; em_len - salt_length - h_len - 2];
+    db.push(0x01);
+    db.extend(&salt);
+
+    let mut encoded = xor(&db, &mgf1::<H>(&hash, db.len()));
+    encoded[0] &= 0xff >> (8 * em_len - em_bits);
+    encoded.extend(hash);
+    encoded.push(TRAILER);
+    Ok(encoded)
+}
+
+/// Checks that an EMSA-PSS encoded message matches a message (RFC 8017 9.1.2)
+///
+/// # Args
+/// `encoded`: the encoded message, `ceil(em_bits / 8)` bytes long
+///
+/// `message`: the signed message
+///
+/// `em_bits`: the maximal bit length of the encoded message, `modBits - 1` for signatures
+///
+/// `salt_length`: the expected length of the salt
+pub fn check_pss<H: HashFunction>(
+    encoded: &[u8],
+    message: &[u8],
+    em_bits: usize,
+    salt_length: usize,
+) -> bool {
+    let h_len = H::OUTPUT_SIZE;
+    let em_len = em_bits.div_ceil(8);
+    if encoded.len() != em_len || em_len < h_len + salt_length + 2 {
+        return false;
+    }
+
+    let top_bits = 8 * em_len - em_bits;
+    if encoded[em_len - 1] != TRAILER || (top_bits > 0 && encoded[0] >> (8 - top_bits) != 0) {
+        return false;
+    }
+
+    let (masked_db, hash) = encoded[..em_len - 1].split_at(em_len - h_len - 1);
+    let mut db = xor(masked_db, &mgf1::<H>(hash, masked_db.len()));
+    db[0] &= 0xff >> top_bits;
+
+    let (padding, salt) = db.split_at(db.len() - salt_length);
+    let (zeros, separator) = padding.split_at(padding.len() - 1);
+    if !ct_is_zero(zeros) || separator[0] != 0x01 {
+        return false;
+    }
+
+    ct_eq(hash, &pss_hash::<H>(message, salt))
+}
+
+/// Signs a message with RSASSA-PSS
+///
+/// # Args
+/// `key`: the private key of the signer
+///
+/// `message`: the message to sign
+///
+/// `salt_length`: the length of the random salt, usually the digest size
+///
+/// `rng`: the source of the salt
+///
+/// # Returns
+/// A `Result` wrapping the `k` bytes signature or `RsaError::MessageTooLong` if the modulus is
+/// too small for the digest and salt
+///
+/// # Examples
+/// ```
+/// use primitives::{BigUint, OsRng, Sha256};
+/// use rsa::{pss_sign, pss_verify, RsaPrivateKey, DEFAULT_PUBLIC_EXPONENT};
+///
+/// let e = BigUint::from(DEFAULT_PUBLIC_EXPONENT);
+/// let key = RsaPrivateKey::generate(&mut OsRng, 1024, &e).unwrap();
+///
+/// let signature = pss_sign::<Sha256, _>(&key, b"hi", 32, &mut OsRng).unwrap();
+/// assert!(pss_verify::<Sha256>(key.public_key(), b"hi", &signature, 32));
+/// ```
+pub fn pss_sign<H: HashFunction, R: Rng>(
+    key: &RsaPrivateKey,
+    message: &[u8],
+    salt_length: usize,
+    rng: &mut R,
+) -> Result<Vec<u8>, RsaError> {
+    let em_bits = key.public_key().n().bits() - 1;
+    let encoded = encode_pss::<H, R>(message, em_bits, salt_length, rng)?;
+    i2osp(&key.decrypt_crt(&os2ip(&encoded))?, key.size())
+}
+
+/// Verifies a RSASSA-PSS signature with the given salt length
+pub fn pss_verify<H: HashFunction>(
+    key: &RsaPublicKey,
+    message: &[u8],
+    signature: &[u8],
+    salt_length: usize,
+) -> bool {
+    if signature.len() != key.size() {
+        return false;
+    }
+
+    let em_bits = key.n().bits() - 1;
+    let encoded = key
+        .encrypt(&os2ip(signature))
+        .and_then(|m| i2osp(&m, em_bits.div_ceil(8)));
+
+    match encoded {
+        Ok(encoded) => check_pss::<H>(&encoded, message, em_bits, salt_length),
+        Err(_) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use encoding::Decode;
+    use primitives::{BigUint, Mt19937, Sha1, Sha256};
+
+    use crate::test_keys::reference_key;
+    use crate::{pkcs1_v15_sign, DEFAULT_PUBLIC_EXPONENT};
+
+    use super::*;
+
+    #[test]
+    fn pss_reference_signature() {
+        // a signature generated with the Python cryptography package
+        let key = reference_key();
+        let signature = Vec::from_hex(concat!(
+            "078f5bd9ef4263d3846c7b1cf29ed9bd97b3a93a68091fdf2d00eb75fb6096fe838b1bb7ca28c1eb1f",
+            "dbc1e6bee6f1a771b965b107e1a7dcf43e1039258ef62d8d68ece4716947f5bcb9ccf3d1ac3318607c",
+            "1660218ed2d2ac0b5d486a0ec2cb515245ee904a40b1bc37598352b82df04e2c42d5e850b34476925c",
+            "b590dfa0af"
+        ))
+        .unwrap();
+
+        let public_key = key.public_key();
+        assert!(pss_verify::<Sha256>(
+            public_key,
+            b"PSS test message",
+            &signature,
+            32
+        ));
+        assert!(!pss_verify::<Sha256>(
+            public_key,
+            b"PSS test massage",
+            &signature,
+            32
+        ));
+        assert!(!pss_verify::<Sha256>(
+            public_key,
+            b"PSS test message",
+            &signature,
+            20
+        ));
+        assert!(!pss_verify::<Sha1>(
+            public_key,
+            b"PSS test message",
+            &signature,
+            32
+        ));
+    }
+
+    #[test]
+    fn pss_round_trip() {
+        let mut rng = Mt19937::new(1);
+        let e = BigUint::from(DEFAULT_PUBLIC_EXPONENT);
+
+        // 1025 bits makes em_len one byte shorter than the modulus
+        for bits in [1024, 1025, 1031] {
+            let key = RsaPrivateKey::generate(&mut rng, bits, &e).unwrap();
+            for salt_length in [0, 20] {
+                let signature =
+                    pss_sign::<Sha1, _>(&key, b"message", salt_length, &mut rng).unwrap();
+                let public_key = key.public_key();
+                assert!(pss_verify::<Sha1>(
+                    public_key,
+                    b"message",
+                    &signature,
+                    salt_length
+                ));
+                assert!(!pss_verify::<Sha1>(
+                    public_key,
+                    b"massage",
+                    &signature,
+                    salt_length
+                ));
+            }
+        }
+
+        let key = RsaPrivateKey::generate(&mut rng, 512, &e).unwrap();
+        let result = pss_sign::<Sha256, _>(&key, b"message", 32, &mut rng);
+        assert_eq!(result, Err(RsaError::MessageTooLong));
+    }
+
+    #[test]
+    fn pss_rejects_forgeries() {
+        let mut rng = Mt19937::new(2);
+        let key = RsaPrivateKey::generate(&mut rng, 1024, &BigUint::from(3u64)).unwrap();
+        let public_key = key.public_key();
+
+        // a cube root with garbage only controls the top of the encoded message, while PSS
+        // checks every byte through the mask
+        let mut encoded = vec![0x00, 0x01, 0xff, 0x00];
+        encoded.resize(key.size(), 0xff);
+        let forgery = i2osp(&os2ip(&encoded).nth_root(3), key.size()).unwrap();
+        assert!(!pss_verify::<Sha1>(public_key, b"message", &forgery, 20));
+
+        let signature = pkcs1_v15_sign::<Sha1>(&key, b"message").unwrap();
+        assert!(!pss_verify::<Sha1>(public_key, b"message", &signature, 20));
+
+        let signature = pss_sign::<Sha1, _>(&key, b"message", 20, &mut rng).unwrap();
+        for i in [0, 64, 127] {
+            let mut corrupted = signature.clone();
+            corrupted[i] ^= 0x01;
+            assert!(!pss_verify::<Sha1>(public_key, b"message", &corrupted, 20));
+        }
+    }
+}